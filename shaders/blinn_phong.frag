@@ -0,0 +1,52 @@
+#version 330 core
+
+// Blinn-Phong fragment shader - runs once per pixel
+// Shared code comes in through #include, and HAS_NORMAL_MAP / SHADOWS are defined by the app per variant
+
+#include "lighting.glsl"
+#include "shadows.glsl"
+#include "normal_mapping.glsl"
+
+struct Material {
+    vec3 diffuse;     // Multiplies the vertex color
+    vec3 specular;    // Highlight color
+    float shininess;  // Blinn-Phong exponent
+    vec3 emissive;    // Glow added after lighting
+};
+
+// Input from vertex shader (interpolated values)
+in vec3 v_normal;     // Surface normal (interpolated across triangle)
+in vec3 v_color;      // Vertex color (interpolated across triangle)
+in vec3 v_position;   // World position (interpolated across triangle)
+in vec2 v_uv;         // Texture coordinate
+
+uniform Material u_material;
+uniform sampler2D u_base_color_map;   // sRGB, linearized by the sampler (white when unset)
+
+// Output - final pixel color
+out vec4 frag_color;  // RGBA color (red, green, blue, alpha)
+
+void main() {
+    vec3 normal = surface_normal(v_normal, v_uv);                   // Interpolated normal, bent by the normal map
+    vec3 view_dir = normalize(u_camera_position - v_position);      // Toward the eye
+    // This path works in display space, so re-encode the linearized texel
+    vec3 texel = pow(texture(u_base_color_map, v_uv).rgb, vec3(1.0 / 2.2));
+    vec3 base = v_color * u_material.diffuse * texel;
+
+    vec3 color = base * u_ambient + u_material.emissive;
+    for (int i = 0; i < u_light_count; i++) {
+        vec3 light_dir;
+        float strength = light_incidence(u_lights[i], v_position, light_dir);
+        strength *= light_shadow(i, v_position, normal, light_dir);
+
+        float diffuse = max(dot(normal, light_dir), 0.0);
+        // Blinn-Phong: highlight where the half vector lines up with the normal
+        vec3 half_dir = normalize(light_dir + view_dir);
+        float specular = diffuse > 0.0 ? pow(max(dot(normal, half_dir), 0.0), u_material.shininess) : 0.0;
+
+        color += u_lights[i].color * strength * (base * diffuse + u_material.specular * specular);
+    }
+
+    // Back to linear for the post chain, which applies exposure, tone mapping and gamma
+    frag_color = vec4(pow(max(color, 0.0), vec3(2.2)), 1.0);  // Alpha = 1.0 (fully opaque)
+}
@@ -0,0 +1,9 @@
+// Camera block shared by every lit program - one std140 uniform buffer, written once per frame
+// Member order and types must match camera_block.rs
+
+layout(std140) uniform Camera {
+    mat4 u_projection;         // 3D to 2D projection matrix
+    mat4 u_view;               // Camera/view transformation
+    vec3 u_camera_position;    // Eye in world space
+    vec3 u_camera_forward;     // View direction, to measure depth for cascade selection
+};
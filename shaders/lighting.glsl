@@ -0,0 +1,45 @@
+// Light struct, uniforms and falloff shared by the lit fragment shaders
+// kind 0 = directional, 1 = point, 2 = spot
+
+#include "camera.glsl"
+
+#define MAX_LIGHTS 8
+
+struct Light {
+    int kind;          // 0 directional, 1 point, 2 spot
+    vec3 color;        // Already multiplied by intensity
+    vec3 position;     // World space (point, spot)
+    vec3 direction;    // Normalized, the way the light travels (directional, spot)
+    float range;       // Fade-out distance (point, spot)
+    float inner_cos;   // Spot cone: full strength above this cosine
+    float outer_cos;   // Spot cone: dark below this cosine
+};
+
+uniform vec3 u_ambient;               // Light reaching every surface
+uniform Light u_lights[MAX_LIGHTS];
+uniform int u_light_count;            // Number of valid entries in u_lights
+
+// Smooth falloff that reaches exactly zero at the light's range
+float attenuation(float distance, float range) {
+    float ratio = distance / range;
+    float window = clamp(1.0 - ratio * ratio * ratio * ratio, 0.0, 1.0);
+    return window * window / (distance * distance + 1.0);
+}
+
+// Direction toward the light from `position`, and how much of it arrives there (0..1)
+float light_incidence(Light light, vec3 position, out vec3 light_dir) {
+    if (light.kind == 0) {
+        light_dir = -light.direction;
+        return 1.0;
+    }
+    vec3 offset = light.position - position;
+    float distance = length(offset);
+    light_dir = offset / max(distance, 1e-4);
+    float strength = attenuation(distance, light.range);
+    if (light.kind == 2) {
+        // Fade between the inner and outer cone
+        float angle_cos = dot(-light_dir, light.direction);
+        strength *= smoothstep(light.outer_cos, max(light.inner_cos, light.outer_cos + 1e-4), angle_cos);
+    }
+    return strength;
+}
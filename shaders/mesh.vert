@@ -0,0 +1,34 @@
+#version 330 core                     // OpenGL 3.3 core profile
+
+// Input vertex attributes (from our VBO)
+layout(location = 0) in vec3 position; // Vertex position (x, y, z)
+layout(location = 1) in vec3 normal;   // Surface normal vector
+layout(location = 2) in vec3 color;    // Vertex color (r, g, b)
+layout(location = 3) in vec2 uv;       // Texture coordinate (0, 0 when the mesh has none)
+layout(location = 4) in vec4 tangent;  // xyz along +U, w = bitangent sign (zero when the mesh has none)
+
+// Projection and view come from the shared camera block, the model matrix is per draw
+#include "camera.glsl"
+uniform mat4 u_model;                   // Object transformation (rotation, etc.)
+
+// Output to fragment shader (interpolated across triangle)
+out vec3 v_normal;       // Normal in world space
+out vec3 v_color;        // Color to be interpolated
+out vec3 v_position;     // Position in world space
+out vec2 v_uv;           // Texture coordinate
+out vec4 v_tangent;      // Tangent in world space, w passed through
+
+void main() {
+    // Transform vertex position to world space
+    vec4 world_pos = u_model * vec4(position, 1.0);  // 1.0 = homogeneous coordinate
+    v_position = world_pos.xyz;  // Pass to fragment shader
+
+    // Transform normal to world space (special matrix for normals)
+    v_normal = mat3(transpose(inverse(u_model))) * normal;
+    v_color = color;  // Pass color through unchanged
+    v_uv = uv;
+    v_tangent = vec4(mat3(u_model) * tangent.xyz, tangent.w);  // Tangents follow the surface, no inverse-transpose
+
+    // Final vertex position in clip space (required output)
+    gl_Position = u_projection * u_view * world_pos;  // MVP transformation
+}
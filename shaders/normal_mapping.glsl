@@ -0,0 +1,23 @@
+// Tangent-space normal mapping shared by the lit fragment shaders
+// Follows the MikkTSpace reference: unnormalized interpolated vectors, bitangent rebuilt per pixel
+// Only compiled in with HAS_NORMAL_MAP defined - the program for objects without one skips the lookup
+
+#ifdef HAS_NORMAL_MAP
+in vec4 v_tangent;
+
+uniform sampler2D u_normal_map;   // Linear, tangent-space
+
+// Shading normal at `uv`: the geometric normal, bent by the normal map when the mesh has tangents
+vec3 surface_normal(vec3 normal, vec2 uv) {
+    if (dot(v_tangent.xyz, v_tangent.xyz) < 1e-12) {
+        return normalize(normal);
+    }
+    vec3 bitangent = v_tangent.w * cross(normal, v_tangent.xyz);
+    vec3 texel = texture(u_normal_map, uv).xyz * 2.0 - 1.0;
+    return normalize(texel.x * v_tangent.xyz + texel.y * bitangent + texel.z * normal);
+}
+#else
+vec3 surface_normal(vec3 normal, vec2 uv) {
+    return normalize(normal);
+}
+#endif
@@ -0,0 +1,128 @@
+#version 330 core
+
+// glTF metallic-roughness fragment shader (Cook-Torrance GGX)
+// Shared code comes in through #include, and HAS_NORMAL_MAP / SHADOWS are defined by the app per variant
+// Output is linear HDR - the post chain tone maps and gamma encodes it
+
+#include "lighting.glsl"
+#include "shadows.glsl"
+#include "normal_mapping.glsl"
+
+const float PI = 3.14159265359;
+
+struct PbrMaterial {
+    vec3 base_color;    // Multiplies the vertex color (glTF base color factor is already in there)
+    float metallic;     // 0 = dielectric, 1 = metal
+    float roughness;    // 0 = mirror, 1 = fully rough
+    float occlusion;    // Ambient occlusion, 1 = unoccluded
+    vec3 emissive;      // Linear emitted radiance
+};
+
+in vec3 v_normal;
+in vec3 v_color;
+in vec3 v_position;
+in vec2 v_uv;
+
+uniform PbrMaterial u_pbr;
+
+// Material textures (texture.rs) - unset slots are white, leaving the factors unchanged
+// (the normal map is declared with surface_normal in normal_mapping.glsl)
+uniform sampler2D u_base_color_map;          // sRGB
+uniform sampler2D u_metallic_roughness_map;  // G = roughness, B = metallic
+uniform sampler2D u_occlusion_map;           // R
+uniform sampler2D u_emissive_map;            // sRGB
+
+// Image-based lighting (environment.rs) - replaces the flat ambient when a map is loaded
+uniform bool u_has_environment;
+uniform samplerCube u_irradiance_map;   // Diffuse, looked up by normal
+uniform samplerCube u_prefiltered_map;  // Specular, looked up by reflection, mip = roughness
+uniform sampler2D u_brdf_lut;           // Split-sum scale/bias by (n.v, roughness)
+uniform mat3 u_environment_rotation;
+uniform float u_environment_intensity;
+uniform float u_prefiltered_max_lod;
+
+out vec4 frag_color;
+
+// GGX / Trowbridge-Reitz normal distribution
+float distribution_ggx(float n_dot_h, float alpha) {
+    float a2 = alpha * alpha;
+    float d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+// Smith height-correlated visibility (geometry term divided by 4 N.L N.V)
+float visibility_smith(float n_dot_l, float n_dot_v, float alpha) {
+    float a2 = alpha * alpha;
+    float ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - a2) + a2);
+    float ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - a2) + a2);
+    float ggx = ggx_v + ggx_l;
+    return ggx > 0.0 ? 0.5 / ggx : 0.0;
+}
+
+// Schlick's Fresnel approximation
+vec3 fresnel_schlick(float cos_theta, vec3 f0) {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// Fresnel averaged over a rough lobe - rough surfaces don't reach full reflectance at grazing angles
+vec3 fresnel_schlick_roughness(float cos_theta, vec3 f0, float roughness) {
+    return f0 + (max(vec3(1.0 - roughness), f0) - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+void main() {
+    vec3 normal = surface_normal(v_normal, v_uv);
+    vec3 view_dir = normalize(u_camera_position - v_position);
+    float n_dot_v = max(dot(normal, view_dir), 1e-4);
+
+    vec3 base = v_color * u_pbr.base_color * texture(u_base_color_map, v_uv).rgb;
+    vec3 metallic_roughness = texture(u_metallic_roughness_map, v_uv).rgb;
+    float metallic = clamp(u_pbr.metallic * metallic_roughness.b, 0.0, 1.0);
+    float roughness = clamp(u_pbr.roughness * metallic_roughness.g, 0.04, 1.0);  // Perfect mirrors alias badly
+    float occlusion = u_pbr.occlusion * texture(u_occlusion_map, v_uv).r;
+    float alpha = roughness * roughness;                  // glTF roughness is perceptual
+
+    // Dielectrics reflect 4% head-on, metals tint the reflection with their base color
+    vec3 f0 = mix(vec3(0.04), base, metallic);
+    vec3 diffuse_color = base * (1.0 - metallic);
+
+    vec3 color = vec3(0.0);
+    for (int i = 0; i < u_light_count; i++) {
+        vec3 light_dir;
+        float strength = light_incidence(u_lights[i], v_position, light_dir);
+        strength *= light_shadow(i, v_position, normal, light_dir);
+        float n_dot_l = max(dot(normal, light_dir), 0.0);
+        if (n_dot_l <= 0.0 || strength <= 0.0) {
+            continue;
+        }
+
+        vec3 half_dir = normalize(light_dir + view_dir);
+        float n_dot_h = max(dot(normal, half_dir), 0.0);
+        vec3 fresnel = fresnel_schlick(max(dot(half_dir, view_dir), 0.0), f0);
+
+        vec3 specular = fresnel * distribution_ggx(n_dot_h, alpha) * visibility_smith(n_dot_l, n_dot_v, alpha);
+        vec3 diffuse = (1.0 - fresnel) * diffuse_color / PI;
+
+        // x PI so intensity 1 lights a white surface as brightly as the Blinn-Phong path
+        vec3 radiance = u_lights[i].color * strength * PI;
+        color += (diffuse + specular) * radiance * n_dot_l;
+    }
+
+    if (u_has_environment) {
+        // Split-sum IBL: prefiltered radiance times the BRDF's scale and bias to F0
+        vec3 fresnel = fresnel_schlick_roughness(n_dot_v, f0, roughness);
+        vec3 irradiance = texture(u_irradiance_map, u_environment_rotation * normal).rgb;
+        vec3 reflected = u_environment_rotation * reflect(-view_dir, normal);
+        vec3 radiance = textureLod(u_prefiltered_map, reflected, roughness * u_prefiltered_max_lod).rgb;
+        vec2 brdf = texture(u_brdf_lut, vec2(n_dot_v, roughness)).rg;
+        vec3 diffuse = (1.0 - fresnel) * diffuse_color * irradiance;
+        vec3 specular = radiance * (fresnel * brdf.x + brdf.y);
+        color += (diffuse + specular) * u_environment_intensity * occlusion;
+    } else {
+        // Flat ambient stands in for environment lighting: diffuse plus the view-angle reflection
+        vec3 ambient_fresnel = fresnel_schlick(n_dot_v, f0);
+        color += u_ambient * ((1.0 - ambient_fresnel) * diffuse_color + ambient_fresnel * (1.0 - roughness)) * occlusion;
+    }
+    color += u_pbr.emissive * texture(u_emissive_map, v_uv).rgb;
+
+    frag_color = vec4(color, 1.0);  // Linear - gamma is the post chain's last pass
+}
@@ -0,0 +1,59 @@
+// Shadow lookup with PCF for the lit fragment shaders (include after lighting.glsl)
+// Only compiled in with SHADOWS defined, for frames that have a shadow caster
+
+#define MAX_CASCADES 4
+
+#ifdef SHADOWS
+uniform int u_shadow_light;           // Index in u_lights that casts shadows, -1 = none
+uniform sampler2DArray u_shadow_map;  // One depth layer per cascade
+uniform mat4 u_shadow_matrices[MAX_CASCADES];    // World -> light clip space
+uniform float u_cascade_ends[MAX_CASCADES];      // View depth where each cascade stops
+uniform float u_shadow_texel_sizes[MAX_CASCADES]; // World size of one shadow texel
+uniform int u_cascade_count;
+uniform float u_shadow_bias;          // Depth offset against acne
+uniform float u_shadow_normal_bias;   // Offset along the normal, in texels
+uniform int u_pcf_radius;             // Filter kernel is (2r + 1)^2 taps
+
+// Fraction of light `light` that reaches `position` (1 = fully lit)
+float light_shadow(int light, vec3 position, vec3 normal, vec3 light_dir) {
+    if (light != u_shadow_light) {
+        return 1.0;
+    }
+
+    float depth = dot(position - u_camera_position, u_camera_forward);
+    int cascade = u_cascade_count - 1;
+    for (int c = 0; c < u_cascade_count; c++) {
+        if (depth < u_cascade_ends[c]) {
+            cascade = c;
+            break;
+        }
+    }
+
+    // Look up slightly off the surface, more at grazing angles where acne is worst
+    float grazing = 1.0 - max(dot(normal, light_dir), 0.0);
+    vec3 offset = normal * u_shadow_normal_bias * u_shadow_texel_sizes[cascade] * grazing;
+    vec4 clip = u_shadow_matrices[cascade] * vec4(position + offset, 1.0);
+    vec3 coord = clip.xyz / clip.w * 0.5 + 0.5;
+    if (coord.z > 1.0) {
+        return 1.0;  // Beyond the light's far plane
+    }
+
+    // Percentage-closer filtering: average the depth test over neighboring texels
+    vec2 texel = 1.0 / vec2(textureSize(u_shadow_map, 0).xy);
+    float lit = 0.0;
+    float taps = 0.0;
+    for (int x = -u_pcf_radius; x <= u_pcf_radius; x++) {
+        for (int y = -u_pcf_radius; y <= u_pcf_radius; y++) {
+            float closest = texture(u_shadow_map, vec3(coord.xy + vec2(x, y) * texel, float(cascade))).r;
+            lit += coord.z - u_shadow_bias > closest ? 0.0 : 1.0;
+            taps += 1.0;
+        }
+    }
+    return lit / taps;
+}
+#else
+// Built without shadows - every light reaches every surface
+float light_shadow(int light, vec3 position, vec3 normal, vec3 light_dir) {
+    return 1.0;
+}
+#endif
@@ -1,6 +1,125 @@
 // Import PathBuf - a owned, growable file system path (like String but for paths)
 use std::path::PathBuf;
 
+use crate::renderer::{Camera, CameraMode, Projection, ShadingModel, ViewPreset, ViewShading};  // Viewport cameras live here so they survive between frames
+use nalgebra_glm as glm;  // Fly direction vector
+
+/// Fly-mode movement keys currently held down (fed from window events in main.rs)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlyKeys {
+    pub forward: bool,  // W
+    pub back: bool,     // S
+    pub left: bool,     // A
+    pub right: bool,    // D
+    pub up: bool,       // E
+    pub down: bool,     // Q
+    pub fast: bool,     // Shift
+}
+
+/// How the central panel is split into viewports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewportLayout {
+    #[default]
+    Single,
+    SideBySide,
+    Quad,  // 2x2
+}
+
+impl ViewportLayout {
+    /// Every layout, in menu order
+    pub const ALL: [ViewportLayout; 3] = [ViewportLayout::Single, ViewportLayout::SideBySide, ViewportLayout::Quad];
+
+    /// Display name for menus
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewportLayout::Single => "Single",
+            ViewportLayout::SideBySide => "Side by Side",
+            ViewportLayout::Quad => "Quad",
+        }
+    }
+
+    /// Number of viewports shown
+    pub fn count(self) -> usize {
+        match self {
+            ViewportLayout::Single => 1,
+            ViewportLayout::SideBySide => 2,
+            ViewportLayout::Quad => 4,
+        }
+    }
+}
+
+/// One view of the shared scene
+#[derive(Debug, Clone)]
+pub struct Viewport {
+    pub camera: Camera,         // Own camera, including its projection
+    pub shading: ViewShading,   // How this view draws the scene
+    pub texture: Option<(egui::TextureId, glow::Texture)>,  // Its offscreen target as registered with egui
+}
+
+impl Viewport {
+    fn new(camera: Camera) -> Self {
+        Self { camera, shading: ViewShading::default(), texture: None }
+    }
+}
+
+/// Which edit the gizmo handles perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+/// Orientation of the gizmo handles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoSpace {
+    World,  // Aligned with the world axes
+    Local,  // Aligned with the object's own rotation (scale always uses these)
+}
+
+/// Transform gizmo settings - ui::gizmo draws the handles and tracks the drag
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gizmo {
+    pub mode: GizmoMode,
+    pub space: GizmoSpace,
+    pub snap: bool,            // Round edits to the steps below
+    pub translate_step: f32,   // World units
+    pub rotate_step: f32,      // Degrees
+    pub scale_step: f32,       // Scale factor increment
+}
+
+impl Default for Gizmo {
+    fn default() -> Self {
+        Self {
+            mode: GizmoMode::Translate,
+            space: GizmoSpace::World,
+            snap: false,
+            translate_step: 0.25,
+            rotate_step: 15.0,
+            scale_step: 0.1,
+        }
+    }
+}
+
+/// The object picked in the viewport
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    pub index: usize,  // Position in the renderer's scene
+    pub name: String,  // Copied for the status bar
+}
+
+impl FlyKeys {
+    /// Camera-relative move direction (x = right, y = up, z = forward); opposite keys cancel
+    pub fn direction(&self) -> glm::Vec3 {
+        // bool as i32 = 0 or 1
+        glm::vec3(
+            self.right as i32 as f32 - self.left as i32 as f32,
+            self.up as i32 as f32 - self.down as i32 as f32,
+            self.forward as i32 as f32 - self.back as i32 as f32,
+        )
+    }
+}
+
 /// Application state management - holds all our app's runtime data
 pub struct AppState {
     // pub = public field, accessible from other modules
@@ -10,6 +129,22 @@ pub struct AppState {
     pub status_text: String,           // Text to show in status bar (String = owned string)
     pub frame_count: u64,              // Current animation frame (u64 = unsigned 64-bit int)
     pub current_file: Option<PathBuf>, // Currently opened file (Option = maybe has a file)
+    pub editing_primitive: Option<usize>, // Scene object shown in the primitive parameter panel
+    pub viewports: Vec<Viewport>,      // Every viewport a layout can show - the first `layout.count()` are visible
+    pub active_viewport: usize,        // The highlighted one: takes mouse and keyboard input, toolbar camera buttons act on it
+    pub layout: ViewportLayout,        // Single, side by side or quad
+    pub viewport_focused: bool,        // Did the active viewport have keyboard focus last frame?
+    pub fly_keys: FlyKeys,             // Held movement keys for fly mode
+    pub frame_all_requested: bool,     // Flag to fit the camera to the scene on the next viewport frame
+    pub frame_all_quiet: bool,         // ...without replacing the status text (it holds a load summary)
+    pub selection: Option<Selection>,  // Object clicked in the viewport (None = nothing selected)
+    pub gizmo: Gizmo,                  // Move/rotate/scale handle settings for the selection
+    pub show_lights_panel: bool,       // Is the Lights window open?
+    pub show_shadow_map: bool,         // Is the shadow map debug window open?
+    pub normal_mapping: bool,          // Draw with normal maps (off = bare geometry, for comparison)
+    pub show_shader_inspector: bool,   // Is the Shader Parameters window open?
+    pub show_post_panel: bool,         // Is the Post Processing window open?
+    pub inspector_shading: ShadingModel,  // Program shown in it
 }
 
 // Implementation block - contains methods for AppState
@@ -23,6 +158,28 @@ impl AppState {
             status_text: String::from("Ready"),        // String::from = convert &str to String
             frame_count: 0,                            // Start at frame 0
             current_file: None,                        // No file loaded initially
+            editing_primitive: None,                   // Parameter panel closed
+            // Quad view: the usual perspective plus orthographic top, front and right
+            viewports: vec![
+                Viewport::new(Camera::default()),      // Looking at the origin from (2, 2, 2)
+                Viewport::new(Camera::looking_along(ViewPreset::Top, Projection::Orthographic)),
+                Viewport::new(Camera::looking_along(ViewPreset::Front, Projection::Orthographic)),
+                Viewport::new(Camera::looking_along(ViewPreset::Right, Projection::Orthographic)),
+            ],
+            active_viewport: 0,
+            layout: ViewportLayout::Single,
+            viewport_focused: false,                   // Focus comes with the first click in the viewport
+            fly_keys: FlyKeys::default(),              // Nothing held
+            frame_all_requested: false,                // Keep the default view until asked
+            frame_all_quiet: false,
+            selection: None,                           // Nothing picked yet
+            gizmo: Gizmo::default(),                   // Translate in world space, no snapping
+            show_lights_panel: false,                  // Opened from the toolbar or View menu
+            show_shadow_map: false,                    // Opened from the Lights panel
+            normal_mapping: true,                      // Toolbar toggle / N key
+            show_shader_inspector: false,              // Opened from the View menu
+            show_post_panel: false,                    // Same
+            inspector_shading: ShadingModel::BlinnPhong,  // What the demo cube uses
         }
     }
 
@@ -47,6 +204,73 @@ impl AppState {
         self.playing = false;      // And stop playing
     }
 
+    // The active viewport's camera - what the toolbar, menus and shortcuts act on
+    pub fn camera(&self) -> &Camera {
+        &self.viewports[self.active_viewport].camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.viewports[self.active_viewport].camera
+    }
+
+    // Switch the layout, keeping the active viewport on screen
+    pub fn set_layout(&mut self, layout: ViewportLayout) {
+        self.layout = layout;
+        if self.active_viewport >= layout.count() {
+            self.set_active_viewport(0);
+        }
+        self.status_text = format!("{} layout", layout.name());
+    }
+
+    // Give another viewport the input (held fly keys belong to the old one)
+    pub fn set_active_viewport(&mut self, index: usize) {
+        if index != self.active_viewport {
+            self.active_viewport = index;
+            self.fly_keys = FlyKeys::default();
+        }
+    }
+
+    // Should movement keys drive the fly camera instead of the global shortcuts?
+    pub fn fly_keys_captured(&self) -> bool {
+        self.camera().mode == CameraMode::Fly && self.viewport_focused
+    }
+
+    // Switch between orbit and fly camera controls
+    pub fn toggle_camera_mode(&mut self) {
+        let camera = self.camera_mut();
+        camera.mode = match camera.mode {
+            CameraMode::Orbit => CameraMode::Fly,
+            CameraMode::Fly => CameraMode::Orbit,
+        };
+        camera.stop();                       // Don't carry an orbit glide into fly mode
+        self.fly_keys = FlyKeys::default();  // Release anything held in the old mode
+        self.status_text = format!("{:?} camera", self.camera().mode);
+    }
+
+    // Animate the camera to a canonical view (front, top, isometric...)
+    pub fn set_view(&mut self, preset: ViewPreset) {
+        self.camera_mut().animate_to(preset);
+        self.status_text = format!("{} view", preset.name());
+    }
+
+    // Switch between perspective and orthographic projection
+    pub fn toggle_projection(&mut self) {
+        self.camera_mut().toggle_projection();
+        self.status_text = format!("{:?} projection", self.camera().projection);
+    }
+
+    // Fit the camera to the whole scene on the next frame (the viewport knows its aspect ratio)
+    pub fn frame_all(&mut self) {
+        self.frame_all_requested = true;
+        self.frame_all_quiet = false;
+    }
+
+    // Frame all, keeping the current status message (a freshly loaded model's summary)
+    pub fn frame_all_quietly(&mut self) {
+        self.frame_all_requested = true;
+        self.frame_all_quiet = true;
+    }
+
     // Update mouse position from UI events
     pub fn update_mouse(&mut self, x: f32, y: f32) {
         self.mouse_pos = (x, y);  // Store as tuple
@@ -64,14 +288,21 @@ impl AppState {
         } else {
             String::new()  // Empty string if no file
         };
+
+        // Selected object, if any
+        let selection_info = match &self.selection {
+            Some(selection) => format!(" | Selected: {}", selection.name),
+            None => String::new(),
+        };
         
         // format! macro - like printf but type-safe!
         format!(
-            "Mouse: ({:.1}, {:.1}) | Frame: {}{}{}",  // {:.1} = float with 1 decimal place
+            "Mouse: ({:.1}, {:.1}) | Frame: {}{}{}{}",  // {:.1} = float with 1 decimal place
             self.mouse_pos.0,          // Access tuple element 0 (x)
             self.mouse_pos.1,          // Access tuple element 1 (y)
             self.frame_count, 
             file_info,
+            selection_info,
             // Conditional expression using if-else
             if !self.status_text.is_empty() { 
                 format!(" | {}", self.status_text) 
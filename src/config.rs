@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};  // Derive macros for JSON serialization
 use std::fs;  // File system operations
 use std::path::PathBuf;  // Owned path type
 
+use crate::renderer::{Antialiasing, ShaderParameters};  // Viewport settings and values from the shader parameter inspector
+
 /// Main configuration structure - serializes to JSON
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
@@ -37,6 +39,12 @@ pub struct AppConfig {
     pub auto_play: bool,
     /// Animation speed multiplier
     pub animation_speed: f32,
+    /// Shader parameter inspector values, per program (missing in older config files)
+    #[serde(default)]
+    pub shader_parameters: ShaderParameters,
+    /// Viewport anti-aliasing (missing in older config files)
+    #[serde(default)]
+    pub antialiasing: Antialiasing,
 }
 
 impl Default for Config {
@@ -54,6 +62,8 @@ impl Default for Config {
                 last_file: None,            // No file loaded initially
                 auto_play: false,           // Start paused
                 animation_speed: 1.0,       // Normal speed
+                shader_parameters: ShaderParameters::new(),  // Shaders' own initializers
+                antialiasing: Antialiasing::default(),       // 4x MSAA
             },
         }
     }
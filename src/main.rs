@@ -4,6 +4,8 @@ mod config;     // Configuration persistence (config.rs)
 mod renderer;   // OpenGL rendering pipeline (renderer/mod.rs + submodules)
 mod ui;         // User interface components (ui/mod.rs + submodules)
 
+use renderer::ViewPreset;  // Numpad view shortcuts
+
 // External crate imports - like #include in C++ but safer!
 use clap::Parser;  // Command-line argument parsing with derive macros
 use glow::HasContext;  // Trait that provides OpenGL methods
@@ -19,7 +21,7 @@ use std::sync::Arc;  // Atomic Reference Counter - shared ownership for multi-th
 use winit::application::ApplicationHandler;  // Trait for handling window events
 use winit::dpi::LogicalSize;  // Device-independent size units
 use winit::event::{WindowEvent, ElementState};  // Enum of all possible window events
-use winit::keyboard::{KeyCode, PhysicalKey};  // Keyboard input handling
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};  // Keyboard input handling
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};  // Event loop management
 use winit::window::{Window, WindowAttributes};  // Window creation and properties
 use winit::dpi::PhysicalPosition;  // Physical position type for window positioning
@@ -35,6 +37,10 @@ struct Args {
     /// Window height
     #[arg(long, default_value_t = 720)]  // Only --height (no short flag to avoid conflict with --help)
     height: u32,
+
+    /// Time N draws with by-name uniform lookups and with cached locations, print the per-draw cost and exit
+    #[arg(long, value_name = "N")]
+    bench_draws: Option<usize>,
 }
 
 // Main application struct - holds all our OpenGL and UI state
@@ -51,6 +57,7 @@ struct App {
     renderer: Option<renderer::Renderer>,  // Our 3D cube renderer
     gl: Option<Arc<glow::Context>>,  // OpenGL function pointers (Arc = shared ownership)
     config: config::Config,  // Persistent configuration (always present)
+    modifiers: ModifiersState,  // Shift/Ctrl/Alt currently held (for Ctrl+Numpad views)
 }
 
 // Implement the ApplicationHandler trait - this is how we handle window events
@@ -92,8 +99,9 @@ impl ApplicationHandler for App {
                 // Closure (anonymous function) to pick the best OpenGL config
                 configs
                     .reduce(|accum, config| {  // Iterator reduce - like fold but simpler
-                        // Pick config with more MSAA samples (anti-aliasing)
-                        if config.num_samples() > accum.num_samples() {
+                        // Pick the config with the fewest MSAA samples - egui smooths its own shapes and the
+                        // viewport anti-aliases its offscreen target (View > Anti-aliasing), so more are wasted
+                        if config.num_samples() < accum.num_samples() {
                             config  // Return the better config
                         } else {
                             accum   // Keep the accumulator
@@ -178,7 +186,24 @@ impl ApplicationHandler for App {
 
         // Initialize our application state and 3D renderer
         let app_state = app::AppState::new();  // Create new app state with defaults
-        let renderer = renderer::Renderer::new(gl.clone());  // Create cube renderer
+        let mut renderer = renderer::Renderer::new(gl.clone());  // Create cube renderer
+        renderer.set_shader_parameters(self.config.app.shader_parameters.clone());  // Last session's tweaks
+        renderer.set_antialiasing(self.config.app.antialiasing);
+
+        // Recompile shaders when their files are saved - the watcher wakes the UI to pick them up
+        let repaint_ctx = self.egui_ctx.clone();
+        if let Err(e) = renderer.watch_shaders(move || repaint_ctx.request_repaint()) {
+            eprintln!("Shader hot-reload unavailable: {}", e);
+        }
+
+        // Benchmark mode: measure with the real driver, then quit before the first frame
+        if let Some(draws) = args.bench_draws {
+            match renderer.benchmark_draws(draws) {
+                Ok(cost) => println!("{}", cost.report()),
+                Err(e) => eprintln!("Benchmark failed: {}", e),
+            }
+            event_loop.exit();
+        }
 
         // Store everything in our App struct - moving ownership from local variables
         self.window = Some(window);          // Some() wraps the value in Option
@@ -213,8 +238,29 @@ impl ApplicationHandler for App {
                 event_loop.exit();  // Quit the application
             }
             WindowEvent::KeyboardInput { event: key_event, .. } => {  // Keyboard key pressed/released
+                let pressed = key_event.state == ElementState::Pressed;
+
+                // Fly mode owns the movement keys (presses and releases) while the viewport has focus
+                if app_state.fly_keys_captured() {
+                    let keys = &mut app_state.fly_keys;
+                    let held = match key_event.physical_key {
+                        PhysicalKey::Code(KeyCode::KeyW) => Some(&mut keys.forward),
+                        PhysicalKey::Code(KeyCode::KeyS) => Some(&mut keys.back),
+                        PhysicalKey::Code(KeyCode::KeyA) => Some(&mut keys.left),
+                        PhysicalKey::Code(KeyCode::KeyD) => Some(&mut keys.right),
+                        PhysicalKey::Code(KeyCode::KeyE) => Some(&mut keys.up),
+                        PhysicalKey::Code(KeyCode::KeyQ) => Some(&mut keys.down),
+                        PhysicalKey::Code(KeyCode::ShiftLeft | KeyCode::ShiftRight) => Some(&mut keys.fast),
+                        _ => None,  // Not a movement key - fall through to the shortcuts below
+                    };
+                    if let Some(held) = held {
+                        *held = pressed;
+                        return;
+                    }
+                }
+
                 // Only handle key press events (not releases)
-                if key_event.state == ElementState::Pressed {
+                if pressed {
                     match key_event.physical_key {  // Match on physical key codes
                         PhysicalKey::Code(KeyCode::Space) | PhysicalKey::Code(KeyCode::ArrowUp) => {
                             // Space or Up Arrow: Toggle play/pause
@@ -231,6 +277,35 @@ impl ApplicationHandler for App {
                             app_state.step();
                             window.request_redraw();
                         }
+                        PhysicalKey::Code(KeyCode::KeyF) => {
+                            // F: Frame all - fit the camera to the scene
+                            app_state.frame_all();
+                        }
+                        PhysicalKey::Code(KeyCode::KeyN) => {
+                            // N: Compare with and without normal maps
+                            app_state.normal_mapping = !app_state.normal_mapping;
+                            window.request_redraw();
+                        }
+                        PhysicalKey::Code(KeyCode::Numpad1) => {
+                            // Numpad 1: Front view (Ctrl: Back)
+                            app_state.set_view(if self.modifiers.control_key() { ViewPreset::Back } else { ViewPreset::Front });
+                        }
+                        PhysicalKey::Code(KeyCode::Numpad3) => {
+                            // Numpad 3: Right view (Ctrl: Left)
+                            app_state.set_view(if self.modifiers.control_key() { ViewPreset::Left } else { ViewPreset::Right });
+                        }
+                        PhysicalKey::Code(KeyCode::Numpad7) => {
+                            // Numpad 7: Top view (Ctrl: Bottom)
+                            app_state.set_view(if self.modifiers.control_key() { ViewPreset::Bottom } else { ViewPreset::Top });
+                        }
+                        PhysicalKey::Code(KeyCode::Numpad0) => {
+                            // Numpad 0: Isometric view
+                            app_state.set_view(ViewPreset::Isometric);
+                        }
+                        PhysicalKey::Code(KeyCode::Numpad5) => {
+                            // Numpad 5: Toggle perspective/orthographic
+                            app_state.toggle_projection();
+                        }
                         PhysicalKey::Code(KeyCode::Escape) => {
                             // Escape: Quit application
                             event_loop.exit();
@@ -251,6 +326,12 @@ impl ApplicationHandler for App {
                 self.config.update_window_size(size.width, size.height);
                 self.config.save();  // Persist immediately
             }
+            WindowEvent::ModifiersChanged(modifiers) => {  // Shift/Ctrl/Alt pressed or released
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::Focused(false) => {  // Window lost focus - we won't see key releases
+                app_state.fly_keys = app::FlyKeys::default();  // Don't keep flying with "stuck" keys
+            }
             WindowEvent::Moved(position) => {  // Window position changed
                 // Save new window position to config  
                 self.config.update_window_pos(position.x, position.y);
@@ -265,8 +346,18 @@ impl ApplicationHandler for App {
                 // Run egui for one frame - the closure builds the UI
                 let full_output = self.egui_ctx.run(raw_input, |ctx| {
                     // This closure is where we build our entire UI!
-                    ui::show_ui(ctx, app_state, renderer, size.width, size.height);
+                    ui::show_ui(ctx, app_state, renderer, painter, size.width, size.height);
                 });  // Returns what egui wants to draw
+
+                // Keep shader parameter tweaks in the config - once a drag ends, not every frame of it
+                if renderer.shader_parameters() != &self.config.app.shader_parameters && !self.egui_ctx.input(|i| i.pointer.any_down()) {
+                    self.config.app.shader_parameters = renderer.shader_parameters().clone();
+                    self.config.save();
+                }
+                if renderer.antialiasing() != self.config.app.antialiasing {
+                    self.config.app.antialiasing = renderer.antialiasing();
+                    self.config.save();
+                }
                 
                 // Handle platform-specific output (cursor changes, etc.)
                 egui_winit.handle_platform_output(window, full_output.platform_output);
@@ -280,7 +371,7 @@ impl ApplicationHandler for App {
                     gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);  // Clear both color and depth
                 }
                 
-                // Render egui (including 3D viewport via callbacks)
+                // Render egui (the 3D viewport is already in its offscreen texture)
                 painter.paint_primitives(
                     [size.width as u32, size.height as u32],  // Screen size as array
                     full_output.pixels_per_point,  // DPI scaling factor
@@ -400,6 +491,7 @@ fn main() {
         renderer: None,
         gl: None,
         config,                 // Store loaded configuration
+        modifiers: ModifiersState::empty(),  // Nothing held at startup
     };
     
     // Run the event loop - this takes ownership of app and never returns!
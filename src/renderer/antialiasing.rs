@@ -0,0 +1,206 @@
+// Viewport anti-aliasing - MSAA on the scene target, or an FXAA pass over the resolved image
+use glow::HasContext;  // Trait providing OpenGL function methods
+use serde::{Deserialize, Serialize};  // Saved in the config
+
+use super::framebuffer::ColorTarget;
+use super::uniforms::UniformLocations;
+
+/// How the viewport smooths its edges
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Antialiasing {
+    Off,
+    Msaa2,
+    #[default]
+    Msaa4,
+    Msaa8,
+    /// One full-screen pass that blurs along high-contrast edges - cheap, also smooths shader aliasing, slightly soft
+    Fxaa,
+}
+
+impl Antialiasing {
+    /// Every option, in menu order
+    pub const ALL: [Antialiasing; 5] = [Antialiasing::Off, Antialiasing::Msaa2, Antialiasing::Msaa4, Antialiasing::Msaa8, Antialiasing::Fxaa];
+
+    /// Display name for menus
+    pub fn name(self) -> &'static str {
+        match self {
+            Antialiasing::Off => "Off",
+            Antialiasing::Msaa2 => "MSAA 2x",
+            Antialiasing::Msaa4 => "MSAA 4x",
+            Antialiasing::Msaa8 => "MSAA 8x",
+            Antialiasing::Fxaa => "FXAA",
+        }
+    }
+
+    /// Samples per pixel in the scene target (1 = single-sampled)
+    pub fn samples(self) -> i32 {
+        match self {
+            Antialiasing::Off | Antialiasing::Fxaa => 1,
+            Antialiasing::Msaa2 => 2,
+            Antialiasing::Msaa4 => 4,
+            Antialiasing::Msaa8 => 8,
+        }
+    }
+}
+
+// FXAA 3.11 "quality" style: find the edge direction from the luma of the 3x3 neighbourhood, walk along
+// the edge to both ends, and resample across it by how far the pixel is from the nearer end
+// Input is display-ready (gamma) color with premultiplied alpha - filtering RGBA together keeps it valid
+pub const FXAA_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+uniform sampler2D u_source;  // Resolved scene, linear filtering
+uniform vec2 u_texel_size;   // 1 / size in pixels
+
+const float EDGE_THRESHOLD = 0.125;       // Local contrast needed, relative to the brightest neighbour
+const float EDGE_THRESHOLD_MIN = 0.0312;  // Ignore contrast in near-black areas
+const float SUBPIXEL_QUALITY = 0.75;      // How much single-pixel detail gets smoothed
+const int SEARCH_STEPS = 12;
+
+float luma(vec4 color) {
+    return dot(color.rgb, vec3(0.299, 0.587, 0.114));
+}
+
+float luma_at(vec2 uv) {
+    return luma(textureLod(u_source, uv, 0.0));
+}
+
+void main() {
+    vec2 uv = v_ndc * 0.5 + 0.5;
+    vec4 center = textureLod(u_source, uv, 0.0);
+    float luma_center = luma(center);
+    float luma_down = luma(textureLodOffset(u_source, uv, 0.0, ivec2(0, -1)));
+    float luma_up = luma(textureLodOffset(u_source, uv, 0.0, ivec2(0, 1)));
+    float luma_left = luma(textureLodOffset(u_source, uv, 0.0, ivec2(-1, 0)));
+    float luma_right = luma(textureLodOffset(u_source, uv, 0.0, ivec2(1, 0)));
+
+    float luma_min = min(luma_center, min(min(luma_down, luma_up), min(luma_left, luma_right)));
+    float luma_max = max(luma_center, max(max(luma_down, luma_up), max(luma_left, luma_right)));
+    float range = luma_max - luma_min;
+    if (range < max(EDGE_THRESHOLD_MIN, luma_max * EDGE_THRESHOLD)) {
+        frag_color = center;  // Flat area - leave it alone
+        return;
+    }
+
+    float luma_down_left = luma(textureLodOffset(u_source, uv, 0.0, ivec2(-1, -1)));
+    float luma_up_right = luma(textureLodOffset(u_source, uv, 0.0, ivec2(1, 1)));
+    float luma_up_left = luma(textureLodOffset(u_source, uv, 0.0, ivec2(-1, 1)));
+    float luma_down_right = luma(textureLodOffset(u_source, uv, 0.0, ivec2(1, -1)));
+
+    float luma_down_up = luma_down + luma_up;
+    float luma_left_right = luma_left + luma_right;
+    float luma_left_corners = luma_down_left + luma_up_left;
+    float luma_down_corners = luma_down_left + luma_down_right;
+    float luma_right_corners = luma_down_right + luma_up_right;
+    float luma_up_corners = luma_up_right + luma_up_left;
+
+    // Horizontal edge = the luma changes vertically
+    float edge_horizontal = abs(-2.0 * luma_left + luma_left_corners) + abs(-2.0 * luma_center + luma_down_up) * 2.0 + abs(-2.0 * luma_right + luma_right_corners);
+    float edge_vertical = abs(-2.0 * luma_up + luma_up_corners) + abs(-2.0 * luma_center + luma_left_right) * 2.0 + abs(-2.0 * luma_down + luma_down_corners);
+    bool is_horizontal = edge_horizontal >= edge_vertical;
+
+    // Which side of the pixel the edge is on
+    float luma1 = is_horizontal ? luma_down : luma_left;
+    float luma2 = is_horizontal ? luma_up : luma_right;
+    float gradient1 = luma1 - luma_center;
+    float gradient2 = luma2 - luma_center;
+    bool is1_steepest = abs(gradient1) >= abs(gradient2);
+    float gradient_scaled = 0.25 * max(abs(gradient1), abs(gradient2));
+
+    float step_length = is_horizontal ? u_texel_size.y : u_texel_size.x;
+    float luma_local_average;
+    if (is1_steepest) {
+        step_length = -step_length;
+        luma_local_average = 0.5 * (luma1 + luma_center);
+    } else {
+        luma_local_average = 0.5 * (luma2 + luma_center);
+    }
+
+    // Walk along the edge (half a pixel over, on it) until the luma leaves the edge's average at both ends
+    vec2 edge_uv = uv;
+    if (is_horizontal) {
+        edge_uv.y += step_length * 0.5;
+    } else {
+        edge_uv.x += step_length * 0.5;
+    }
+    vec2 offset = is_horizontal ? vec2(u_texel_size.x, 0.0) : vec2(0.0, u_texel_size.y);
+    vec2 uv1 = edge_uv - offset;
+    vec2 uv2 = edge_uv + offset;
+    float luma_end1 = luma_at(uv1) - luma_local_average;
+    float luma_end2 = luma_at(uv2) - luma_local_average;
+    bool reached1 = abs(luma_end1) >= gradient_scaled;
+    bool reached2 = abs(luma_end2) >= gradient_scaled;
+    for (int i = 1; i < SEARCH_STEPS && !(reached1 && reached2); i++) {
+        float stride = i < 5 ? 1.0 : (i < 8 ? 2.0 : 4.0);  // Longer strides further out
+        if (!reached1) {
+            uv1 -= offset * stride;
+            luma_end1 = luma_at(uv1) - luma_local_average;
+            reached1 = abs(luma_end1) >= gradient_scaled;
+        }
+        if (!reached2) {
+            uv2 += offset * stride;
+            luma_end2 = luma_at(uv2) - luma_local_average;
+            reached2 = abs(luma_end2) >= gradient_scaled;
+        }
+    }
+
+    // Closer to an end = more of the pixel is on the other side of the edge
+    float distance1 = is_horizontal ? (uv.x - uv1.x) : (uv.y - uv1.y);
+    float distance2 = is_horizontal ? (uv2.x - uv.x) : (uv2.y - uv.y);
+    bool nearer1 = distance1 < distance2;
+    float pixel_offset = 0.5 - min(distance1, distance2) / (distance1 + distance2);
+    bool center_darker = luma_center < luma_local_average;
+    bool correct_variation = ((nearer1 ? luma_end1 : luma_end2) < 0.0) != center_darker;
+    float final_offset = correct_variation ? pixel_offset : 0.0;
+
+    // Single-pixel features have no edge to walk - blend them by their contrast with the neighbourhood
+    float luma_average = (2.0 * (luma_down_up + luma_left_right) + luma_left_corners + luma_right_corners) / 12.0;
+    float subpixel = clamp(abs(luma_average - luma_center) / range, 0.0, 1.0);
+    subpixel = (-2.0 * subpixel + 3.0) * subpixel * subpixel;
+    final_offset = max(final_offset, subpixel * subpixel * SUBPIXEL_QUALITY);
+
+    vec2 final_uv = uv;
+    if (is_horizontal) {
+        final_uv.y += final_offset * step_length;
+    } else {
+        final_uv.x += final_offset * step_length;
+    }
+    frag_color = textureLod(u_source, final_uv, 0.0);
+}
+"#;
+
+/// Run the FXAA program from `source` (a texture the size of `target`) into `target`
+pub fn apply_fxaa(gl: &glow::Context, program: glow::Program, locations: &UniformLocations, vao: glow::VertexArray, source: glow::Texture, target: &ColorTarget) {
+    let (width, height) = target.size();
+    unsafe {
+        target.bind();
+        gl.use_program(Some(program));
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, Some(source));
+        gl.uniform_1_i32(locations.get("u_source"), 0);
+        gl.uniform_2_f32(locations.get("u_texel_size"), 1.0 / width as f32, 1.0 / height as f32);
+
+        gl.disable(glow::DEPTH_TEST);
+        gl.disable(glow::BLEND);
+        gl.bind_vertex_array(Some(vao));
+        gl.draw_arrays(glow::TRIANGLES, 0, 3);
+        gl.bind_vertex_array(None);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        gl.enable(glow::DEPTH_TEST);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_msaa_options_multisample() {
+        for option in Antialiasing::ALL {
+            let multisampled = matches!(option, Antialiasing::Msaa2 | Antialiasing::Msaa4 | Antialiasing::Msaa8);
+            assert_eq!(option.samples() > 1, multisampled, "{}", option.name());
+        }
+        assert_eq!(Antialiasing::default().samples(), 4);  // What the viewport used before the setting existed
+    }
+}
@@ -0,0 +1,111 @@
+// Draw-call benchmark - CPU cost of setting a lit object's uniforms, the old way and the current one
+// Needs a live GL context, so it runs from the app (--bench-draws) rather than as a cargo bench
+use glow::HasContext;  // Trait providing OpenGL function methods
+use nalgebra_glm as glm;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::camera_block::{self, CameraBuffer};
+use super::hot_reload;
+use super::lighting::Material;
+use super::uniforms::UniformLocations;
+use super::{cube, mesh, preprocess, shader};
+
+/// Rounds per method - the fastest is kept, so a stall in one doesn't skew the result
+const ROUNDS: usize = 5;
+
+/// The camera as plain uniforms, like before the camera block: swapped in for camera.glsl
+/// so the "before" program is the same shader with the old interface
+const PLAIN_CAMERA_GLSL: &str = "uniform mat4 u_projection;\nuniform mat4 u_view;\nuniform vec3 u_camera_position;\nuniform vec3 u_camera_forward;\n";
+
+/// Best time for the same number of draws each way
+#[derive(Debug, Clone, Copy)]
+pub struct DrawCost {
+    pub draws: usize,
+    pub by_name: Duration,  // Before: glGetUniformLocation for every uniform of every draw, matrices set per draw
+    pub cached: Duration,   // After: locations resolved at link time, projection/view in the camera block
+}
+
+impl DrawCost {
+    /// Per-draw figures and the speedup, for the console
+    pub fn report(&self) -> String {
+        let per_draw = |time: Duration| time.as_nanos() as f64 / self.draws.max(1) as f64;
+        let (before, after) = (per_draw(self.by_name), per_draw(self.cached));
+        format!(
+            "Uniform setup per draw ({} draws of the Blinn-Phong cube, best of {}):\n  by name, matrices per draw:      {:>8.0} ns\n  cached locations + camera block: {:>8.0} ns\n  {:.1}x faster",
+            self.draws, ROUNDS, before, after, before / after.max(1.0),
+        )
+    }
+}
+
+/// Time `draws` draws of the cube with each method (the GL context must be current)
+/// CPU time to issue the calls is measured - the GPU is drained between rounds, outside the timing
+pub fn measure(gl: &Arc<glow::Context>, draws: usize) -> Result<DrawCost, String> {
+    let cube = mesh::Mesh::new(gl.clone(), &cube::mesh_data())?;
+    let camera_buffer = CameraBuffer::new(gl.clone())?;
+    let legacy = build(gl, Some(PLAIN_CAMERA_GLSL))?;
+    let program = build(gl, None)?;
+    camera_block::init_program(gl, program);
+    let locations = UniformLocations::new(gl, program);
+
+    let projection = glm::perspective(16.0 / 9.0, 45f32.to_radians(), 0.1, 100.0);
+    let view = glm::look_at(&glm::vec3(0.0, 0.0, 3.0), &glm::Vec3::zeros(), &glm::Vec3::y());
+    let eye = glm::vec3(0.0, 0.0, 3.0);
+    let forward = -glm::Vec3::z();
+    let material = Material::default();
+    let model = |i: usize| glm::translation(&glm::vec3(i as f32 * 1e-3, 0.0, 0.0));
+
+    let mut cost = DrawCost { draws, by_name: Duration::MAX, cached: Duration::MAX };
+    unsafe {
+        // Draw into a single pixel - fill rate isn't what's measured
+        let mut viewport = [0i32; 4];
+        gl.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport);
+        gl.viewport(0, 0, 1, 1);
+
+        for _ in 0..ROUNDS {
+            gl.finish();
+            let start = Instant::now();
+            for i in 0..draws {
+                gl.use_program(Some(legacy));
+                let location = |name: &str| gl.get_uniform_location(legacy, name);
+                gl.uniform_matrix_4_f32_slice(location("u_projection").as_ref(), false, projection.as_slice());
+                gl.uniform_matrix_4_f32_slice(location("u_view").as_ref(), false, view.as_slice());
+                gl.uniform_matrix_4_f32_slice(location("u_model").as_ref(), false, model(i).as_slice());
+                gl.uniform_3_f32_slice(location("u_material.diffuse").as_ref(), &material.diffuse);
+                gl.uniform_3_f32_slice(location("u_material.specular").as_ref(), &material.specular);
+                gl.uniform_1_f32(location("u_material.shininess").as_ref(), material.shininess);
+                gl.uniform_3_f32_slice(location("u_material.emissive").as_ref(), &material.emissive);
+                cube.draw(gl);
+            }
+            cost.by_name = cost.by_name.min(start.elapsed());
+
+            gl.finish();
+            let start = Instant::now();
+            camera_buffer.update(&camera_block::pack(&projection, &view, &eye, &forward));  // Once per frame
+            for i in 0..draws {
+                gl.use_program(Some(program));
+                material.upload(gl, &locations);
+                gl.uniform_matrix_4_f32_slice(locations.get("u_model"), false, model(i).as_slice());
+                cube.draw(gl);
+            }
+            cost.cached = cost.cached.min(start.elapsed());
+        }
+
+        gl.finish();
+        gl.viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        gl.delete_program(legacy);
+        gl.delete_program(program);
+    }
+    Ok(cost)
+}
+
+/// The plain Blinn-Phong variant from the embedded sources, optionally with another camera.glsl
+fn build(gl: &glow::Context, camera_glsl: Option<&str>) -> Result<glow::Program, String> {
+    let read = |name: &str| match (name, camera_glsl) {
+        ("camera.glsl", Some(replacement)) => Some(replacement.to_string()),
+        _ => hot_reload::read_shader(&[], name),
+    };
+    let vertex = preprocess::preprocess("mesh.vert", &[], &read).map_err(|e| e.to_string())?;
+    let fragment = preprocess::preprocess("blinn_phong.frag", &[], &read).map_err(|e| e.to_string())?;
+    shader::compile_program(gl, &vertex.text, &fragment.text).map_err(|e| e.to_string())
+}
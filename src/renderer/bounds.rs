@@ -0,0 +1,169 @@
+// Bounding volumes - axis-aligned boxes and spheres for framing and clip planes
+use nalgebra_glm as glm;  // Vectors and matrices
+
+/// Axis-aligned bounding box (empty when min > max)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: glm::Vec3,
+    pub max: glm::Vec3,
+}
+
+impl Aabb {
+    /// Box containing nothing - extending it with a point gives that point
+    pub fn empty() -> Self {
+        Self {
+            min: glm::Vec3::repeat(f32::INFINITY),
+            max: glm::Vec3::repeat(f32::NEG_INFINITY),
+        }
+    }
+
+    /// Smallest box around a set of points
+    pub fn from_points(points: impl IntoIterator<Item = [f32; 3]>) -> Self {
+        let mut aabb = Self::empty();
+        for point in points {
+            aabb.extend(&glm::Vec3::from(point));
+        }
+        aabb
+    }
+
+    /// True if no point was ever added
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x
+    }
+
+    /// Grow to include a point
+    pub fn extend(&mut self, point: &glm::Vec3) {
+        self.min = glm::min2(&self.min, point);
+        self.max = glm::max2(&self.max, point);
+    }
+
+    pub fn center(&self) -> glm::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> glm::Vec3 {
+        self.max - self.min
+    }
+
+    /// The 8 corner points
+    pub fn corners(&self) -> [glm::Vec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            glm::vec3(a.x, a.y, a.z), glm::vec3(b.x, a.y, a.z),
+            glm::vec3(a.x, b.y, a.z), glm::vec3(b.x, b.y, a.z),
+            glm::vec3(a.x, a.y, b.z), glm::vec3(b.x, a.y, b.z),
+            glm::vec3(a.x, b.y, b.z), glm::vec3(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Box around this box after a transform (may be larger than a box fitted to the transformed mesh)
+    pub fn transformed(&self, matrix: &glm::Mat4) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let mut aabb = Self::empty();
+        for corner in self.corners() {
+            aabb.extend(&(matrix * corner.push(1.0)).xyz());
+        }
+        aabb
+    }
+}
+
+/// Sphere enclosing some geometry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: glm::Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    /// Sphere centered on the points' box, just large enough to reach the farthest point
+    /// (tighter than the box's half diagonal, not the minimal sphere)
+    pub fn from_points(points: &[[f32; 3]]) -> Option<Self> {
+        let aabb = Aabb::from_points(points.iter().copied());
+        if aabb.is_empty() {
+            return None;
+        }
+        let center = aabb.center();
+        let radius = points.iter()
+            .map(|p| glm::distance(&glm::Vec3::from(*p), &center))
+            .fold(0.0, f32::max);
+        Some(Self { center, radius })
+    }
+
+    /// Sphere after a transform - non-uniform scale uses the largest axis
+    pub fn transformed(&self, matrix: &glm::Mat4) -> Self {
+        let scale = (0..3)
+            .map(|column| matrix.fixed_view::<3, 1>(0, column).norm())
+            .fold(0.0, f32::max);
+        Self {
+            center: (matrix * self.center.push(1.0)).xyz(),
+            radius: self.radius * scale,
+        }
+    }
+
+    /// Smallest sphere containing both spheres
+    pub fn merge(&self, other: &Self) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.norm();
+        if distance + other.radius <= self.radius {
+            return *self;  // Other is inside self
+        }
+        if distance + self.radius <= other.radius {
+            return *other;  // Self is inside other
+        }
+        // Span from self's far side to other's far side along the line between centers
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        let center = self.center + offset * ((radius - self.radius) / distance);
+        Self { center, radius }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POINTS: [[f32; 3]; 4] = [[-1.0, 0.0, 0.0], [3.0, 0.0, 0.0], [1.0, 2.0, -1.0], [1.0, -1.0, 1.0]];
+
+    #[test]
+    fn aabb_from_points_and_transform() {
+        let aabb = Aabb::from_points(POINTS);
+        assert_eq!(aabb.min, glm::vec3(-1.0, -1.0, -1.0));
+        assert_eq!(aabb.max, glm::vec3(3.0, 2.0, 1.0));
+        assert_eq!(aabb.center(), glm::vec3(1.0, 0.5, 0.0));
+        assert!(Aabb::empty().is_empty() && !aabb.is_empty());
+
+        // 90 degrees around Y swaps X and Z extents, then translate
+        let matrix = glm::translate(&glm::Mat4::identity(), &glm::vec3(10.0, 0.0, 0.0))
+            * glm::rotate_y(&glm::Mat4::identity(), std::f32::consts::FRAC_PI_2);
+        let moved = aabb.transformed(&matrix);
+        assert!((moved.size() - glm::vec3(2.0, 3.0, 4.0)).norm() < 1e-5);
+        assert!((moved.center() - glm::vec3(10.0, 0.5, -1.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn sphere_contains_all_points() {
+        let sphere = BoundingSphere::from_points(&POINTS).unwrap();
+        for point in POINTS {
+            assert!(glm::distance(&glm::Vec3::from(point), &sphere.center) <= sphere.radius + 1e-6);
+        }
+        assert!(BoundingSphere::from_points(&[]).is_none());
+
+        // Uniform scale by 2 doubles the radius
+        let scaled = sphere.transformed(&glm::scale(&glm::Mat4::identity(), &glm::vec3(2.0, 2.0, 2.0)));
+        assert!((scaled.radius - sphere.radius * 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn merged_sphere_encloses_both() {
+        let a = BoundingSphere { center: glm::vec3(0.0, 0.0, 0.0), radius: 1.0 };
+        let b = BoundingSphere { center: glm::vec3(4.0, 0.0, 0.0), radius: 1.0 };
+        let merged = a.merge(&b);
+        assert!((merged.center - glm::vec3(2.0, 0.0, 0.0)).norm() < 1e-6);
+        assert!((merged.radius - 3.0).abs() < 1e-6);
+
+        let inner = BoundingSphere { center: glm::vec3(0.5, 0.0, 0.0), radius: 0.2 };
+        assert_eq!(a.merge(&inner), a);
+        assert_eq!(inner.merge(&a), a);
+    }
+}
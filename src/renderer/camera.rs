@@ -0,0 +1,599 @@
+// Viewport camera - orbits a pivot or flies first-person, pans in the view plane and zooms toward the cursor
+use nalgebra_glm as glm;  // Vectors and matrices
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, TAU};
+
+use super::bounds::BoundingSphere;
+
+/// How mouse and keyboard input move the camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraMode {
+    /// Left-drag turns around the pivot
+    #[default]
+    Orbit,
+    /// WASD/QE move the eye, right-drag looks around from it
+    Fly,
+}
+
+/// Perspective foreshortening or true parallel (CAD-style) projection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Projection {
+    #[default]
+    Perspective,
+    Orthographic,
+}
+
+/// Canonical viewing directions, like a numeric keypad in CAD and DCC tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewPreset {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Isometric,
+}
+
+impl ViewPreset {
+    /// Every preset, in menu order
+    pub const ALL: [ViewPreset; 7] = [
+        ViewPreset::Front,
+        ViewPreset::Back,
+        ViewPreset::Left,
+        ViewPreset::Right,
+        ViewPreset::Top,
+        ViewPreset::Bottom,
+        ViewPreset::Isometric,
+    ];
+
+    /// Display name for menus and the status bar
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewPreset::Front => "Front",
+            ViewPreset::Back => "Back",
+            ViewPreset::Left => "Left",
+            ViewPreset::Right => "Right",
+            ViewPreset::Top => "Top",
+            ViewPreset::Bottom => "Bottom",
+            ViewPreset::Isometric => "Isometric",
+        }
+    }
+
+    /// Camera yaw and pitch that look along this direction (front = looking down -Z)
+    fn angles(self) -> (f32, f32) {
+        match self {
+            ViewPreset::Front => (0.0, 0.0),
+            ViewPreset::Back => (PI, 0.0),
+            ViewPreset::Left => (-FRAC_PI_2, 0.0),
+            ViewPreset::Right => (FRAC_PI_2, 0.0),
+            ViewPreset::Top => (0.0, FRAC_PI_2),
+            ViewPreset::Bottom => (0.0, -FRAC_PI_2),
+            // True isometric: the eye along (1, 1, 1), all three axes foreshortened equally
+            ViewPreset::Isometric => (FRAC_PI_4, (1.0 / 3.0_f32.sqrt()).asin()),
+        }
+    }
+}
+
+/// An in-progress animated change of view direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transition {
+    from: glm::Vec2,  // Starting yaw/pitch
+    to: glm::Vec2,    // Final yaw/pitch
+    elapsed: f32,     // Seconds since the transition started
+}
+
+/// How long switching to a view preset takes
+const TRANSITION_SECONDS: f32 = 0.35;
+
+/// Spherical camera around `target` with drag inertia
+/// In fly mode `target` is simply the point `distance` ahead of the eye, carried along as it moves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub mode: CameraMode,   // Orbit or fly controls
+    pub target: glm::Vec3,  // Pivot point the camera orbits and looks at
+    pub distance: f32,      // Eye-to-target distance
+    pub yaw: f32,           // Radians around +Y (0 = eye on the +Z side of the target)
+    pub pitch: f32,         // Radians above the target's horizontal plane
+    pub projection: Projection,  // Perspective or orthographic
+    pub fov_y: f32,         // Vertical field of view in radians (orthographic shows the same height at the pivot)
+    pub near: f32,          // Near clipping plane (refit to the scene every frame by fit_clip_planes)
+    pub far: f32,           // Far clipping plane
+
+    pub orbit_sensitivity: f32,  // Radians per dragged point
+    pub pan_sensitivity: f32,    // 1.0 = the point under the cursor follows it exactly at the pivot depth
+    pub zoom_sensitivity: f32,   // Zoom speed per scrolled point
+    pub inertia: f32,            // Seconds for a released drag to slow to ~37% (0 = stop at once)
+    pub fly_speed: f32,          // World units per second in fly mode (shift multiplies by FAST_FLY_FACTOR)
+
+    orbit_velocity: glm::Vec2,  // Yaw/pitch change per second carried after release
+    pan_velocity: glm::Vec3,    // Target movement per second carried after release
+    transition: Option<Transition>,  // View preset animation in progress
+}
+
+/// Straight down or up - the up vector comes from the yaw, so the poles are safe to reach
+const MAX_PITCH: f32 = FRAC_PI_2;
+
+/// Closest the eye may get to the pivot
+const MIN_DISTANCE: f32 = 1e-3;
+
+/// Below this speed a gliding camera snaps to rest
+const REST_SPEED: f32 = 1e-4;
+
+/// Smallest near/far ratio when the eye is inside the scene (keeps 24-bit depth usable)
+const MIN_NEAR_RATIO: f32 = 1e-4;
+
+/// Fly speed multiplier while shift is held
+pub const FAST_FLY_FACTOR: f32 = 4.0;
+
+impl Default for Camera {
+    /// Looks at the origin from (2, 2, 2), the old fixed viewpoint
+    fn default() -> Self {
+        Self {
+            mode: CameraMode::Orbit,
+            target: glm::Vec3::zeros(),
+            distance: 12.0_f32.sqrt(),                     // |(2, 2, 2)|
+            yaw: FRAC_PI_4,
+            pitch: (1.0 / 3.0_f32.sqrt()).asin(),          // ~35.26 degrees
+            projection: Projection::Perspective,
+            fov_y: 45.0_f32.to_radians(),
+            near: 0.1,
+            far: 100.0,
+            orbit_sensitivity: 0.01,
+            pan_sensitivity: 1.0,
+            zoom_sensitivity: 0.002,
+            inertia: 0.15,
+            fly_speed: 2.0,
+            orbit_velocity: glm::Vec2::zeros(),
+            pan_velocity: glm::Vec3::zeros(),
+            transition: None,
+        }
+    }
+}
+
+impl Camera {
+    /// Eye position in world space
+    pub fn eye(&self) -> glm::Vec3 {
+        let offset = glm::vec3(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        );
+        self.target + offset * self.distance
+    }
+
+    /// World-to-camera matrix
+    pub fn view_matrix(&self) -> glm::Mat4 {
+        let (_, up, _) = self.axes();
+        glm::look_at(&self.eye(), &self.target, &up)
+    }
+
+    /// Camera-to-clip matrix for a viewport with the given width/height ratio
+    pub fn projection_matrix(&self, aspect: f32) -> glm::Mat4 {
+        match self.projection {
+            Projection::Perspective => glm::perspective(aspect, self.fov_y, self.near, self.far),
+            Projection::Orthographic => {
+                // Same framing as perspective at the pivot, so toggling doesn't jump in size
+                let half_height = self.half_height_at_target();
+                let half_width = half_height * aspect;
+                // The depth range starts behind the eye: zooming only shrinks the box, nothing gets clipped
+                glm::ortho(-half_width, half_width, -half_height, half_height, -self.far, self.far)
+            }
+        }
+    }
+
+    /// Camera right, up and forward axes in world space
+    /// Derived from yaw and pitch rather than world up, so they stay valid looking straight down
+    fn axes(&self) -> (glm::Vec3, glm::Vec3, glm::Vec3) {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let forward = -glm::vec3(cos_pitch * sin_yaw, sin_pitch, cos_pitch * cos_yaw);
+        let right = glm::vec3(cos_yaw, 0.0, -sin_yaw);
+        let up = glm::vec3(-sin_yaw * sin_pitch, cos_pitch, -cos_yaw * sin_pitch);
+        (right, up, forward)
+    }
+
+    /// Camera right and up axes in world space
+    fn basis(&self) -> (glm::Vec3, glm::Vec3) {
+        let (right, up, _) = self.axes();
+        (right, up)
+    }
+
+    /// Half height of the view at the pivot's depth
+    fn half_height_at_target(&self) -> f32 {
+        self.distance * (self.fov_y * 0.5).tan()
+    }
+
+    /// Rotate around the pivot by a mouse drag (in points), `dt` seconds after the last one
+    pub fn orbit(&mut self, delta: glm::Vec2, dt: f32) {
+        let angles = glm::vec2(-delta.x, delta.y) * self.orbit_sensitivity;
+        self.rotate(angles);
+        self.orbit_velocity = if dt > 0.0 { angles / dt } else { glm::Vec2::zeros() };
+        self.pan_velocity = glm::Vec3::zeros();
+        self.transition = None;  // Grabbing the view cancels a preset animation
+    }
+
+    /// Turn the view around the eye by a mouse drag (in points) - the fly-mode mouse-look
+    pub fn look(&mut self, delta: glm::Vec2) {
+        let eye = self.eye();
+        self.rotate(glm::vec2(-delta.x, delta.y) * self.orbit_sensitivity);
+        // Same yaw/pitch convention as orbiting, but the eye stays put and the target swings
+        self.target += eye - self.eye();
+        self.stop();
+    }
+
+    /// Move eye and target together for `dt` seconds
+    /// `direction` is camera-relative: x = right, y = world up, z = forward (each -1..1)
+    pub fn fly(&mut self, direction: glm::Vec3, fast: bool, dt: f32) {
+        if direction == glm::Vec3::zeros() {
+            return;
+        }
+        let (right, _, forward) = self.axes();
+        let speed = self.fly_speed * if fast { FAST_FLY_FACTOR } else { 1.0 };
+        let velocity = (right * direction.x + glm::Vec3::y() * direction.y + forward * direction.z).normalize() * speed;
+        self.target += velocity * dt;
+    }
+
+    /// Slide the pivot in the view plane by a mouse drag in a viewport `viewport_height` points tall
+    pub fn pan(&mut self, delta: glm::Vec2, viewport_height: f32, dt: f32) {
+        let (right, up) = self.basis();
+        let world_per_point = 2.0 * self.half_height_at_target() / viewport_height.max(1.0) * self.pan_sensitivity;
+        // Screen Y points down, so dragging down moves the scene down = the pivot up
+        let offset = (-right * delta.x + up * delta.y) * world_per_point;
+        self.target += offset;
+        self.pan_velocity = if dt > 0.0 { offset / dt } else { glm::Vec3::zeros() };
+        self.orbit_velocity = glm::Vec2::zeros();
+        self.transition = None;
+    }
+
+    /// Dolly in (positive `scroll`) or out, keeping the point under the cursor in place
+    /// `cursor` is in normalized device coordinates (-1..1, +Y up)
+    pub fn zoom(&mut self, scroll: f32, cursor: glm::Vec2, aspect: f32) {
+        let factor = (-scroll * self.zoom_sensitivity).exp();
+        let new_distance = (self.distance * factor).max(MIN_DISTANCE);
+        let factor = new_distance / self.distance;
+
+        // Point under the cursor on the plane through the pivot, facing the camera
+        let (right, up) = self.basis();
+        let half_height = self.half_height_at_target();
+        let under_cursor = self.target
+            + right * (cursor.x * half_height * aspect)
+            + up * (cursor.y * half_height);
+
+        // Scaling the view about that point keeps it fixed on screen
+        self.target = under_cursor + (self.target - under_cursor) * factor;
+        self.distance = new_distance;
+    }
+
+    /// Orbit around a new pivot without moving the eye (the view turns to face it)
+    pub fn set_pivot(&mut self, pivot: glm::Vec3) {
+        let eye = self.eye();
+        let offset = eye - pivot;
+        let distance = offset.norm();
+        if distance < MIN_DISTANCE {
+            return;  // Clicked right at the eye - no direction to keep
+        }
+        self.target = pivot;
+        self.distance = distance;
+        self.pitch = (offset.y / distance).clamp(-1.0, 1.0).asin().clamp(-MAX_PITCH, MAX_PITCH);
+        self.yaw = offset.x.atan2(offset.z);
+        self.stop();
+    }
+
+    /// The default camera turned to look along a preset direction, e.g. for the fixed views of a quad layout
+    pub fn looking_along(preset: ViewPreset, projection: Projection) -> Self {
+        let (yaw, pitch) = preset.angles();
+        Self { yaw, pitch, projection, ..Self::default() }
+    }
+
+    /// Go back to the default viewpoint, keeping the sensitivity settings
+    pub fn reset_view(&mut self) {
+        *self = Self {
+            orbit_sensitivity: self.orbit_sensitivity,
+            pan_sensitivity: self.pan_sensitivity,
+            zoom_sensitivity: self.zoom_sensitivity,
+            inertia: self.inertia,
+            fly_speed: self.fly_speed,
+            mode: self.mode,
+            projection: self.projection,
+            ..Self::default()
+        };
+    }
+
+    /// Turn smoothly to look along a preset direction, keeping the pivot and distance
+    pub fn animate_to(&mut self, preset: ViewPreset) {
+        let (yaw, pitch) = preset.angles();
+        // Take the short way round: bring the target yaw within half a turn of the current one
+        let yaw = self.yaw + (yaw - self.yaw + PI).rem_euclid(TAU) - PI;
+        self.stop();
+        self.transition = Some(Transition {
+            from: glm::vec2(self.yaw, self.pitch),
+            to: glm::vec2(yaw, pitch),
+            elapsed: 0.0,
+        });
+    }
+
+    /// Look at a sphere from the current direction, close enough that it fills the view
+    pub fn frame(&mut self, sphere: &BoundingSphere, aspect: f32) {
+        // The narrower of the vertical and horizontal fields of view limits the fit
+        let half_fov_y = self.fov_y * 0.5;
+        let half_fov_x = (half_fov_y.tan() * aspect).atan();
+        let half_fov = half_fov_y.min(half_fov_x);
+
+        self.stop();
+        self.target = sphere.center;
+        self.distance = (sphere.radius / half_fov.sin()).max(MIN_DISTANCE);
+    }
+
+    /// Pull the near and far planes in to just enclose a sphere, for the best depth precision
+    pub fn fit_clip_planes(&mut self, sphere: &BoundingSphere) {
+        let (_, _, forward) = self.axes();
+        let depth = glm::dot(&(sphere.center - self.eye()), &forward);  // Center's distance along the view
+        let radius = sphere.radius.max(MIN_DISTANCE) * 1.01;  // Small margin so surfaces at the edge don't flicker
+        self.far = (depth + radius).max(MIN_DISTANCE);
+        self.near = (depth - radius).max(self.far * MIN_NEAR_RATIO);
+    }
+
+    /// Switch between perspective and orthographic projection
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            Projection::Perspective => Projection::Orthographic,
+            Projection::Orthographic => Projection::Perspective,
+        };
+    }
+
+    /// Advance the preset animation and inertial glide by `dt` seconds (skip while a drag is in progress)
+    pub fn update(&mut self, dt: f32) {
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed += dt;
+            let t = (transition.elapsed / TRANSITION_SECONDS).min(1.0);
+            let eased = t * t * (3.0 - 2.0 * t);  // Smoothstep: ease in and out
+            let angles = glm::lerp(&transition.from, &transition.to, eased);
+            self.yaw = angles.x;
+            self.pitch = angles.y;
+            if t >= 1.0 {
+                self.transition = None;
+            }
+            return;
+        }
+
+        if self.inertia <= 0.0 {
+            self.stop();
+            return;
+        }
+        self.rotate(self.orbit_velocity * dt);
+        self.target += self.pan_velocity * dt;
+
+        // Exponential decay is frame-rate independent
+        let decay = (-dt / self.inertia).exp();
+        self.orbit_velocity *= decay;
+        self.pan_velocity *= decay;
+        if self.orbit_velocity.norm() < REST_SPEED && self.pan_velocity.norm() < REST_SPEED {
+            self.stop();
+        }
+    }
+
+    /// Is the camera still gliding after a drag or animating to a preset?
+    pub fn is_moving(&self) -> bool {
+        self.orbit_velocity != glm::Vec2::zeros() || self.pan_velocity != glm::Vec3::zeros() || self.transition.is_some()
+    }
+
+    /// Cancel any inertial motion or preset animation
+    pub fn stop(&mut self) {
+        self.orbit_velocity = glm::Vec2::zeros();
+        self.pan_velocity = glm::Vec3::zeros();
+        self.transition = None;
+    }
+
+    /// World size of one pixel at a point, for things that should keep a fixed size on screen
+    pub fn pixel_size_at(&self, point: &glm::Vec3, viewport_height: f32) -> f32 {
+        let half_height = match self.projection {
+            Projection::Perspective => {
+                let (_, _, forward) = self.axes();
+                let depth = glm::dot(&(point - self.eye()), &forward).max(self.near);
+                depth * (self.fov_y * 0.5).tan()
+            }
+            Projection::Orthographic => self.half_height_at_target(),
+        };
+        2.0 * half_height / viewport_height.max(1.0)
+    }
+
+    /// World position of a point in normalized device coordinates (depth -1 = near, 1 = far)
+    pub fn unproject(&self, ndc: glm::Vec3, aspect: f32) -> glm::Vec3 {
+        let inverse = (self.projection_matrix(aspect) * self.view_matrix())
+            .try_inverse()
+            .unwrap_or_else(glm::Mat4::identity);
+        let world = inverse * glm::vec4(ndc.x, ndc.y, ndc.z, 1.0);
+        world.xyz() / world.w
+    }
+
+    fn rotate(&mut self, angles: glm::Vec2) {
+        self.yaw += angles.x;
+        self.pitch = (self.pitch + angles.y).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: glm::Vec3, b: glm::Vec3) {
+        assert!((a - b).norm() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn default_matches_old_viewpoint() {
+        assert_near(Camera::default().eye(), glm::vec3(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn preset_cameras_keep_the_default_distance() {
+        let distance = Camera::default().distance;
+        let top = Camera::looking_along(ViewPreset::Top, Projection::Orthographic);
+        assert_near(top.eye(), glm::vec3(0.0, distance, 0.0));
+        assert_eq!(top.projection, Projection::Orthographic);
+        assert_near(Camera::looking_along(ViewPreset::Front, Projection::Perspective).eye(), glm::vec3(0.0, 0.0, distance));
+    }
+
+    #[test]
+    fn orbit_keeps_distance_and_clamps_pitch() {
+        let mut camera = Camera::default();
+        camera.orbit(glm::vec2(30.0, 10_000.0), 0.016);
+        assert_eq!(camera.pitch, MAX_PITCH);
+        assert!((camera.eye() - camera.target).norm() - camera.distance < 1e-4);
+    }
+
+    /// Normalized device coordinates of a world point
+    fn project(camera: &Camera, point: glm::Vec3, aspect: f32) -> glm::Vec3 {
+        let clip = camera.projection_matrix(aspect) * camera.view_matrix() * glm::vec4(point.x, point.y, point.z, 1.0);
+        clip.xyz() / clip.w
+    }
+
+    #[test]
+    fn zoom_keeps_point_under_cursor() {
+        let mut camera = Camera::default();
+        let cursor = glm::vec2(0.5, -0.25);
+        let aspect = 1.5;
+
+        // The world point under the cursor at the pivot's depth
+        let pivot_depth = project(&camera, camera.target, aspect).z;
+        let under_cursor = camera.unproject(glm::vec3(cursor.x, cursor.y, pivot_depth), aspect);
+
+        camera.zoom(200.0, cursor, aspect);
+        assert!(camera.distance < Camera::default().distance);
+
+        let after = project(&camera, under_cursor, aspect);
+        assert!((after.x - cursor.x).abs() < 1e-4);
+        assert!((after.y - cursor.y).abs() < 1e-4);
+    }
+
+    #[test]
+    fn set_pivot_keeps_eye() {
+        let mut camera = Camera::default();
+        let eye = camera.eye();
+        camera.set_pivot(glm::vec3(0.5, 0.0, -0.5));
+        assert_near(camera.eye(), eye);
+        assert_near(camera.target, glm::vec3(0.5, 0.0, -0.5));
+    }
+
+    #[test]
+    fn pan_moves_eye_and_target_together() {
+        let mut camera = Camera::default();
+        let offset = camera.eye() - camera.target;
+        camera.pan(glm::vec2(40.0, -20.0), 600.0, 0.016);
+        assert!(camera.target != glm::Vec3::zeros());
+        assert_near(camera.eye() - camera.target, offset);
+    }
+
+    #[test]
+    fn look_keeps_eye_and_fly_moves_forward() {
+        let mut camera = Camera::default();
+        let eye = camera.eye();
+        camera.look(glm::vec2(25.0, -10.0));
+        assert_near(camera.eye(), eye);
+
+        // One second forward at unit speed covers one unit along the view direction
+        camera.fly_speed = 1.0;
+        let forward = (camera.target - camera.eye()).normalize();
+        camera.fly(glm::vec3(0.0, 0.0, 1.0), false, 1.0);
+        assert_near(camera.eye(), eye + forward);
+
+        camera.fly(glm::vec3(0.0, 1.0, 0.0), true, 0.5);
+        assert_near(camera.eye(), eye + forward + glm::vec3(0.0, FAST_FLY_FACTOR * 0.5, 0.0));
+    }
+
+    #[test]
+    fn presets_animate_to_axis_views() {
+        let mut camera = Camera::default();
+        camera.animate_to(ViewPreset::Top);
+        camera.update(TRANSITION_SECONDS * 0.5);
+        assert!(camera.is_moving());
+        camera.update(TRANSITION_SECONDS);
+        assert!(!camera.is_moving());
+        assert_near(camera.eye(), glm::vec3(0.0, camera.distance, 0.0));
+
+        // Looking straight down still has a usable view matrix: world -Z is screen up
+        let up = camera.view_matrix() * glm::vec4(0.0, 0.0, -1.0, 0.0);
+        assert_near(up.xyz(), glm::vec3(0.0, 1.0, 0.0));
+
+        // Back from the front goes the short way (half a turn), not the long way
+        camera.animate_to(ViewPreset::Front);
+        camera.update(1.0);
+        camera.yaw = 0.1;
+        camera.animate_to(ViewPreset::Back);
+        camera.update(1.0);
+        assert!((camera.yaw - PI).abs() < 1e-5);
+        assert_near(camera.eye(), glm::vec3(0.0, 0.0, -camera.distance));
+    }
+
+    #[test]
+    fn orthographic_matches_perspective_at_pivot() {
+        let mut camera = Camera::default();
+        let aspect = 1.5;
+        let corner = camera.unproject(glm::vec3(1.0, 1.0, project(&camera, camera.target, aspect).z), aspect);
+
+        camera.toggle_projection();
+        assert_eq!(camera.projection, Projection::Orthographic);
+        let ndc = project(&camera, corner, aspect);
+        assert!((ndc.x - 1.0).abs() < 1e-4 && (ndc.y - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn frame_fits_sphere_and_clip_planes() {
+        let mut camera = Camera::default();
+        let sphere = BoundingSphere { center: glm::vec3(100.0, 50.0, -20.0), radius: 40.0 };
+        let aspect = 0.5;  // Tall viewport - the horizontal field of view limits the fit
+        camera.frame(&sphere, aspect);
+        camera.fit_clip_planes(&sphere);
+        assert_eq!(camera.target, sphere.center);
+
+        // The sphere's sides touch the view edges: its tangent point lands on |ndc.x| <= 1
+        let (right, _, _) = camera.axes();
+        let side = sphere.center + right * sphere.radius;
+        assert!(project(&camera, side, aspect).x <= 1.0);
+
+        // Near/far bracket the sphere along the view direction
+        assert!(camera.near <= camera.distance - sphere.radius);
+        assert!(camera.far >= camera.distance + sphere.radius);
+        assert!(camera.near > 0.0 && camera.far < 1000.0);
+
+        // Inside the sphere the near plane stays positive
+        camera.distance = 1.0;
+        camera.fit_clip_planes(&sphere);
+        assert!(camera.near > 0.0 && camera.near < 1.0);
+    }
+
+    #[test]
+    fn pixel_size_matches_projection() {
+        let mut camera = Camera::default();
+        let height = 400.0;
+        for projection in [Projection::Perspective, Projection::Orthographic] {
+            camera.projection = projection;
+            // Moving a point up by one pixel's size moves it 2/height in NDC
+            let (_, up, _) = camera.axes();
+            let point = glm::vec3(0.3, -0.2, 0.1);
+            let step = camera.pixel_size_at(&point, height);
+            let delta = project(&camera, point + up * step, 1.0).y - project(&camera, point, 1.0).y;
+            assert!((delta - 2.0 / height).abs() < 1e-4, "{:?}: {}", projection, delta);
+        }
+    }
+
+    #[test]
+    fn inertia_glides_then_stops() {
+        let mut camera = Camera::default();
+        camera.orbit(glm::vec2(10.0, 0.0), 0.016);
+        let yaw = camera.yaw;
+
+        camera.update(0.016);
+        assert!(camera.yaw < yaw, "release keeps turning the same way");
+        for _ in 0..600 {
+            camera.update(0.016);
+        }
+        assert!(!camera.is_moving());
+
+        camera.inertia = 0.0;
+        camera.orbit(glm::vec2(10.0, 0.0), 0.016);
+        let yaw = camera.yaw;
+        camera.update(0.016);
+        assert_eq!(camera.yaw, yaw);
+    }
+}
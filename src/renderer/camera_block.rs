@@ -0,0 +1,101 @@
+// Camera uniform buffer - projection, view and eye in one std140 block shared by every lit program
+// Written once per frame instead of setting the matrices in each program before each draw
+use glow::HasContext;  // Trait providing OpenGL function methods
+use nalgebra_glm as glm;
+use std::sync::Arc;    // Shared OpenGL context so the buffer can be freed on drop
+
+/// The `Camera` block declaration, included by mesh.vert and lighting.glsl
+pub const CAMERA_GLSL: &str = include_str!("../../shaders/camera.glsl");
+
+/// Uniform buffer binding point the block is attached to in every program
+pub const CAMERA_BINDING: u32 = 0;
+
+/// Floats in the block: two mat4s, then two vec3s each padded to 16 bytes by std140
+pub const CAMERA_BLOCK_FLOATS: usize = 40;
+
+/// Block contents in std140 layout
+/// Offsets: u_projection 0, u_view 64, u_camera_position 128, u_camera_forward 144 (bytes)
+pub fn pack(projection: &glm::Mat4, view: &glm::Mat4, eye: &glm::Vec3, forward: &glm::Vec3) -> [f32; CAMERA_BLOCK_FLOATS] {
+    let mut block = [0.0; CAMERA_BLOCK_FLOATS];
+    block[0..16].copy_from_slice(projection.as_slice());  // Column-major, like glUniformMatrix4fv
+    block[16..32].copy_from_slice(view.as_slice());
+    block[32..35].copy_from_slice(eye.as_slice());
+    block[36..39].copy_from_slice(forward.as_slice());
+    block
+}
+
+/// The GPU buffer behind the block
+pub struct CameraBuffer {
+    gl: Arc<glow::Context>,
+    buffer: glow::Buffer,
+}
+
+impl CameraBuffer {
+    pub fn new(gl: Arc<glow::Context>) -> Result<Self, String> {
+        unsafe {
+            let buffer = gl.create_buffer()?;
+            gl.bind_buffer(glow::UNIFORM_BUFFER, Some(buffer));
+            gl.buffer_data_size(glow::UNIFORM_BUFFER, (CAMERA_BLOCK_FLOATS * 4) as i32, glow::DYNAMIC_DRAW);
+            gl.bind_buffer(glow::UNIFORM_BUFFER, None);
+            Ok(Self { gl, buffer })
+        }
+    }
+
+    /// Upload this frame's camera and attach the buffer to CAMERA_BINDING
+    pub fn update(&self, block: &[f32; CAMERA_BLOCK_FLOATS]) {
+        unsafe {
+            self.gl.bind_buffer(glow::UNIFORM_BUFFER, Some(self.buffer));
+            self.gl.buffer_sub_data_u8_slice(glow::UNIFORM_BUFFER, 0, bytemuck::cast_slice(block));
+            self.gl.bind_buffer(glow::UNIFORM_BUFFER, None);
+            self.gl.bind_buffer_base(glow::UNIFORM_BUFFER, CAMERA_BINDING, Some(self.buffer));
+        }
+    }
+}
+
+// Free GPU memory when the renderer goes away
+impl Drop for CameraBuffer {
+    fn drop(&mut self) {
+        unsafe { self.gl.delete_buffer(self.buffer) };
+    }
+}
+
+/// Attach a program's `Camera` block (if it uses one) to CAMERA_BINDING
+pub fn init_program(gl: &glow::Context, program: glow::Program) {
+    unsafe {
+        let Some(index) = gl.get_uniform_block_index(program, "Camera") else { return };
+        gl.uniform_block_binding(program, index, CAMERA_BINDING);
+        // A mismatch with `pack` would scramble the matrices silently - say so instead
+        let size = gl.get_active_uniform_block_parameter_i32(program, index, glow::UNIFORM_BLOCK_DATA_SIZE);
+        if size as usize != CAMERA_BLOCK_FLOATS * 4 {
+            eprintln!("Camera block is {} bytes, expected {} - camera.glsl and camera_block.rs disagree", size, CAMERA_BLOCK_FLOATS * 4);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_members_at_their_std140_offsets() {
+        let projection = glm::perspective(1.5, 1.0, 0.1, 100.0);
+        let view = glm::translation(&glm::vec3(1.0, 2.0, 3.0));
+        let block = pack(&projection, &view, &glm::vec3(4.0, 5.0, 6.0), &glm::vec3(0.0, 0.0, -1.0));
+        assert_eq!(&block[0..16], projection.as_slice());
+        assert_eq!(&block[16..32], view.as_slice());
+        assert_eq!(&block[128 / 4..128 / 4 + 4], &[4.0, 5.0, 6.0, 0.0]);  // vec3 padded to a vec4
+        assert_eq!(&block[144 / 4..144 / 4 + 4], &[0.0, 0.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn shader_block_declares_members_in_packing_order() {
+        let members: Vec<&str> = ["mat4 u_projection;", "mat4 u_view;", "vec3 u_camera_position;", "vec3 u_camera_forward;"]
+            .into_iter()
+            .filter(|member| CAMERA_GLSL.contains(member))
+            .collect();
+        assert_eq!(members.len(), 4);
+        let positions: Vec<usize> = members.iter().map(|member| CAMERA_GLSL.find(member).unwrap()).collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(CAMERA_GLSL.contains("layout(std140) uniform Camera"));
+    }
+}
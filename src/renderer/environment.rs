@@ -0,0 +1,496 @@
+// Image-based lighting - an HDR equirectangular image baked into the cubemaps the PBR shader samples
+use glow::HasContext;     // Trait providing OpenGL function methods
+use nalgebra_glm as glm;  // Rotation matrix for the environment
+use std::path::Path;      // Borrowed file path
+use std::sync::Arc;       // Shared OpenGL context so textures can be freed on drop
+
+use super::uniforms::UniformLocations;
+
+/// Texture units the environment is bound to while drawing (unit 0 is left to egui)
+pub const IRRADIANCE_UNIT: u32 = 1;
+pub const PREFILTERED_UNIT: u32 = 2;
+pub const BRDF_LUT_UNIT: u32 = 3;
+pub const SKYBOX_UNIT: u32 = 4;
+
+// Baked texture sizes - the sky needs detail, the diffuse irradiance barely any
+const ENVIRONMENT_SIZE: i32 = 512;
+const IRRADIANCE_SIZE: i32 = 32;
+const PREFILTERED_SIZE: i32 = 128;
+const PREFILTERED_LEVELS: i32 = 5;  // Roughness 0, 0.25, 0.5, 0.75, 1
+const BRDF_LUT_SIZE: i32 = 256;
+
+// One triangle covering the whole target, positioned from gl_VertexID so no vertex buffer is needed
+pub const FULLSCREEN_VERTEX_SHADER_SOURCE: &str = r#"#version 330 core
+out vec2 v_ndc;  // -1..1 across the target
+
+void main() {
+    // Vertices 0, 1, 2 -> (-1, -1), (3, -1), (-1, 3)
+    v_ndc = vec2(float((gl_VertexID & 1) << 2) - 1.0, float((gl_VertexID & 2) << 1) - 1.0);
+    gl_Position = vec4(v_ndc, 0.0, 1.0);
+}
+"#;
+
+// Skybox - looks up the environment along the view ray through each pixel
+pub const SKYBOX_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+uniform mat4 u_inverse_view_projection;  // Rotation-only view, so the sky stays at infinity
+uniform mat3 u_environment_rotation;
+uniform float u_environment_intensity;
+uniform samplerCube u_environment_map;
+
+void main() {
+    vec4 point = u_inverse_view_projection * vec4(v_ndc, 1.0, 1.0);
+    vec3 direction = normalize(point.xyz / point.w);
+    vec3 color = textureLod(u_environment_map, u_environment_rotation * direction, 0.0).rgb * u_environment_intensity;
+    frag_color = vec4(color, 1.0);  // Linear, like the lit shaders - the post chain encodes it
+}
+"#;
+
+// Bake shaders share the version line and the cube face lookup
+const BAKE_PRELUDE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+const float PI = 3.14159265359;
+
+uniform int u_face;  // Cube face being rendered: +X, -X, +Y, -Y, +Z, -Z
+
+// Direction of the texel at `ndc` on the current face (OpenGL cubemap layout)
+vec3 face_direction(vec2 ndc) {
+    float s = ndc.x;
+    float t = ndc.y;
+    if (u_face == 0) return normalize(vec3(1.0, -t, -s));
+    if (u_face == 1) return normalize(vec3(-1.0, -t, s));
+    if (u_face == 2) return normalize(vec3(s, 1.0, t));
+    if (u_face == 3) return normalize(vec3(s, -1.0, -t));
+    if (u_face == 4) return normalize(vec3(s, -t, 1.0));
+    return normalize(vec3(-s, -t, -1.0));
+}
+
+// Low-discrepancy sample points for importance sampling
+vec2 hammersley(uint i, uint count) {
+    uint bits = i;
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return vec2(float(i) / float(count), float(bits) * 2.3283064365386963e-10);
+}
+
+// Half vector around `n` distributed like GGX with the given perceptual roughness
+vec3 importance_sample_ggx(vec2 xi, vec3 n, float roughness) {
+    float alpha = roughness * roughness;
+    float phi = 2.0 * PI * xi.x;
+    float cos_theta = sqrt((1.0 - xi.y) / (1.0 + (alpha * alpha - 1.0) * xi.y));
+    float sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    vec3 h = vec3(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+    vec3 up = abs(n.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+    vec3 tangent = normalize(cross(up, n));
+    vec3 bitangent = cross(n, tangent);
+    return normalize(tangent * h.x + bitangent * h.y + n * h.z);
+}
+"#;
+
+// Equirectangular image -> cube face
+const EQUIRECT_FRAGMENT_BODY: &str = r#"
+uniform sampler2D u_equirect;
+
+void main() {
+    vec3 dir = face_direction(v_ndc);
+    // Longitude around Y, latitude from the top row (+Y) down
+    vec2 uv = vec2(atan(dir.z, dir.x) / (2.0 * PI) + 0.5, 0.5 - asin(clamp(dir.y, -1.0, 1.0)) / PI);
+    frag_color = vec4(textureLod(u_equirect, uv, 0.0).rgb, 1.0);
+}
+"#;
+
+// Cosine-weighted hemisphere average -> diffuse irradiance
+const IRRADIANCE_FRAGMENT_BODY: &str = r#"
+uniform samplerCube u_environment;
+uniform float u_source_lod;  // Blurrier mip so the coarse sampling doesn't alias
+
+void main() {
+    vec3 n = face_direction(v_ndc);
+    vec3 up = abs(n.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0);
+    vec3 right = normalize(cross(up, n));
+    up = cross(n, right);
+
+    vec3 sum = vec3(0.0);
+    float count = 0.0;
+    const float STEP = 0.05;
+    for (float phi = 0.0; phi < 2.0 * PI; phi += STEP) {
+        for (float theta = 0.0; theta < 0.5 * PI; theta += STEP) {
+            vec3 local = vec3(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+            vec3 dir = local.x * right + local.y * up + local.z * n;
+            sum += textureLod(u_environment, dir, u_source_lod).rgb * cos(theta) * sin(theta);
+            count += 1.0;
+        }
+    }
+    frag_color = vec4(PI * sum / count, 1.0);
+}
+"#;
+
+// GGX-filtered reflections for one roughness level (split-sum, first half)
+const PREFILTER_FRAGMENT_BODY: &str = r#"
+uniform samplerCube u_environment;
+uniform float u_roughness;
+uniform float u_source_size;  // Face size of u_environment, to pick a mip per sample
+
+void main() {
+    vec3 n = face_direction(v_ndc);
+    vec3 v = n;  // Assume the view direction equals the reflection direction
+    float alpha = u_roughness * u_roughness;
+
+    const uint SAMPLES = 512u;
+    vec3 sum = vec3(0.0);
+    float weight = 0.0;
+    for (uint i = 0u; i < SAMPLES; i++) {
+        vec3 h = importance_sample_ggx(hammersley(i, SAMPLES), n, u_roughness);
+        vec3 l = normalize(2.0 * dot(v, h) * h - v);
+        float n_dot_l = dot(n, l);
+        if (n_dot_l > 0.0) {
+            // Sample a mip matching the solid angle this sample stands for (fewer bright speckles)
+            float n_dot_h = max(dot(n, h), 0.0);
+            float a2 = alpha * alpha;
+            float d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+            float distribution = a2 / (PI * d * d);
+            float pdf = distribution * 0.25 + 1e-4;  // n_dot_h / (4 h_dot_v) with n = v
+            float texel_angle = 4.0 * PI / (6.0 * u_source_size * u_source_size);
+            float sample_angle = 1.0 / (float(SAMPLES) * pdf + 1e-4);
+            float lod = u_roughness == 0.0 ? 0.0 : 0.5 * log2(sample_angle / texel_angle);
+
+            sum += textureLod(u_environment, l, lod).rgb * n_dot_l;
+            weight += n_dot_l;
+        }
+    }
+    frag_color = vec4(sum / max(weight, 1e-4), 1.0);
+}
+"#;
+
+// Scale and bias to F0 by view angle and roughness (split-sum, second half)
+const BRDF_FRAGMENT_BODY: &str = r#"
+float geometry_schlick(float n_dot_x, float roughness) {
+    float k = roughness * roughness * 0.5;  // IBL remapping of alpha
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+void main() {
+    vec2 uv = v_ndc * 0.5 + 0.5;
+    float n_dot_v = max(uv.x, 1e-3);
+    float roughness = uv.y;
+    vec3 v = vec3(sqrt(1.0 - n_dot_v * n_dot_v), 0.0, n_dot_v);
+    vec3 n = vec3(0.0, 0.0, 1.0);
+
+    const uint SAMPLES = 512u;
+    vec2 sum = vec2(0.0);
+    for (uint i = 0u; i < SAMPLES; i++) {
+        vec3 h = importance_sample_ggx(hammersley(i, SAMPLES), n, roughness);
+        vec3 l = normalize(2.0 * dot(v, h) * h - v);
+        float n_dot_l = max(l.z, 0.0);
+        float n_dot_h = max(h.z, 0.0);
+        float v_dot_h = max(dot(v, h), 0.0);
+        if (n_dot_l > 0.0) {
+            float geometry = geometry_schlick(n_dot_v, roughness) * geometry_schlick(n_dot_l, roughness);
+            float visibility = geometry * v_dot_h / (n_dot_h * n_dot_v);
+            float fresnel = pow(1.0 - v_dot_h, 5.0);
+            sum += vec2((1.0 - fresnel) * visibility, fresnel * visibility);
+        }
+    }
+    frag_color = vec4(sum / float(SAMPLES), 0.0, 1.0);
+}
+"#;
+
+/// User controls for the environment (kept when a new map is loaded)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvironmentSettings {
+    pub exposure: f32,      // Stops: each +1 doubles the environment's brightness
+    pub rotation: f32,      // Degrees around the world Y axis
+    pub show_skybox: bool,  // Draw the environment behind the scene
+}
+
+impl Default for EnvironmentSettings {
+    fn default() -> Self {
+        Self { exposure: 0.0, rotation: 0.0, show_skybox: true }
+    }
+}
+
+impl EnvironmentSettings {
+    /// Brightness multiplier for the exposure
+    pub fn intensity(&self) -> f32 {
+        self.exposure.exp2()
+    }
+
+    /// World direction -> environment direction (turning the environment one way turns lookups the other)
+    pub fn rotation_matrix(&self) -> glm::Mat3 {
+        glm::mat4_to_mat3(&glm::rotation(-self.rotation.to_radians(), &glm::Vec3::y()))
+    }
+}
+
+/// Decoded equirectangular image, linear RGB
+#[derive(Debug, Clone)]
+pub struct HdrImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<f32>,  // RGB triples, top row first
+}
+
+/// Read a Radiance .hdr (or any format the image crate decodes) as linear floats
+pub fn read_hdr(path: &Path) -> Result<HdrImage, String> {
+    let image = image::open(path).map_err(|e| e.to_string())?.into_rgb32f();
+    Ok(HdrImage { width: image.width(), height: image.height(), pixels: image.into_raw() })
+}
+
+/// Baked environment textures, ready for the PBR shader and the skybox
+pub struct Environment {
+    gl: Arc<glow::Context>,
+    pub name: String,             // File name, for the UI
+    cubemap: glow::Texture,       // Full environment (skybox), mipmapped
+    irradiance: glow::Texture,    // Diffuse lighting per normal
+    prefiltered: glow::Texture,   // Specular lighting per reflection direction, one mip per roughness
+    brdf_lut: glow::Texture,      // Split-sum scale/bias by (n.v, roughness)
+}
+
+impl Environment {
+    /// Decode an HDR file and bake it (the GL context must be current)
+    pub fn load(gl: Arc<glow::Context>, path: &Path) -> Result<Self, String> {
+        let image = read_hdr(path)?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        Self::bake(gl, name, &image)
+    }
+
+    /// Convert the equirectangular image to a cubemap and precompute the lighting maps
+    pub fn bake(gl: Arc<glow::Context>, name: String, image: &HdrImage) -> Result<Self, String> {
+        let bake_program = |body: &str| {
+            super::shader::create_program(&gl, FULLSCREEN_VERTEX_SHADER_SOURCE, &[BAKE_PRELUDE, body].concat())
+        };
+        unsafe {
+            // Build the value as soon as the textures exist so Drop frees them if a step fails
+            let environment = Self {
+                name,
+                cubemap: create_cubemap(&gl, ENVIRONMENT_SIZE, mip_count(ENVIRONMENT_SIZE))?,
+                irradiance: create_cubemap(&gl, IRRADIANCE_SIZE, 1)?,
+                prefiltered: create_cubemap(&gl, PREFILTERED_SIZE, PREFILTERED_LEVELS)?,
+                brdf_lut: create_texture_2d(&gl, glow::RG16F, BRDF_LUT_SIZE, BRDF_LUT_SIZE, glow::RG, glow::FLOAT, None)?,
+                gl: gl.clone(),
+            };
+
+            let framebuffer = gl.create_framebuffer()?;
+            let vao = gl.create_vertex_array()?;  // Core profile needs one bound even without attributes
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.bind_vertex_array(Some(vao));
+            gl.disable(glow::DEPTH_TEST);
+            gl.disable(glow::CULL_FACE);
+            gl.disable(glow::SCISSOR_TEST);
+            gl.disable(glow::BLEND);
+            gl.enable(glow::TEXTURE_CUBE_MAP_SEAMLESS);  // Filter across face edges
+            gl.active_texture(glow::TEXTURE0);
+
+            let result = (|| -> Result<(), String> {
+                // 1. Equirectangular -> environment cubemap, then mips for the filters below
+                let equirect = create_texture_2d(&gl, glow::RGB32F, image.width as i32, image.height as i32, glow::RGB, glow::FLOAT, Some(bytemuck::cast_slice(&image.pixels)))?;
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::REPEAT as i32);  // Longitude wraps
+                let program = bake_program(EQUIRECT_FRAGMENT_BODY).inspect_err(|_| gl.delete_texture(equirect))?;
+                render_cube_faces(&gl, program, environment.cubemap, ENVIRONMENT_SIZE, 0, |_| {});
+                gl.delete_program(program);
+                gl.delete_texture(equirect);
+                gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(environment.cubemap));
+                gl.generate_mipmap(glow::TEXTURE_CUBE_MAP);
+
+                // 2. Diffuse irradiance
+                let program = bake_program(IRRADIANCE_FRAGMENT_BODY)?;
+                let source_lod = (ENVIRONMENT_SIZE / IRRADIANCE_SIZE) as f32;
+                render_cube_faces(&gl, program, environment.irradiance, IRRADIANCE_SIZE, 0, |program| {
+                    let location = gl.get_uniform_location(program, "u_source_lod");
+                    gl.uniform_1_f32(location.as_ref(), source_lod.log2());
+                });
+                gl.delete_program(program);
+
+                // 3. Specular, one mip per roughness step
+                let program = bake_program(PREFILTER_FRAGMENT_BODY)?;
+                for level in 0..PREFILTERED_LEVELS {
+                    let roughness = level as f32 / (PREFILTERED_LEVELS - 1) as f32;
+                    render_cube_faces(&gl, program, environment.prefiltered, PREFILTERED_SIZE >> level, level, |program| {
+                        gl.uniform_1_f32(gl.get_uniform_location(program, "u_roughness").as_ref(), roughness);
+                        gl.uniform_1_f32(gl.get_uniform_location(program, "u_source_size").as_ref(), ENVIRONMENT_SIZE as f32);
+                    });
+                }
+                gl.delete_program(program);
+
+                // 4. BRDF lookup table
+                let program = bake_program(BRDF_FRAGMENT_BODY)?;
+                gl.use_program(Some(program));
+                gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(environment.brdf_lut), 0);
+                gl.viewport(0, 0, BRDF_LUT_SIZE, BRDF_LUT_SIZE);
+                gl.draw_arrays(glow::TRIANGLES, 0, 3);
+                gl.delete_program(program);
+                Ok(())
+            })();
+
+            // Back to the window for egui
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.bind_vertex_array(None);
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_vertex_array(vao);
+            gl.enable(glow::DEPTH_TEST);
+            result.map(|_| environment)
+        }
+    }
+
+    /// Bind the lighting maps to their units and set the PBR shader's environment uniforms
+    pub fn bind(&self, gl: &glow::Context, locations: &UniformLocations, settings: &EnvironmentSettings) {
+        unsafe {
+            bind_cubemap(gl, IRRADIANCE_UNIT, self.irradiance);
+            bind_cubemap(gl, PREFILTERED_UNIT, self.prefiltered);
+            gl.active_texture(glow::TEXTURE0 + BRDF_LUT_UNIT);
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.brdf_lut));
+            gl.active_texture(glow::TEXTURE0);
+
+            let location = |name: &str| locations.get(name);
+            gl.uniform_1_i32(location("u_has_environment"), 1);
+            gl.uniform_matrix_3_f32_slice(location("u_environment_rotation"), false, settings.rotation_matrix().as_slice());
+            gl.uniform_1_f32(location("u_environment_intensity"), settings.intensity());
+            gl.uniform_1_f32(location("u_prefiltered_max_lod"), (PREFILTERED_LEVELS - 1) as f32);
+        }
+    }
+
+    /// Draw the sky behind everything (call before the scene, depth test off)
+    pub fn draw_skybox(&self, gl: &glow::Context, program: glow::Program, locations: &UniformLocations, vao: glow::VertexArray, inverse_view_projection: &glm::Mat4, settings: &EnvironmentSettings) {
+        unsafe {
+            gl.use_program(Some(program));
+            bind_cubemap(gl, SKYBOX_UNIT, self.cubemap);
+            gl.active_texture(glow::TEXTURE0);
+
+            gl.uniform_matrix_4_f32_slice(locations.get("u_inverse_view_projection"), false, inverse_view_projection.as_slice());
+            gl.uniform_matrix_3_f32_slice(locations.get("u_environment_rotation"), false, settings.rotation_matrix().as_slice());
+            gl.uniform_1_f32(locations.get("u_environment_intensity"), settings.intensity());
+
+            gl.disable(glow::DEPTH_TEST);
+            gl.bind_vertex_array(Some(vao));
+            gl.draw_arrays(glow::TRIANGLES, 0, 3);
+            gl.bind_vertex_array(None);
+            gl.enable(glow::DEPTH_TEST);
+        }
+    }
+}
+
+/// Point the PBR program's samplers at the environment units (they never change) and mark it unlit by IBL
+pub fn init_pbr_program(gl: &glow::Context, program: glow::Program) {
+    unsafe {
+        gl.use_program(Some(program));
+        let location = |name: &str| gl.get_uniform_location(program, name);
+        gl.uniform_1_i32(location("u_irradiance_map").as_ref(), IRRADIANCE_UNIT as i32);
+        gl.uniform_1_i32(location("u_prefiltered_map").as_ref(), PREFILTERED_UNIT as i32);
+        gl.uniform_1_i32(location("u_brdf_lut").as_ref(), BRDF_LUT_UNIT as i32);
+        gl.uniform_1_i32(location("u_has_environment").as_ref(), 0);
+    }
+}
+
+/// Point the skybox program's sampler at its unit
+pub fn init_skybox_program(gl: &glow::Context, program: glow::Program) {
+    unsafe {
+        gl.use_program(Some(program));
+        let location = gl.get_uniform_location(program, "u_environment_map");
+        gl.uniform_1_i32(location.as_ref(), SKYBOX_UNIT as i32);
+    }
+}
+
+// Free GPU memory when another map is loaded or the environment is cleared
+impl Drop for Environment {
+    fn drop(&mut self) {
+        unsafe {
+            for texture in [self.cubemap, self.irradiance, self.prefiltered, self.brdf_lut] {
+                self.gl.delete_texture(texture);
+            }
+        }
+    }
+}
+
+/// Number of mip levels down to 1x1
+fn mip_count(size: i32) -> i32 {
+    32 - (size.max(1) as u32).leading_zeros() as i32
+}
+
+/// Empty RGBA16F cubemap with `levels` mips (trilinear filtering when mipmapped)
+unsafe fn create_cubemap(gl: &glow::Context, size: i32, levels: i32) -> Result<glow::Texture, String> {
+    let texture = gl.create_texture()?;
+    gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(texture));
+    for face in 0..6 {
+        for level in 0..levels {
+            let face_size = (size >> level).max(1);
+            gl.tex_image_2d(glow::TEXTURE_CUBE_MAP_POSITIVE_X + face, level, glow::RGBA16F as i32, face_size, face_size, 0, glow::RGBA, glow::FLOAT, glow::PixelUnpackData::Slice(None));
+        }
+    }
+    let min_filter = if levels > 1 { glow::LINEAR_MIPMAP_LINEAR } else { glow::LINEAR };
+    gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_MIN_FILTER, min_filter as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_MAX_LEVEL, levels - 1);
+    for wrap in [glow::TEXTURE_WRAP_S, glow::TEXTURE_WRAP_T, glow::TEXTURE_WRAP_R] {
+        gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, wrap, glow::CLAMP_TO_EDGE as i32);
+    }
+    Ok(texture)
+}
+
+/// Single-level 2D texture with linear filtering and clamped edges (left bound to TEXTURE_2D)
+unsafe fn create_texture_2d(gl: &glow::Context, internal_format: u32, width: i32, height: i32, format: u32, ty: u32, pixels: Option<&[u8]>) -> Result<glow::Texture, String> {
+    let texture = gl.create_texture()?;
+    gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);  // RGB float rows aren't padded
+    gl.tex_image_2d(glow::TEXTURE_2D, 0, internal_format as i32, width, height, 0, format, ty, glow::PixelUnpackData::Slice(pixels));
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+    Ok(texture)
+}
+
+unsafe fn bind_cubemap(gl: &glow::Context, unit: u32, texture: glow::Texture) {
+    gl.active_texture(glow::TEXTURE0 + unit);
+    gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(texture));
+}
+
+/// Run a bake program once per face of `target` at mip `level`; the source texture is already bound to unit 0
+/// `setup` sets the program's extra uniforms
+unsafe fn render_cube_faces(gl: &glow::Context, program: glow::Program, target: glow::Texture, size: i32, level: i32, setup: impl Fn(glow::Program)) {
+    gl.use_program(Some(program));
+    setup(program);
+    let u_face = gl.get_uniform_location(program, "u_face");
+    gl.viewport(0, 0, size, size);
+    for face in 0..6 {
+        gl.uniform_1_i32(u_face.as_ref(), face as i32);
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_CUBE_MAP_POSITIVE_X + face, Some(target), level);
+        gl.draw_arrays(glow::TRIANGLES, 0, 3);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_radiance_hdr_as_linear_floats() {
+        let path = std::env::temp_dir().join(format!("environment_test_{}.hdr", std::process::id()));
+        let pixels = [image::Rgb([1.0, 0.5, 0.25]), image::Rgb([4.0, 2.0, 0.0])];
+        let file = std::fs::File::create(&path).unwrap();
+        image::codecs::hdr::HdrEncoder::new(file).encode(&pixels, 2, 1).unwrap();
+
+        let image = read_hdr(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!((image.width, image.height), (2, 1));
+        // RGBE shares one exponent per pixel, so values above 1 survive
+        assert_eq!(&image.pixels[3..6], &[4.0, 2.0, 0.0]);
+        assert!(read_hdr(Path::new("does_not_exist.hdr")).is_err());
+    }
+
+    #[test]
+    fn exposure_and_rotation() {
+        let settings = EnvironmentSettings { exposure: 1.0, rotation: 90.0, show_skybox: true };
+        assert_eq!(settings.intensity(), 2.0);
+        // Turning the environment +90 degrees about Y means a +X lookup finds what used to be at +Z
+        let lookup = settings.rotation_matrix() * glm::Vec3::x();
+        assert!((lookup - glm::Vec3::z()).norm() < 1e-6);
+        assert_eq!(mip_count(512), 10);
+    }
+}
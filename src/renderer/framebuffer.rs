@@ -0,0 +1,279 @@
+// Offscreen render targets for the viewport - the HDR scene with depth, and color-only
+// targets for the passes that turn it into the texture egui shows
+// Optionally multisampled: the scene is drawn into MSAA renderbuffers and resolved into the texture
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::sync::Arc;    // Shared OpenGL context so the target can be freed on drop
+
+/// Texel format of a color target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    Rgba8,    // Display-ready, what egui shows
+    Rgba16F,  // Linear HDR - values above 1 survive for tone mapping and bloom
+}
+
+impl ColorFormat {
+    /// Internal format, then the pixel format and type to allocate it with
+    fn gl_formats(self) -> (u32, u32, u32) {
+        match self {
+            ColorFormat::Rgba8 => (glow::RGBA8, glow::RGBA, glow::UNSIGNED_BYTE),
+            ColorFormat::Rgba16F => (glow::RGBA16F, glow::RGBA, glow::HALF_FLOAT),
+        }
+    }
+}
+
+/// Multisampled attachments the scene is drawn into before the resolve
+struct Multisample {
+    framebuffer: glow::Framebuffer,
+    color: glow::Renderbuffer,
+    depth: glow::Renderbuffer,
+}
+
+/// HDR color texture + depth for the scene, resizable in place so the texture handle stays valid
+pub struct RenderTarget {
+    gl: Arc<glow::Context>,
+    framebuffer: glow::Framebuffer,  // Resolved result - what the post chain reads and readback reads
+    color: glow::Texture,            // RGBA16F, linear (the post chain encodes it for display)
+    depth: glow::Renderbuffer,       // Resolved too, for the depth probe
+    multisample: Option<Multisample>,
+    width: i32,
+    height: i32,
+    samples: i32,  // 1 = no MSAA
+}
+
+impl RenderTarget {
+    /// Allocate a target of the given size in pixels; `samples` above 1 adds MSAA (clamped to what the driver allows)
+    pub fn new(gl: Arc<glow::Context>, width: i32, height: i32, samples: i32) -> Result<Self, String> {
+        unsafe {
+            let framebuffer = gl.create_framebuffer()?;
+            let color = gl.create_texture()?;
+            let depth = gl.create_renderbuffer()?;
+            // Build the value first so Drop cleans up if anything below fails
+            let mut target = Self { gl, framebuffer, color, depth, multisample: None, width: 0, height: 0, samples: 0 };
+            target.resize(width, height, samples)?;
+            Ok(target)
+        }
+    }
+
+    /// Size in pixels
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// The resolved color, for the post chain (same handle for the target's whole life)
+    pub fn texture(&self) -> glow::Texture {
+        self.color
+    }
+
+    /// Reallocate the attachments if the size or sample count changed
+    pub fn resize(&mut self, width: i32, height: i32, samples: i32) -> Result<(), String> {
+        let (width, height) = (width.max(1), height.max(1));
+        let samples = samples.clamp(1, unsafe { self.gl.get_parameter_i32(glow::MAX_SAMPLES) }.max(1));
+        if (width, height, samples) == (self.width, self.height, self.samples) {
+            return Ok(());
+        }
+        let gl = &self.gl;
+        allocate_color(gl, self.color, ColorFormat::Rgba16F, width, height);
+        unsafe {
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(self.depth));
+            gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, width, height);
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+            attach(gl, self.framebuffer, |gl| {
+                gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(self.color), 0);
+                gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(self.depth));
+            }).map_err(|status| format!("Viewport framebuffer incomplete (status 0x{:X})", status))?;
+
+            if samples > 1 {
+                if self.multisample.is_none() {
+                    self.multisample = Some(Multisample {
+                        framebuffer: gl.create_framebuffer()?,
+                        color: gl.create_renderbuffer()?,
+                        depth: gl.create_renderbuffer()?,
+                    });
+                }
+                let Some(multisample) = self.multisample.as_ref() else { unreachable!() };
+                gl.bind_renderbuffer(glow::RENDERBUFFER, Some(multisample.color));
+                gl.renderbuffer_storage_multisample(glow::RENDERBUFFER, samples, glow::RGBA16F, width, height);
+                gl.bind_renderbuffer(glow::RENDERBUFFER, Some(multisample.depth));
+                gl.renderbuffer_storage_multisample(glow::RENDERBUFFER, samples, glow::DEPTH_COMPONENT24, width, height);
+                gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+                attach(gl, multisample.framebuffer, |gl| {
+                    gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(multisample.color));
+                    gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(multisample.depth));
+                }).map_err(|status| format!("Multisampled viewport framebuffer incomplete (status 0x{:X})", status))?;
+            } else if let Some(multisample) = self.multisample.take() {
+                delete_multisample(gl, multisample);
+            }
+        }
+        (self.width, self.height, self.samples) = (width, height, samples);
+        Ok(())
+    }
+
+    /// Bind for drawing, cover it with the viewport and clear to transparent (egui's panel shows through)
+    pub fn begin(&self) {
+        let framebuffer = self.multisample.as_ref().map_or(self.framebuffer, |multisample| multisample.framebuffer);
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            self.gl.viewport(0, 0, self.width, self.height);
+            self.gl.disable(glow::SCISSOR_TEST);  // egui may leave a clip rect set
+            self.gl.depth_mask(true);
+            self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            self.gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+        }
+    }
+
+    /// Resolve MSAA drawing into the texture; leaves the resolved framebuffer bound for readback
+    pub fn resolve(&self) {
+        unsafe {
+            if let Some(multisample) = &self.multisample {
+                self.gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(multisample.framebuffer));
+                self.gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(self.framebuffer));
+                // Depth can't be filtered, so it gets its own NEAREST blit
+                self.gl.blit_framebuffer(0, 0, self.width, self.height, 0, 0, self.width, self.height, glow::COLOR_BUFFER_BIT, glow::LINEAR);
+                self.gl.blit_framebuffer(0, 0, self.width, self.height, 0, 0, self.width, self.height, glow::DEPTH_BUFFER_BIT, glow::NEAREST);
+            }
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+        }
+    }
+
+    /// Depth (0..1, 1 = nothing drawn) at a pixel in bottom-up coordinates; call after `resolve`
+    pub fn read_depth(&self, x: i32, y: i32) -> f32 {
+        let mut depth = [0u8; 4];  // One f32
+        let (x, y) = (x.clamp(0, self.width - 1), y.clamp(0, self.height - 1));
+        unsafe {
+            self.gl.read_pixels(x, y, 1, 1, glow::DEPTH_COMPONENT, glow::FLOAT, glow::PixelPackData::Slice(Some(&mut depth)));
+        }
+        f32::from_ne_bytes(depth)
+    }
+}
+
+/// Everything one viewport renders into
+pub struct ViewportTargets {
+    pub scene: RenderTarget,                // HDR scene and depth
+    pub bloom: Option<[ColorTarget; 2]>,    // Half-size ping-pong for the bloom blur, only while bloom is on
+    pub output: ColorTarget,                // End of the post chain, display-ready
+    pub fxaa: Option<ColorTarget>,          // Only while FXAA is on
+}
+
+impl ViewportTargets {
+    pub fn new(gl: Arc<glow::Context>, width: i32, height: i32, samples: i32) -> Result<Self, String> {
+        Ok(Self {
+            scene: RenderTarget::new(gl.clone(), width, height, samples)?,
+            bloom: None,
+            output: ColorTarget::new(gl, width, height, ColorFormat::Rgba8)?,
+            fxaa: None,
+        })
+    }
+}
+
+/// Color only - the output of a full-screen pass over another target's texture
+pub struct ColorTarget {
+    gl: Arc<glow::Context>,
+    framebuffer: glow::Framebuffer,
+    color: glow::Texture,
+    format: ColorFormat,
+    width: i32,
+    height: i32,
+}
+
+impl ColorTarget {
+    pub fn new(gl: Arc<glow::Context>, width: i32, height: i32, format: ColorFormat) -> Result<Self, String> {
+        unsafe {
+            let framebuffer = gl.create_framebuffer()?;
+            let color = gl.create_texture()?;
+            let mut target = Self { gl, framebuffer, color, format, width: 0, height: 0 };
+            target.resize(width, height)?;
+            Ok(target)
+        }
+    }
+
+    /// Size in pixels
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// The color texture, for egui or the next pass (same handle for the target's whole life)
+    pub fn texture(&self) -> glow::Texture {
+        self.color
+    }
+
+    /// Reallocate the texture if the size changed
+    pub fn resize(&mut self, width: i32, height: i32) -> Result<(), String> {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.width, self.height) {
+            return Ok(());
+        }
+        allocate_color(&self.gl, self.color, self.format, width, height);
+        unsafe {
+            attach(&self.gl, self.framebuffer, |gl| {
+                gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(self.color), 0);
+            }).map_err(|status| format!("Post-process framebuffer incomplete (status 0x{:X})", status))?;
+        }
+        (self.width, self.height) = (width, height);
+        Ok(())
+    }
+
+    /// Bind for drawing and cover it with the viewport - passes overwrite every pixel, so no clear
+    pub fn bind(&self) {
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+            self.gl.viewport(0, 0, self.width, self.height);
+            self.gl.disable(glow::SCISSOR_TEST);
+        }
+    }
+}
+
+impl Drop for ColorTarget {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_framebuffer(self.framebuffer);
+            self.gl.delete_texture(self.color);
+        }
+    }
+}
+
+/// (Re)allocate a color texture, filtered linearly and clamped at the edges
+fn allocate_color(gl: &glow::Context, texture: glow::Texture, format: ColorFormat, width: i32, height: i32) {
+    let (internal, pixel_format, pixel_type) = format.gl_formats();
+    unsafe {
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(glow::TEXTURE_2D, 0, internal as i32, width, height, 0, pixel_format, pixel_type, glow::PixelUnpackData::Slice(None));
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);  // egui and the post passes sample between texels
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+    }
+}
+
+/// Set a framebuffer's attachments and check it; Err carries the status
+fn attach(gl: &glow::Context, framebuffer: glow::Framebuffer, attachments: impl FnOnce(&glow::Context)) -> Result<(), u32> {
+    unsafe {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        attachments(gl);
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        if status == glow::FRAMEBUFFER_COMPLETE { Ok(()) } else { Err(status) }
+    }
+}
+
+fn delete_multisample(gl: &glow::Context, multisample: Multisample) {
+    unsafe {
+        gl.delete_framebuffer(multisample.framebuffer);
+        gl.delete_renderbuffer(multisample.color);
+        gl.delete_renderbuffer(multisample.depth);
+    }
+}
+
+// Free GPU memory when the renderer goes away
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        if let Some(multisample) = self.multisample.take() {
+            delete_multisample(&self.gl, multisample);
+        }
+        unsafe {
+            self.gl.delete_framebuffer(self.framebuffer);
+            self.gl.delete_texture(self.color);
+            self.gl.delete_renderbuffer(self.depth);
+        }
+    }
+}
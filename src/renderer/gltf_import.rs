@@ -0,0 +1,364 @@
+// glTF 2.0 importer - flattens a .gltf/.glb node hierarchy into placed meshes
+use nalgebra_glm as glm;  // Matrices for the node hierarchy
+use std::fmt;             // Display trait for readable errors
+use std::path::Path;      // Borrowed file path
+
+use super::mesh::{MeshData, PrimitiveMode};
+use super::pbr::PbrMaterial;
+use super::texture::{SamplerSettings, TextureImage, TextureSlot};
+
+/// Everything that can go wrong while importing a glTF file
+#[derive(Debug)]
+pub enum GltfError {
+    /// The gltf crate could not read the file, its buffers or its images
+    Import(gltf::Error),
+    /// A primitive without the mandatory POSITION attribute
+    MissingPositions { mesh: String },
+    /// An index points past the end of the primitive's vertices
+    IndexOutOfRange { mesh: String, index: u32, vertex_count: usize },
+    /// A vertex attribute has a different number of entries than POSITION
+    AttributeCount { mesh: String, attribute: &'static str, count: usize, vertex_count: usize },
+    /// The file has no scene or no meshes to show
+    Empty,
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GltfError::Import(e) => write!(f, "{}", e),
+            GltfError::MissingPositions { mesh } => write!(f, "mesh '{}' has a primitive without positions", mesh),
+            GltfError::IndexOutOfRange { mesh, index, vertex_count } => {
+                write!(f, "mesh '{}': index {} out of range ({} vertices)", mesh, index, vertex_count)
+            }
+            GltfError::AttributeCount { mesh, attribute, count, vertex_count } => {
+                write!(f, "mesh '{}': {} {} values for {} vertices", mesh, count, attribute, vertex_count)
+            }
+            GltfError::Empty => write!(f, "file contains no meshes"),
+        }
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+// From lets the ? operator convert gltf errors automatically
+impl From<gltf::Error> for GltfError {
+    fn from(e: gltf::Error) -> Self {
+        GltfError::Import(e)
+    }
+}
+
+/// Texture of a material slot: one of the scene's images, and how to sample it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GltfTexture {
+    pub image: usize,              // Index into GltfScene::images
+    pub sampler: SamplerSettings,
+}
+
+/// One primitive placed in the world by its node
+#[derive(Debug, Clone)]
+pub struct GltfObject {
+    pub name: String,               // "Node/Mesh" path for display
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 3]>,      // Base color factor times COLOR_0 (if present)
+    pub uvs: Option<Vec<[f32; 2]>>, // TEXCOORD_0 if present, flipped to v-up like OBJ
+    pub tangents: Option<Vec<[f32; 4]>>,  // TANGENT if present (otherwise generated from the UVs)
+    pub indices: Vec<u32>,          // Vertex order for `mode`
+    pub mode: PrimitiveMode,        // Triangles, strips, lines or points
+    pub material: PbrMaterial,      // Metallic-roughness factors of the primitive's material
+    pub textures: Vec<(TextureSlot, GltfTexture)>,  // The material's textures that use TEXCOORD_0
+    pub parent: glm::Mat4,          // World transform of the node's parents (identity at the root)
+    pub local: glm::Mat4,           // The node's own transform
+}
+
+impl GltfObject {
+    /// Node's world transform (parents applied)
+    pub fn transform(&self) -> glm::Mat4 {
+        self.parent * self.local
+    }
+
+    /// Interleave the attributes into a mesh ready for upload
+    pub fn mesh_data(&self) -> MeshData {
+        MeshData::from_streams(
+            &self.positions,
+            &self.normals,
+            Some(&self.colors),
+            self.uvs.as_deref(),  // Option<Vec<T>> -> Option<&[T]>
+            self.tangents.as_deref(),
+            self.indices.clone(),
+            self.mode,
+        )
+    }
+}
+
+/// Result of importing a glTF file
+#[derive(Debug, Clone)]
+pub struct GltfScene {
+    pub objects: Vec<GltfObject>,
+    pub images: Vec<TextureImage>,  // In file order (float images become checkerboards)
+}
+
+impl GltfScene {
+    /// Total number of triangles across all objects (lines and points don't count)
+    pub fn triangle_count(&self) -> usize {
+        // From the index counts - building MeshData here would redo the interleaving and tangents
+        self.objects.iter().map(|o| o.mode.triangle_count(o.indices.len())).sum()
+    }
+}
+
+/// Import a .gltf (embedded or external buffers) or binary .glb file
+pub fn load(path: &Path) -> Result<GltfScene, GltfError> {
+    // gltf::import resolves data: URIs, external .bin files and the GLB binary chunk for us
+    let (document, buffers, images) = gltf::import(path)?;
+
+    // Use the default scene, or the first one if the file doesn't name a default
+    let scene = document.default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or(GltfError::Empty)?;
+
+    let mut objects = Vec::new();
+    for node in scene.nodes() {
+        visit_node(&node, &glm::Mat4::identity(), &buffers, &mut objects)?;
+    }
+
+    if objects.is_empty() {
+        return Err(GltfError::Empty);
+    }
+    let images = images.iter()
+        .map(|data| TextureImage::from_gltf(data).unwrap_or_else(TextureImage::checkerboard))
+        .collect();
+    Ok(GltfScene { objects, images })
+}
+
+/// Walk the node hierarchy depth-first, accumulating transforms from the root
+fn visit_node(
+    node: &gltf::Node,
+    parent_transform: &glm::Mat4,
+    buffers: &[gltf::buffer::Data],
+    objects: &mut Vec<GltfObject>,
+) -> Result<(), GltfError> {
+    // matrix() returns column-major [[f32; 4]; 4], the same layout nalgebra uses
+    let local = glm::Mat4::from(node.transform().matrix());
+    let world = parent_transform * local;
+
+    if let Some(mesh) = node.mesh() {
+        let node_name = node.name().map(str::to_string).unwrap_or_else(|| format!("Node {}", node.index()));
+        let mesh_name = mesh.name().map(str::to_string).unwrap_or_else(|| format!("Mesh {}", mesh.index()));
+
+        for primitive in mesh.primitives() {
+            let mode = primitive_mode(primitive.mode());
+
+            // The reader looks up accessor data in the already loaded buffers
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data.0[..]));
+
+            let positions: Vec<[f32; 3]> = reader.read_positions()
+                .ok_or_else(|| GltfError::MissingPositions { mesh: mesh_name.clone() })?
+                .collect();
+
+            // Non-indexed primitives draw vertices in order
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+            // gltf::import doesn't check indices against the vertex count - a bad one would panic below
+            if let Some(&index) = indices.iter().find(|&&index| index as usize >= positions.len()) {
+                return Err(GltfError::IndexOutOfRange { mesh: mesh_name.clone(), index, vertex_count: positions.len() });
+            }
+
+            // Only triangles have a surface to derive normals from
+            let normals: Vec<[f32; 3]> = match reader.read_normals() {
+                Some(normals) => normals.collect(),
+                None if mode == PrimitiveMode::Triangles => smooth_normals(&positions, &indices),
+                None => vec![[0.0, 1.0, 0.0]; positions.len()],
+            };
+            // glTF's v runs down the image; ours runs up (see Texture::new)
+            let uvs: Option<Vec<[f32; 2]>> = reader.read_tex_coords(0).map(|uvs| uvs.into_f32().map(|[u, v]| [u, 1.0 - v]).collect());
+            // Bitangents are defined in world space (toward the image's top), so the flip above doesn't affect them
+            let tangents: Option<Vec<[f32; 4]>> = reader.read_tangents().map(|tangents| tangents.collect());
+
+            // Final color = material base color * optional per-vertex color
+            let base = primitive.material().pbr_metallic_roughness().base_color_factor();
+            let colors: Vec<[f32; 3]> = match reader.read_colors(0) {
+                Some(colors) => colors.into_rgb_f32()
+                    .map(|c| [c[0] * base[0], c[1] * base[1], c[2] * base[2]])
+                    .collect(),
+                None => vec![[base[0], base[1], base[2]]; positions.len()],
+            };
+
+            // Every stream is indexed like POSITION, so all must have one entry per vertex
+            let counts = [
+                ("NORMAL", Some(normals.len())),
+                ("TEXCOORD_0", uvs.as_ref().map(Vec::len)),
+                ("TANGENT", tangents.as_ref().map(Vec::len)),
+                ("COLOR_0", Some(colors.len())),
+            ];
+            for (attribute, count) in counts {
+                if let Some(count) = count.filter(|&count| count != positions.len()) {
+                    return Err(GltfError::AttributeCount { mesh: mesh_name.clone(), attribute, count, vertex_count: positions.len() });
+                }
+            }
+
+            objects.push(GltfObject {
+                name: format!("{}/{}", node_name, mesh_name),
+                positions,
+                normals,
+                colors,
+                uvs,
+                tangents,
+                indices,
+                mode,
+                material: PbrMaterial::from_gltf(&primitive.material()),
+                textures: material_textures(&primitive.material()),
+                parent: *parent_transform,
+                local,
+            });
+        }
+    }
+
+    // Recurse into children with this node's world transform as their parent
+    for child in node.children() {
+        visit_node(&child, &world, buffers, objects)?;
+    }
+    Ok(())
+}
+
+/// Textures of a material, by slot - only those on TEXCOORD_0, the one UV set imported
+fn material_textures(material: &gltf::Material) -> Vec<(TextureSlot, GltfTexture)> {
+    let pbr = material.pbr_metallic_roughness();
+    let slots = [
+        (TextureSlot::BaseColor, pbr.base_color_texture().map(|info| (info.texture(), info.tex_coord()))),
+        (TextureSlot::MetallicRoughness, pbr.metallic_roughness_texture().map(|info| (info.texture(), info.tex_coord()))),
+        (TextureSlot::Occlusion, material.occlusion_texture().map(|info| (info.texture(), info.tex_coord()))),
+        (TextureSlot::Emissive, material.emissive_texture().map(|info| (info.texture(), info.tex_coord()))),
+        (TextureSlot::Normal, material.normal_texture().map(|info| (info.texture(), info.tex_coord()))),
+    ];
+    slots.into_iter()
+        .filter_map(|(slot, texture)| match texture? {
+            (texture, 0) => Some((slot, GltfTexture {
+                image: texture.source().index(),
+                sampler: SamplerSettings::from_gltf(&texture.sampler()),
+            })),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Map glTF primitive modes onto ours (they are the same set as OpenGL's)
+fn primitive_mode(mode: gltf::mesh::Mode) -> PrimitiveMode {
+    match mode {
+        gltf::mesh::Mode::Points => PrimitiveMode::Points,
+        gltf::mesh::Mode::Lines => PrimitiveMode::Lines,
+        gltf::mesh::Mode::LineLoop => PrimitiveMode::LineLoop,
+        gltf::mesh::Mode::LineStrip => PrimitiveMode::LineStrip,
+        gltf::mesh::Mode::Triangles => PrimitiveMode::Triangles,
+        gltf::mesh::Mode::TriangleStrip => PrimitiveMode::TriangleStrip,
+        gltf::mesh::Mode::TriangleFan => PrimitiveMode::TriangleFan,
+    }
+}
+
+/// Average face normals around each vertex (glTF says to use flat normals, but smooth reads better)
+fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![glm::Vec3::zeros(); positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| glm::Vec3::from(positions[i as usize]));
+        let face = (b - a).cross(&(c - a));  // Area-weighted face normal
+        for &i in triangle {
+            normals[i as usize] += face;
+        }
+    }
+    normals.iter()
+        .map(|n| if n.norm() > 0.0 { n.normalize().into() } else { [0.0, 1.0, 0.0] })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::texture::{Filter, Wrap};
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+    }
+
+    /// All three container flavours hold the same scene
+    fn check_sample_scene(scene: &GltfScene) {
+        let names: Vec<&str> = scene.objects.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["Child/Triangle", "Sibling/Unlit"]);
+        assert_eq!(scene.triangle_count(), 2);
+
+        // Child is scaled by 2 inside a parent translated by +1 on X
+        let child = &scene.objects[0];
+        let corner = child.transform() * glm::vec4(1.0, 0.0, 0.0, 1.0);
+        assert_eq!(corner, glm::vec4(3.0, 0.0, 0.0, 1.0));
+        // The parent's part stays separate from the node's own (editable) scale
+        assert_eq!(child.parent, glm::translation(&glm::vec3(1.0, 0.0, 0.0)));
+        assert_eq!(child.colors[0], [1.0, 0.0, 0.0]);
+        assert_eq!(child.indices, [0, 1, 2]);
+        // Factors left out of the file take glTF's defaults
+        assert_eq!((child.material.metallic, child.material.roughness), (1.0, 1.0));
+
+        // Sibling has no indices or normals - both are generated
+        let sibling = &scene.objects[1];
+        assert_eq!(sibling.transform(), glm::Mat4::identity());
+        assert_eq!(sibling.indices, [0, 1, 2]);
+        assert_eq!(sibling.normals[0], [0.0, 0.0, 1.0]);
+        assert_eq!(sibling.colors[2], [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn imports_embedded_buffers() {
+        check_sample_scene(&load(&fixture("triangle_embedded.gltf")).unwrap());
+    }
+
+    #[test]
+    fn imports_external_buffers() {
+        check_sample_scene(&load(&fixture("triangle_external.gltf")).unwrap());
+    }
+
+    #[test]
+    fn imports_binary_glb() {
+        check_sample_scene(&load(&fixture("triangle.glb")).unwrap());
+    }
+
+    #[test]
+    fn imports_textures_and_samplers() {
+        let scene = load(&fixture("textured.gltf")).unwrap();
+        assert_eq!(scene.images.len(), 1);
+        assert_eq!((scene.images[0].width, scene.images[0].height), (2, 1));
+        assert_eq!(scene.images[0].pixels[..4], [255, 0, 0, 255]);
+
+        // The occlusion texture is on TEXCOORD_1, which isn't imported
+        let object = &scene.objects[0];
+        assert_eq!(object.textures.len(), 1);
+        let (slot, texture) = object.textures[0];
+        assert_eq!(slot, TextureSlot::BaseColor);
+        assert_eq!(texture.image, 0);
+        assert_eq!(texture.sampler.wrap, Wrap::ClampToEdge);
+        assert_eq!(texture.sampler.filter, Filter::Nearest);
+
+        assert_eq!(object.uvs.as_ref().unwrap()[2], [0.0, 0.0]);  // v = 1 flipped to 0
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        match load(&fixture("bad_index.gltf")).unwrap_err() {
+            GltfError::IndexOutOfRange { index, vertex_count, .. } => assert_eq!((index, vertex_count), (5, 3)),
+            other => panic!("expected index error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_short_attribute_streams() {
+        match load(&fixture("short_normals.gltf")).unwrap_err() {
+            GltfError::AttributeCount { attribute, count, vertex_count, .. } => assert_eq!((attribute, count, vertex_count), ("NORMAL", 2, 3)),
+            other => panic!("expected attribute count error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let err = load(&fixture("does_not_exist.gltf")).unwrap_err();
+        assert!(matches!(err, GltfError::Import(_)));
+    }
+}
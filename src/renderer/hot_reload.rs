@@ -0,0 +1,240 @@
+// Shader sources on disk - watched for edits and recompiled while the app runs
+// A program that fails to compile keeps drawing with its last good version, and the
+// driver's log is turned into file:line errors for the viewport overlay
+use notify::Watcher;  // Trait for watch()
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;  // Watcher thread -> UI thread
+
+use super::preprocess::{AssembledSource, PreprocessError};
+use super::shader::{ProgramError, ShaderStage};
+use super::{camera_block, lighting, pbr, shader, shadows};
+
+/// The editable copies: the shaders/ folder of the source tree this binary was built from
+pub const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/shaders");
+
+/// The shader folder, if it exists (an installed binary without it uses the embedded sources)
+pub fn shader_dir() -> Option<PathBuf> {
+    let dir = Path::new(SHADER_DIR);
+    dir.is_dir().then(|| dir.to_path_buf())
+}
+
+/// One shader source file, plus the copy compiled into the binary
+#[derive(Debug, Clone, Copy)]
+pub struct ShaderFile {
+    pub name: &'static str,      // File name within the shader folder
+    pub embedded: &'static str,  // include_str! of the same file at build time
+}
+
+/// Every file in the shader folder, including the ones only reached through #include
+pub const SHADER_FILES: [ShaderFile; 7] = [
+    ShaderFile { name: "mesh.vert", embedded: shader::VERTEX_SHADER_SOURCE },
+    ShaderFile { name: "blinn_phong.frag", embedded: shader::FRAGMENT_SHADER_SOURCE },
+    ShaderFile { name: "pbr.frag", embedded: pbr::PBR_FRAGMENT_SHADER_SOURCE },
+    ShaderFile { name: "camera.glsl", embedded: camera_block::CAMERA_GLSL },
+    ShaderFile { name: "lighting.glsl", embedded: lighting::LIGHTS_GLSL },
+    ShaderFile { name: "shadows.glsl", embedded: shadows::SHADOWS_GLSL },
+    ShaderFile { name: "normal_mapping.glsl", embedded: shader::NORMAL_MAPPING_GLSL },
+];
+
+/// Find a shader by name in the first folder of `search_path` that has it, then among the embedded copies
+pub fn read_shader(search_path: &[PathBuf], name: &str) -> Option<String> {
+    search_path.iter()
+        .find_map(|dir| std::fs::read_to_string(dir.join(name)).ok())
+        .or_else(|| SHADER_FILES.iter().find(|file| file.name == name).map(|file| file.embedded.to_string()))
+}
+
+/// One line of a compiler log, with the line it points at when the driver gave one
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub line: Option<usize>,  // In the assembled source
+    pub message: String,
+}
+
+/// Split a driver info log into messages, pulling out line numbers in the common formats:
+/// Mesa `0:12(5): error: ...`, NVIDIA `0(12) : error C1008: ...`, AMD/Intel/Apple `ERROR: 0:12: ...`
+pub fn parse_log(log: &str) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+    for text in log.lines().map(str::trim).filter(|text| !text.is_empty()) {
+        // Severity first on AMD-style logs - moved after the location so every format reads the same
+        let (severity, rest) = match text.split_once(": ") {
+            Some((severity @ ("ERROR" | "WARNING"), rest)) => (Some(severity.to_lowercase()), rest),
+            _ => (None, text),
+        };
+        let entry = match parse_location(rest) {
+            Some((line, message)) => LogEntry {
+                line: Some(line),
+                message: match severity {
+                    Some(severity) => format!("{}: {}", severity, message),
+                    None => message.to_string(),
+                },
+            },
+            None => LogEntry { line: None, message: text.to_string() },
+        };
+        entries.push(entry);
+    }
+    entries
+}
+
+/// `<source>:<line>[(<column>)]: message` or `<source>(<line>) : message` -> (line, message)
+fn parse_location(text: &str) -> Option<(usize, &str)> {
+    let (_source, rest) = leading_number(text)?;
+    let (line, rest) = if let Some(rest) = rest.strip_prefix(':') {
+        let (line, rest) = leading_number(rest)?;
+        // Mesa adds a column in parentheses
+        let rest = match rest.strip_prefix('(') {
+            Some(column) => column.split_once(')')?.1,
+            None => rest,
+        };
+        (line, rest)
+    } else {
+        let (line, rest) = leading_number(rest.strip_prefix('(')?)?;
+        (line, rest.strip_prefix(')')?)
+    };
+    let message = rest.trim_start().strip_prefix(':')?.trim();
+    Some((line, message))
+}
+
+/// Split off the digits at the start of `text`
+fn leading_number(text: &str) -> Option<(usize, &str)> {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    Some((text[..digits].parse().ok()?, &text[digits..]))
+}
+
+/// A program that failed to build, with its log mapped back to the files
+#[derive(Debug, Clone)]
+pub struct ShaderError {
+    pub program: String,  // Display name, e.g. "PBR (SHADOWS)"
+    pub stage: ShaderStage,
+    pub lines: Vec<ErrorLine>,
+}
+
+/// One message of a failed build
+#[derive(Debug, Clone)]
+pub struct ErrorLine {
+    pub location: Option<(String, usize)>,  // File and line within it
+    pub message: String,
+    pub code: Option<String>,               // The line it points at, trimmed
+}
+
+impl ShaderError {
+    /// Map a compile or link log to the sources it came from
+    pub fn new(program: String, error: &ProgramError, vertex: &AssembledSource, fragment: &AssembledSource) -> Self {
+        let source = match error.stage {
+            ShaderStage::Vertex => Some(vertex),
+            ShaderStage::Fragment => Some(fragment),
+            ShaderStage::Geometry | ShaderStage::Link => None,  // Not from these files / could be either - show the log as it is
+        };
+        let lines = parse_log(&error.log).into_iter().map(|entry| {
+            let located = source.zip(entry.line).and_then(|(source, line)| {
+                let (file, file_line) = source.map.locate(line)?;
+                Some(((file.to_string(), file_line), source.line(line).map(|code| code.trim().to_string())))
+            });
+            ErrorLine {
+                location: located.as_ref().map(|(location, _)| location.clone()),
+                message: entry.message,
+                code: located.and_then(|(_, code)| code),
+            }
+        }).collect();
+        Self { program, stage: error.stage, lines }
+    }
+
+    /// A shader that couldn't be assembled from its files (`stage` is the one being assembled)
+    pub fn from_preprocess(program: String, stage: ShaderStage, error: &PreprocessError) -> Self {
+        let line = ErrorLine { location: error.location().cloned(), message: format!("error: {}", error), code: None };
+        Self { program, stage, lines: vec![line] }
+    }
+
+    /// First message, for the status bar
+    pub fn summary(&self) -> String {
+        match self.lines.first() {
+            Some(ErrorLine { location: Some((file, line)), message, .. }) => format!("{}:{}: {}", file, line, message),
+            Some(line) => line.message.clone(),
+            None => format!("{} failed", self.stage.name()),
+        }
+    }
+}
+
+/// Watches the shader folder on a background thread and queues the names of changed files
+pub struct ShaderWatcher {
+    _watcher: notify::RecommendedWatcher,  // Stops watching when dropped
+    changes: mpsc::Receiver<PathBuf>,
+}
+
+impl ShaderWatcher {
+    /// Start watching `dir`; `on_change` runs on the watcher's thread after each event (use it to wake the UI)
+    pub fn new(dir: &Path, on_change: impl Fn() + Send + 'static) -> Result<Self, String> {
+        let (sender, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            // Editors save by writing in place or by renaming a temporary file over the original
+            if matches!(event.kind, notify::EventKind::Modify(_) | notify::EventKind::Create(_)) {
+                for path in event.paths {
+                    let _ = sender.send(path);  // Only fails once the renderer is gone
+                }
+                on_change();
+            }
+        }).map_err(|e| e.to_string())?;
+        watcher.watch(dir, notify::RecursiveMode::NonRecursive).map_err(|e| e.to_string())?;
+        Ok(Self { _watcher: watcher, changes })
+    }
+
+    /// Names of the files changed since the last call, each once
+    pub fn changed_files(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.changes.try_iter()
+            .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::preprocess;
+
+    #[test]
+    fn parses_line_numbers_from_common_driver_logs() {
+        let mesa = parse_log("0:12(5): error: `foo' undeclared\n");
+        assert_eq!(mesa, vec![LogEntry { line: Some(12), message: "error: `foo' undeclared".to_string() }]);
+
+        let nvidia = parse_log("0(7) : error C1008: undefined variable \"foo\"");
+        assert_eq!(nvidia[0].line, Some(7));
+        assert_eq!(nvidia[0].message, "error C1008: undefined variable \"foo\"");
+
+        let amd = parse_log("ERROR: 0:3: 'foo' : undeclared identifier\nERROR: 1 compilation errors.  No code generated.");
+        assert_eq!(amd[0], LogEntry { line: Some(3), message: "error: 'foo' : undeclared identifier".to_string() });
+        assert_eq!(amd[1].line, None);  // Kept, just without a location
+    }
+
+    #[test]
+    fn maps_errors_to_the_included_file() {
+        let read = |name: &str| match name {
+            "test.frag" => Some("#version 330 core\n#include \"common.glsl\"\nvoid main() {}\n".to_string()),
+            "common.glsl" => Some("// common\n    oops;\n".to_string()),
+            _ => None,
+        };
+        let fragment = preprocess::preprocess("test.frag", &["SHADOWS"], &read).unwrap();
+        let vertex = AssembledSource { text: String::new(), map: Default::default() };
+
+        // Line 4 of the flattened text: version, define, comment, then the broken line
+        let error = ProgramError { stage: ShaderStage::Fragment, log: "0:4(5): error: syntax error".to_string() };
+        let error = ShaderError::new("Test".to_string(), &error, &vertex, &fragment);
+        assert_eq!(error.lines[0].location, Some(("common.glsl".to_string(), 2)));
+        assert_eq!(error.lines[0].code.as_deref(), Some("oops;"));
+        assert_eq!(error.summary(), "common.glsl:2: error: syntax error");
+    }
+
+    #[test]
+    fn falls_back_to_the_embedded_source() {
+        assert_eq!(read_shader(&[], "pbr.frag").as_deref(), Some(pbr::PBR_FRAGMENT_SHADER_SOURCE));
+        assert_eq!(read_shader(&[PathBuf::from("/nonexistent")], "shadows.glsl").as_deref(), Some(shadows::SHADOWS_GLSL));
+        assert_eq!(read_shader(&[], "missing.glsl"), None);
+        // The repository's folder is the one the embedded copies came from
+        let search_path: Vec<PathBuf> = shader_dir().into_iter().collect();
+        for file in SHADER_FILES {
+            assert_eq!(read_shader(&search_path, file.name).as_deref(), Some(file.embedded), "{}", file.name);
+        }
+    }
+}
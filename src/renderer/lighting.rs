@@ -0,0 +1,230 @@
+// Lights and materials for the lit shaders - uploaded as plain uniforms every draw
+use glow::HasContext;     // Trait providing OpenGL function methods
+use nalgebra_glm as glm;  // Light positions and directions
+
+use super::environment::EnvironmentSettings;
+use super::shadows::ShadowSettings;
+use super::uniforms::UniformLocations;
+
+/// Most lights the shader loops over (must match MAX_LIGHTS in LIGHTS_GLSL)
+pub const MAX_LIGHTS: usize = 8;
+
+/// Light struct, uniforms and falloff shared by the lit fragment shaders (#include "lighting.glsl")
+pub const LIGHTS_GLSL: &str = include_str!("../../shaders/lighting.glsl");
+
+/// How a light emits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightKind {
+    Directional,  // Parallel rays (sun), position ignored
+    Point,        // All directions from a position
+    Spot,         // A cone from a position
+}
+
+impl LightKind {
+    pub const ALL: [LightKind; 3] = [LightKind::Directional, LightKind::Point, LightKind::Spot];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightKind::Directional => "Directional",
+            LightKind::Point => "Point",
+            LightKind::Spot => "Spot",
+        }
+    }
+
+    /// Value of the `kind` field in the shader's Light struct
+    fn shader_id(&self) -> i32 {
+        match self {
+            LightKind::Directional => 0,
+            LightKind::Point => 1,
+            LightKind::Spot => 2,
+        }
+    }
+}
+
+/// One light source, in world space (not affected by the animation spin)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub kind: LightKind,
+    pub enabled: bool,
+    pub color: [f32; 3],      // Linear RGB
+    pub intensity: f32,       // Multiplies color
+    pub position: glm::Vec3,  // Point and spot
+    pub direction: glm::Vec3, // Directional and spot: the way the light travels (need not be normalized)
+    pub range: f32,           // Point and spot: distance where the light fades to nothing
+    pub inner_angle: f32,     // Spot: full strength inside this half-angle (degrees)
+    pub outer_angle: f32,     // Spot: no light outside this half-angle (degrees)
+}
+
+impl Light {
+    /// White sun shining along `direction`
+    pub fn directional(direction: glm::Vec3, intensity: f32) -> Self {
+        Self {
+            kind: LightKind::Directional,
+            enabled: true,
+            color: [1.0, 1.0, 1.0],
+            intensity,
+            position: glm::vec3(0.0, 3.0, 0.0),
+            direction,
+            range: 10.0,
+            inner_angle: 20.0,
+            outer_angle: 30.0,
+        }
+    }
+
+    /// New light of a kind, placed above and in front of the origin
+    pub fn new(kind: LightKind) -> Self {
+        Self {
+            kind,
+            position: glm::vec3(2.0, 3.0, 2.0),
+            ..Self::directional(glm::vec3(-2.0, -3.0, -2.0), 1.0)
+        }
+    }
+
+    /// Cosines of the spot cone half-angles (inner, outer) - the shader compares against dot products
+    pub fn cone_cosines(&self) -> (f32, f32) {
+        let outer = self.outer_angle.clamp(0.0, 90.0);
+        let inner = self.inner_angle.clamp(0.0, outer);  // Inner cone can't be wider than the outer one
+        (inner.to_radians().cos(), outer.to_radians().cos())
+    }
+}
+
+/// Surface response of an object
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    pub diffuse: [f32; 3],   // Multiplies the vertex color
+    pub specular: [f32; 3],  // Highlight color
+    pub shininess: f32,      // Blinn-Phong exponent - higher is a tighter highlight
+    pub emissive: [f32; 3],  // Added regardless of lighting
+}
+
+impl Default for Material {
+    /// Vertex color as-is with a soft white highlight
+    fn default() -> Self {
+        Self {
+            diffuse: [1.0, 1.0, 1.0],
+            specular: [0.25, 0.25, 0.25],
+            shininess: 32.0,
+            emissive: [0.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl Material {
+    /// Set the `u_material` struct uniform of the program in use
+    pub fn upload(&self, gl: &glow::Context, locations: &UniformLocations) {
+        unsafe {
+            let location = |name: &str| locations.get(name);
+            gl.uniform_3_f32_slice(location("u_material.diffuse"), &self.diffuse);
+            gl.uniform_3_f32_slice(location("u_material.specular"), &self.specular);
+            gl.uniform_1_f32(location("u_material.shininess"), self.shininess.max(1.0));
+            gl.uniform_3_f32_slice(location("u_material.emissive"), &self.emissive);
+        }
+    }
+}
+
+/// Ambient term plus the light list
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lighting {
+    pub ambient: [f32; 3],
+    pub lights: Vec<Light>,  // Only the first MAX_LIGHTS enabled lights are used
+    pub environment: EnvironmentSettings,  // Exposure/rotation of the HDR environment, if one is loaded
+    pub shadows: ShadowSettings,           // Shadow map for the first directional light
+}
+
+impl Default for Lighting {
+    /// Matches the old baked-in light: 30% ambient plus a 70% light from the upper right
+    fn default() -> Self {
+        Self {
+            ambient: [0.3, 0.3, 0.3],
+            lights: vec![Light::directional(glm::vec3(-1.0, -1.0, -1.0), 0.7)],
+            environment: EnvironmentSettings::default(),
+            shadows: ShadowSettings::default(),
+        }
+    }
+}
+
+impl Lighting {
+    /// Lights the shader will actually see
+    pub fn active(&self) -> impl Iterator<Item = &Light> {
+        self.lights.iter().filter(|light| light.enabled).take(MAX_LIGHTS)
+    }
+
+    /// Index in the shader's light array of the light that casts shadows - the first active directional one
+    pub fn shadow_caster(&self) -> Option<(usize, &Light)> {
+        if !self.shadows.enabled {
+            return None;
+        }
+        self.active().enumerate().find(|(_, light)| light.kind == LightKind::Directional)
+    }
+
+    /// Set the ambient and `u_lights` array uniforms of the program in use
+    pub fn upload(&self, gl: &glow::Context, locations: &UniformLocations) {
+        unsafe {
+            let location = |name: &str| locations.get(name);
+            gl.uniform_3_f32_slice(location("u_ambient"), &self.ambient);
+
+            let mut count = 0;
+            for (i, light) in self.active().enumerate() {
+                let field = |name: &str| location(&format!("u_lights[{}].{}", i, name));
+                let color = glm::Vec3::from(light.color) * light.intensity;
+                let direction = light.direction.try_normalize(1e-6).unwrap_or(-glm::Vec3::y());  // Straight down if zeroed
+                let (inner, outer) = light.cone_cosines();
+                gl.uniform_1_i32(field("kind"), light.kind.shader_id());
+                gl.uniform_3_f32_slice(field("color"), color.as_slice());
+                gl.uniform_3_f32_slice(field("position"), light.position.as_slice());
+                gl.uniform_3_f32_slice(field("direction"), direction.as_slice());
+                gl.uniform_1_f32(field("range"), light.range.max(1e-3));
+                gl.uniform_1_f32(field("inner_cos"), inner);
+                gl.uniform_1_f32(field("outer_cos"), outer);
+                count += 1;
+            }
+            gl.uniform_1_i32(location("u_light_count"), count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_enabled_lights_up_to_the_limit_are_active() {
+        let mut lighting = Lighting { lights: vec![Light::new(LightKind::Point); MAX_LIGHTS + 3], ..Lighting::default() };
+        lighting.lights[0].enabled = false;
+        lighting.lights[1].kind = LightKind::Spot;
+        let active: Vec<_> = lighting.active().collect();
+        assert_eq!(active.len(), MAX_LIGHTS);
+        assert_eq!(active[0].kind, LightKind::Spot);  // The disabled one is skipped, not counted
+    }
+
+    #[test]
+    fn shader_array_matches_light_limit() {
+        assert!(LIGHTS_GLSL.contains(&format!("#define MAX_LIGHTS {}", MAX_LIGHTS)));
+    }
+
+    #[test]
+    fn spot_cone_inner_never_exceeds_outer() {
+        let mut light = Light::new(LightKind::Spot);
+        light.inner_angle = 45.0;
+        light.outer_angle = 30.0;
+        let (inner, outer) = light.cone_cosines();
+        assert_eq!(inner, outer);
+        light.inner_angle = 0.0;
+        assert_eq!(light.cone_cosines().0, 1.0);
+    }
+
+    #[test]
+    fn first_active_directional_light_casts_shadows() {
+        let mut lighting = Lighting::default();
+        lighting.lights.insert(0, Light::new(LightKind::Point));
+        lighting.lights.push(Light::directional(glm::vec3(0.0, -1.0, 0.0), 1.0));
+        assert_eq!(lighting.shadow_caster().map(|(i, _)| i), Some(1));
+
+        lighting.lights[1].enabled = false;  // Index counts active lights only
+        let (index, light) = lighting.shadow_caster().unwrap();
+        assert_eq!((index, light.direction), (1, glm::vec3(0.0, -1.0, 0.0)));
+
+        lighting.shadows.enabled = false;
+        assert!(lighting.shadow_caster().is_none());
+    }
+}
@@ -0,0 +1,446 @@
+// Generic mesh - vertex data described by a declarative layout, uploaded to OpenGL
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::sync::Arc;    // Shared OpenGL context so we can free buffers on drop
+
+use super::bounds::{Aabb, BoundingSphere};
+use super::tangents;
+
+/// Standard attribute names and the shader locations they bind to
+/// Shaders declare e.g. `layout(location = 1) in vec3 normal;` to match this table
+pub const ATTRIBUTE_LOCATIONS: [&str; 5] = ["position", "normal", "color", "uv", "tangent"];
+
+/// Location of the color attribute (meshes without colors get a constant white)
+const COLOR_LOCATION: u32 = 2;
+
+/// Location of the tangent attribute (meshes without tangents get zero, which turns normal mapping off)
+const TANGENT_LOCATION: u32 = 4;
+
+/// Component type of a vertex attribute
+#[allow(dead_code)]  // Full set of GL component types for custom layouts, not all used by our loaders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType {
+    F32,
+    U8,
+    U16,
+    I8,
+    I16,
+}
+
+impl AttributeType {
+    /// Size of one component in bytes
+    pub fn size(self) -> i32 {
+        match self {
+            AttributeType::F32 => 4,
+            AttributeType::U8 | AttributeType::I8 => 1,
+            AttributeType::U16 | AttributeType::I16 => 2,
+        }
+    }
+
+    /// Matching OpenGL enum
+    fn gl_type(self) -> u32 {
+        match self {
+            AttributeType::F32 => glow::FLOAT,
+            AttributeType::U8 => glow::UNSIGNED_BYTE,
+            AttributeType::U16 => glow::UNSIGNED_SHORT,
+            AttributeType::I8 => glow::BYTE,
+            AttributeType::I16 => glow::SHORT,
+        }
+    }
+}
+
+/// One attribute in an interleaved vertex, e.g. "normal": 3 x f32
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexAttribute {
+    pub name: &'static str,   // Semantic name, see ATTRIBUTE_LOCATIONS
+    pub components: i32,      // 1-4 values per vertex
+    pub kind: AttributeType,  // Component type
+    pub normalized: bool,     // Map integer values to 0..1 / -1..1 in the shader
+}
+
+impl VertexAttribute {
+    /// Shorthand for the common case of un-normalized floats
+    pub fn f32(name: &'static str, components: i32) -> Self {
+        Self { name, components, kind: AttributeType::F32, normalized: false }
+    }
+
+    /// Size of this attribute in bytes
+    pub fn size(&self) -> i32 {
+        self.components * self.kind.size()
+    }
+}
+
+/// Ordered list of attributes making up one interleaved vertex
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VertexLayout {
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// Builder-style: append an attribute
+    pub fn with(mut self, attribute: VertexAttribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Bytes between the start of consecutive vertices
+    pub fn stride(&self) -> i32 {
+        self.attributes.iter().map(|a| a.size()).sum()
+    }
+
+    /// Byte offset of the named attribute inside a vertex (None if not present)
+    pub fn offset_of(&self, name: &str) -> Option<i32> {
+        let mut offset = 0;
+        for attribute in &self.attributes {
+            if attribute.name == name {
+                return Some(offset);
+            }
+            offset += attribute.size();
+        }
+        None
+    }
+
+    /// Shader location for an attribute: standard names use the fixed table, others follow it
+    fn location(&self, index: usize) -> u32 {
+        let name = self.attributes[index].name;
+        ATTRIBUTE_LOCATIONS.iter()
+            .position(|n| *n == name)
+            .unwrap_or(ATTRIBUTE_LOCATIONS.len() + index) as u32
+    }
+}
+
+/// Index buffer contents - 16-bit indices halve the memory for small meshes
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    /// Pick the smallest index type able to hold every index (all must be below `vertex_count`)
+    pub fn compact(indices: Vec<u32>, vertex_count: usize) -> Self {
+        debug_assert!(indices.iter().all(|&i| (i as usize) < vertex_count), "index out of range for {} vertices", vertex_count);
+        // Decided by the indices themselves, so even a bad one is never truncated into a valid-looking vertex
+        if indices.iter().all(|&i| i <= u16::MAX as u32) {
+            Indices::U16(indices.into_iter().map(|i| i as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(v) => v.len(),
+            Indices::U32(v) => v.len(),
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Indices::U16(v) => bytemuck::cast_slice(v),
+            Indices::U32(v) => bytemuck::cast_slice(v),
+        }
+    }
+
+    fn gl_type(&self) -> u32 {
+        match self {
+            Indices::U16(_) => glow::UNSIGNED_SHORT,
+            Indices::U32(_) => glow::UNSIGNED_INT,
+        }
+    }
+}
+
+/// How vertices are assembled into primitives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveMode {
+    Points,
+    Lines,
+    LineLoop,
+    LineStrip,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl PrimitiveMode {
+    fn gl_mode(self) -> u32 {
+        match self {
+            PrimitiveMode::Points => glow::POINTS,
+            PrimitiveMode::Lines => glow::LINES,
+            PrimitiveMode::LineLoop => glow::LINE_LOOP,
+            PrimitiveMode::LineStrip => glow::LINE_STRIP,
+            PrimitiveMode::Triangles => glow::TRIANGLES,
+            PrimitiveMode::TriangleStrip => glow::TRIANGLE_STRIP,
+            PrimitiveMode::TriangleFan => glow::TRIANGLE_FAN,
+        }
+    }
+
+    /// Triangles drawn from `count` vertices (or indices) - 0 for points and lines
+    pub fn triangle_count(self, count: usize) -> usize {
+        match self {
+            PrimitiveMode::Triangles => count / 3,
+            PrimitiveMode::TriangleStrip | PrimitiveMode::TriangleFan => count.saturating_sub(2),
+            _ => 0,
+        }
+    }
+}
+
+/// CPU-side mesh: raw interleaved vertex bytes plus the layout describing them
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshData {
+    pub layout: VertexLayout,
+    pub vertices: Vec<u8>,         // Interleaved vertex data, layout.stride() bytes per vertex
+    pub indices: Option<Indices>,  // None = draw vertices in order
+    pub mode: PrimitiveMode,
+}
+
+impl MeshData {
+    /// Build from f32 vertex data (the usual case - positions, normals, colors, UVs)
+    pub fn from_f32(layout: VertexLayout, vertices: &[f32], indices: Option<Indices>, mode: PrimitiveMode) -> Self {
+        Self {
+            layout,
+            vertices: bytemuck::cast_slice(vertices).to_vec(),  // Reinterpret floats as bytes
+            indices,
+            mode,
+        }
+    }
+
+    /// Interleave separate attribute streams into an indexed mesh
+    /// Optional streams are only added to the layout when present; triangle meshes with UVs
+    /// but no tangents get MikkTSpace tangents generated
+    pub fn from_streams(
+        positions: &[[f32; 3]],
+        normals: &[[f32; 3]],
+        colors: Option<&[[f32; 3]]>,
+        uvs: Option<&[[f32; 2]]>,
+        tangents: Option<&[[f32; 4]]>,
+        indices: Vec<u32>,
+        mode: PrimitiveMode,
+    ) -> Self {
+        let generated = match (tangents, uvs) {
+            (None, Some(uvs)) if mode == PrimitiveMode::Triangles => tangents::generate(positions, normals, uvs, &indices),
+            _ => None,
+        };
+        let tangents = tangents.or(generated.as_deref());
+
+        let mut layout = VertexLayout::default()
+            .with(VertexAttribute::f32("position", 3))
+            .with(VertexAttribute::f32("normal", 3));
+        if colors.is_some() {
+            layout = layout.with(VertexAttribute::f32("color", 3));
+        }
+        if uvs.is_some() {
+            layout = layout.with(VertexAttribute::f32("uv", 2));
+        }
+        if tangents.is_some() {
+            layout = layout.with(VertexAttribute::f32("tangent", 4));
+        }
+
+        let floats_per_vertex = (layout.stride() / 4) as usize;
+        let mut vertices: Vec<f32> = Vec::with_capacity(positions.len() * floats_per_vertex);
+        for i in 0..positions.len() {
+            vertices.extend_from_slice(&positions[i]);
+            vertices.extend_from_slice(&normals[i]);
+            if let Some(colors) = colors {
+                vertices.extend_from_slice(&colors[i]);
+            }
+            if let Some(uvs) = uvs {
+                vertices.extend_from_slice(&uvs[i]);
+            }
+            if let Some(tangents) = tangents {
+                vertices.extend_from_slice(&tangents[i]);
+            }
+        }
+
+        let indices = Indices::compact(indices, positions.len());
+        Self::from_f32(layout, &vertices, Some(indices), mode)
+    }
+
+    /// Number of vertices described by the byte buffer
+    pub fn vertex_count(&self) -> usize {
+        match self.layout.stride() {
+            0 => 0,
+            stride => self.vertices.len() / stride as usize,
+        }
+    }
+
+    /// Vertex positions read back from the interleaved bytes (empty without an f32 xyz "position")
+    pub fn positions(&self) -> Vec<[f32; 3]> {
+        let position = self.layout.attributes.iter().find(|a| a.name == "position");
+        let (Some(offset), Some(position)) = (self.layout.offset_of("position"), position) else {
+            return Vec::new();
+        };
+        if position.kind != AttributeType::F32 || position.components < 3 {
+            return Vec::new();
+        }
+        self.vertices
+            .chunks_exact(self.layout.stride() as usize)
+            .map(|vertex| {
+                let bytes = &vertex[offset as usize..offset as usize + 12];
+                // pod_read_unaligned: the vertex bytes have no alignment guarantee
+                bytemuck::pod_read_unaligned::<[f32; 3]>(bytes)
+            })
+            .collect()
+    }
+}
+
+/// GPU-side mesh: vertex array, buffers and what's needed to issue the draw call
+pub struct Mesh {
+    gl: Arc<glow::Context>,       // Kept so Drop can release the GPU objects
+    vao: glow::VertexArray,       // Vertex attribute setup
+    vbo: glow::Buffer,            // Interleaved vertex data
+    ebo: Option<glow::Buffer>,    // Index data (None for non-indexed meshes)
+    mode: u32,                    // GL primitive mode
+    count: i32,                   // Number of indices (or vertices) to draw
+    index_type: Option<u32>,      // UNSIGNED_SHORT / UNSIGNED_INT when indexed
+    has_color: bool,              // False = use constant white for the color attribute
+    has_tangent: bool,            // False = use a zero tangent (no normal mapping)
+    pub bounds: Aabb,             // Object-space box around the vertices
+    pub sphere: Option<BoundingSphere>,  // Object-space sphere (None for an empty mesh)
+}
+
+impl Mesh {
+    /// Upload mesh data to the GPU, configuring attributes from its layout
+    pub fn new(gl: Arc<glow::Context>, data: &MeshData) -> Result<Self, String> {
+        let stride = data.layout.stride();
+        if stride == 0 {
+            return Err("mesh layout has no attributes".to_string());
+        }
+
+        // Bounds come from the CPU copy - nothing is read back from the GPU
+        let positions = data.positions();
+        let bounds = Aabb::from_points(positions.iter().copied());
+        let sphere = BoundingSphere::from_points(&positions);
+
+        unsafe {
+            let vao = gl.create_vertex_array()?;
+            let vbo = gl.create_buffer()?;
+
+            gl.bind_vertex_array(Some(vao));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, &data.vertices, glow::STATIC_DRAW);
+
+            // One attribute pointer per layout entry, offsets accumulated in order
+            let mut offset = 0;
+            for (index, attribute) in data.layout.attributes.iter().enumerate() {
+                let location = data.layout.location(index);
+                gl.vertex_attrib_pointer_f32(
+                    location,
+                    attribute.components,
+                    attribute.kind.gl_type(),
+                    attribute.normalized,
+                    stride,
+                    offset,
+                );
+                gl.enable_vertex_attrib_array(location);
+                offset += attribute.size();
+            }
+
+            // The element buffer binding is stored in the VAO, so bind it while the VAO is active
+            let ebo = match &data.indices {
+                Some(indices) => {
+                    let ebo = gl.create_buffer()?;
+                    gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(ebo));
+                    gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, indices.bytes(), glow::STATIC_DRAW);
+                    Some(ebo)
+                }
+                None => None,
+            };
+
+            gl.bind_vertex_array(None);
+
+            Ok(Self {
+                gl,
+                vao,
+                vbo,
+                ebo,
+                mode: data.mode.gl_mode(),
+                count: data.indices.as_ref().map(|i| i.len()).unwrap_or_else(|| data.vertex_count()) as i32,
+                index_type: data.indices.as_ref().map(|i| i.gl_type()),
+                has_color: data.layout.offset_of("color").is_some(),
+                has_tangent: data.layout.offset_of("tangent").is_some(),
+                bounds,
+                sphere,
+            })
+        }
+    }
+
+    /// Issue the draw call (the caller binds the program and sets uniforms first)
+    pub fn draw(&self, gl: &glow::Context) {
+        unsafe {
+            gl.bind_vertex_array(Some(self.vao));
+            if !self.has_color {
+                // Disabled attributes read this constant value instead
+                gl.vertex_attrib_4_f32(COLOR_LOCATION, 1.0, 1.0, 1.0, 1.0);
+            }
+            if !self.has_tangent {
+                gl.vertex_attrib_4_f32(TANGENT_LOCATION, 0.0, 0.0, 0.0, 1.0);
+            }
+            match self.index_type {
+                Some(index_type) => gl.draw_elements(self.mode, self.count, index_type, 0),
+                None => gl.draw_arrays(self.mode, 0, self.count),
+            }
+            gl.bind_vertex_array(None);
+        }
+    }
+}
+
+// Free GPU memory when the last Arc<Mesh> goes away (e.g. after opening another file)
+impl Drop for Mesh {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_vertex_array(self.vao);
+            self.gl.delete_buffer(self.vbo);
+            if let Some(ebo) = self.ebo {
+                self.gl.delete_buffer(ebo);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_computes_stride_and_offsets() {
+        let layout = VertexLayout::default()
+            .with(VertexAttribute::f32("position", 3))
+            .with(VertexAttribute { name: "color", components: 4, kind: AttributeType::U8, normalized: true })
+            .with(VertexAttribute::f32("uv", 2));
+
+        assert_eq!(layout.stride(), 12 + 4 + 8);
+        assert_eq!(layout.offset_of("color"), Some(12));
+        assert_eq!(layout.offset_of("uv"), Some(16));
+        assert_eq!(layout.offset_of("normal"), None);
+        assert_eq!(layout.location(2), 3);  // "uv" keeps its standard location
+    }
+
+    #[test]
+    fn picks_index_width_from_vertex_count() {
+        assert!(matches!(Indices::compact(vec![0, 1, 2], 3), Indices::U16(_)));
+        assert!(matches!(Indices::compact(vec![0, 1, 70_000], 70_001), Indices::U32(_)));
+    }
+
+    #[test]
+    fn interleaves_optional_streams() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let normals = [[0.0, 0.0, 1.0]; 3];
+        let uvs = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+        let data = MeshData::from_streams(&positions, &normals, None, Some(&uvs), None, vec![0, 1, 2], PrimitiveMode::Triangles);
+
+        assert_eq!(data.layout.stride(), 12 * 4);
+        assert_eq!(data.vertex_count(), 3);
+        assert_eq!(data.mode.triangle_count(data.indices.as_ref().map_or(0, Indices::len)), 1);
+
+        // Second vertex: position, normal, uv, then the generated tangent (+U is +X here)
+        let floats: &[f32] = bytemuck::cast_slice(&data.vertices);
+        assert_eq!(&floats[12..24], &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+
+        // No UVs, no tangents
+        let plain = MeshData::from_streams(&positions, &normals, None, None, None, vec![0, 1, 2], PrimitiveMode::Triangles);
+        assert_eq!(plain.layout.offset_of("tangent"), None);
+
+        // Positions can be read back for bounds
+        assert_eq!(data.positions(), positions);
+    }
+}
@@ -1,26 +1,146 @@
 // Module declarations - include submodules
-mod cube;    // cube.rs - 3D cube mesh and rendering
-mod shader;  // shader.rs - OpenGL shader utilities
+mod antialiasing; // antialiasing.rs - MSAA sample counts and the FXAA pass
+mod benchmark;    // benchmark.rs - per-draw uniform cost, by name vs cached
+mod bounds;       // bounds.rs - axis-aligned boxes and bounding spheres
+mod camera;       // camera.rs - orbit camera with pan, zoom-to-cursor and inertia
+mod camera_block; // camera_block.rs - std140 uniform buffer with the per-frame camera
+mod cube;         // cube.rs - built-in demo cube geometry
+mod environment;  // environment.rs - HDR image-based lighting and skybox
+mod framebuffer;  // framebuffer.rs - offscreen viewport target with optional MSAA resolve
+mod gltf_import;  // gltf_import.rs - glTF 2.0 (.gltf/.glb) scene importer
+mod hot_reload;   // hot_reload.rs - shader files on disk, recompiled when they change
+mod lighting;     // lighting.rs - lights and Blinn-Phong materials
+mod mesh;         // mesh.rs - generic GPU mesh with a declarative vertex layout
+mod obj;          // obj.rs - Wavefront OBJ/MTL parser
+mod pbr;          // pbr.rs - glTF metallic-roughness shading
+mod post;         // post.rs - HDR post chain: exposure, bloom, tone mapping, vignette, gamma
+mod picking;      // picking.rs - object ID buffer for click selection
+mod preprocess;   // preprocess.rs - #include and #define handling for GLSL
+mod primitives;   // primitives.rs - procedural spheres, tori, cylinders...
+mod programs;     // programs.rs - lit shader variants, cached by feature set
+mod scene;        // scene.rs - the list of objects the viewport draws
+mod shader;       // shader.rs - OpenGL shader utilities
+mod shadows;      // shadows.rs - directional light shadow maps (optionally cascaded)
+mod tangents;     // tangents.rs - MikkTSpace tangent generation for normal maps
+mod texture;      // texture.rs - image textures, samplers and material slots
+mod transform;    // transform.rs - editable translation/rotation/scale
+mod uniforms;     // uniforms.rs - active uniform reflection and user parameters
+mod view_shading; // view_shading.rs - per-viewport display modes
 
 // Import OpenGL context trait and math library
 use glow::HasContext;        // Trait that provides OpenGL function methods
 use nalgebra_glm as glm;     // Linear algebra library (vectors, matrices) - aliased as 'glm'
-use std::sync::Arc;          // Atomic Reference Counter for shared ownership
+use std::collections::{hash_map::Entry, HashMap};  // Images and samplers shared between glTF materials, viewport targets
+use std::path::{Path, PathBuf};  // File system paths
+use std::sync::{Arc, Mutex}; // Shared ownership, plus a lock for results coming back from paint callbacks
+
+// Re-export so the UI and app state can name the camera and primitive parameters
+pub use antialiasing::Antialiasing;
+pub use benchmark::DrawCost;
+pub use bounds::{Aabb, BoundingSphere};
+pub use camera::{Camera, CameraMode, Projection, ViewPreset};
+pub use hot_reload::ShaderError;
+pub use lighting::{Light, LightKind, Lighting, Material, MAX_LIGHTS};
+pub use pbr::{PbrMaterial, ShadingModel};
+pub use picking::PickTarget;
+pub use post::{PostSettings, ToneMapOperator};
+pub use transform::Transform;
+pub use uniforms::{ShaderParameters, UniformInfo, UniformKind};
+pub use view_shading::ViewShading;
+pub use primitives::Primitive;
+pub use shadows::{ShadowSettings, MAX_CASCADES};
+pub use texture::{ColorSpace, Filter, MaterialTextures, SamplerSettings, TextureSlot, Wrap, MAX_ANISOTROPY};
 
 // Derive Clone trait so we can clone the entire Renderer
 #[derive(Clone)]  // Auto-generates clone() method
 pub struct Renderer {
     gl: Arc<glow::Context>,  // Shared OpenGL context (Arc allows multiple owners)
-    cube: Arc<cube::Cube>,   // Our 3D cube mesh (also shared)
+    programs: Arc<Mutex<programs::ProgramCache>>,  // Blinn-Phong and PBR variants, built as objects need them
+    camera_buffer: Arc<camera_block::CameraBuffer>,  // Projection, view and eye for every lit program
+    id_program: glow::Program,  // Writes object IDs for picking
+    id_locations: Arc<uniforms::UniformLocations>,  // Its uniforms, resolved once
+    sky_program: glow::Program,  // Draws the environment behind the scene
+    sky_locations: Arc<uniforms::UniformLocations>,
+    shadow_program: glow::Program,  // Depth only, from the light
+    shadow_locations: Arc<uniforms::UniformLocations>,
+    shadow_debug_program: glow::Program,  // Shows a shadow map layer in the debug window
+    shadow_debug_locations: Arc<uniforms::UniformLocations>,
+    fullscreen_vao: glow::VertexArray,  // Empty - the skybox triangle comes from gl_VertexID
+    scene: Arc<scene::Scene>,  // Objects to draw (starts with the demo cube)
+    lighting: Arc<Lighting>,   // Ambient + light list, kept when the scene is replaced
+    environment: Option<Arc<environment::Environment>>,  // Baked HDR lighting (None = flat ambient)
     rotation: f32,           // Current rotation angle in radians
+    depth_probe: Arc<Mutex<Option<(usize, glm::Vec3)>>>,  // Viewport and world point found under its cursor by the last render
+    id_buffer: Arc<Mutex<Option<picking::IdBuffer>>>,  // Offscreen pick target, created on first click
+    shadow_map: Arc<Mutex<Option<shadows::ShadowMap>>>,  // Depth maps, created on the first shadowed frame
+    shadow_error: Arc<Mutex<Option<(i32, String)>>>,  // Resolution that failed to allocate and why - not retried until it changes
+    post_programs: Arc<post::PostPrograms>,  // HDR scene -> display image
+    post: PostSettings,          // Which passes run, with their parameters
+    fxaa_program: glow::Program,  // Anti-aliasing pass over the post chain's output
+    fxaa_locations: Arc<uniforms::UniformLocations>,
+    diagnostic_program: glow::Program,  // Wireframe overlay and the data views (normals, depth, UVs...)
+    diagnostic_locations: Arc<uniforms::UniformLocations>,
+    viewport_targets: Arc<Mutex<HashMap<usize, framebuffer::ViewportTargets>>>,  // Each viewport's textures, created on its first render
+    antialiasing: Antialiasing,  // MSAA samples in the scene targets, or FXAA after them
+    default_textures: Arc<texture::DefaultTextures>,  // Bound to empty material slots
+    normal_mapping: bool,    // Apply normal maps (off shows the bare geometry for comparison)
+    shader_dir: Option<PathBuf>,  // Editable shader sources (None = only the embedded copies)
+    shader_watcher: Option<Arc<Mutex<hot_reload::ShaderWatcher>>>,  // Set by watch_shaders
+    shader_parameters: Arc<ShaderParameters>,  // Inspector values for uniforms the app doesn't set
 }
 
 // Implementation block for Renderer methods
 impl Renderer {
     /// Create a new renderer instance with OpenGL setup
     pub fn new(gl: Arc<glow::Context>) -> Self {
-        // Create our cube mesh (wrapped in Arc for sharing)
-        let cube = Arc::new(cube::Cube::new(&gl));
+        // Lit programs come from the shader folder - build the plain variants now so broken
+        // sources are reported at startup (the cache falls back to the embedded copies)
+        let shader_dir = hot_reload::shader_dir();
+        let mut programs = programs::ProgramCache::new(gl.clone(), shader_dir.iter().cloned().collect());
+        for shading in ShadingModel::ALL {
+            programs.get(programs::ProgramKey { shading, features: programs::Features::default() })
+                .expect("Failed to create shader program");
+        }
+        let sky_program = shader::create_program(&gl, environment::FULLSCREEN_VERTEX_SHADER_SOURCE, environment::SKYBOX_FRAGMENT_SHADER_SOURCE)
+            .expect("Failed to create skybox shader program");
+        environment::init_skybox_program(&gl, sky_program);
+        let default_textures = texture::DefaultTextures::new(gl.clone()).expect("Failed to create default textures");
+        // Depth pass reuses the picking vertex shader - it only needs the transform
+        let shadow_program = shader::create_program(&gl, picking::ID_VERTEX_SHADER_SOURCE, shadows::DEPTH_FRAGMENT_SHADER_SOURCE)
+            .expect("Failed to create shadow shader program");
+        let shadow_debug_program = shader::create_program(&gl, environment::FULLSCREEN_VERTEX_SHADER_SOURCE, shadows::DEBUG_FRAGMENT_SHADER_SOURCE)
+            .expect("Failed to create shadow debug shader program");
+        let shadow_debug_locations = uniforms::UniformLocations::new(&gl, shadow_debug_program);
+        shadows::init_program(&gl, shadow_debug_program, &shadow_debug_locations);
+        let post_programs = post::PostPrograms::new(&gl).expect("Failed to create post-processing shader programs");
+        let fxaa_program = shader::create_program(&gl, environment::FULLSCREEN_VERTEX_SHADER_SOURCE, antialiasing::FXAA_FRAGMENT_SHADER_SOURCE)
+            .expect("Failed to create FXAA shader program");
+        let fxaa_locations = uniforms::UniformLocations::new(&gl, fxaa_program);
+        let diagnostic_program = view_shading::create_diagnostic_program(&gl).expect("Failed to create diagnostic shader program");
+        let diagnostic_locations = uniforms::UniformLocations::new(&gl, diagnostic_program);
+        let fullscreen_vao = unsafe { gl.create_vertex_array() }.expect("Failed to create vertex array");
+        let id_program = shader::create_program(&gl, picking::ID_VERTEX_SHADER_SOURCE, picking::ID_FRAGMENT_SHADER_SOURCE)
+            .expect("Failed to create picking shader program");
+        let id_locations = uniforms::UniformLocations::new(&gl, id_program);
+        let shadow_locations = uniforms::UniformLocations::new(&gl, shadow_program);
+        let sky_locations = uniforms::UniformLocations::new(&gl, sky_program);
+        let camera_buffer = camera_block::CameraBuffer::new(gl.clone()).expect("Failed to create camera uniform buffer");
+
+        // Upload our cube mesh as the only object of the starting scene
+        let cube = mesh::Mesh::new(gl.clone(), &cube::mesh_data()).expect("Failed to create cube mesh");
+        let scene = scene::Scene {
+            objects: vec![scene::SceneObject {
+                name: "Cube".to_string(),
+                mesh: Arc::new(cube),
+                parent: glm::Mat4::identity(),
+                transform: Transform::default(),
+                shading: ShadingModel::BlinnPhong,
+                material: Material::default(),
+                pbr: PbrMaterial::default(),
+                textures: MaterialTextures::default(),
+                primitive: None,
+            }],
+        };
         
         // Set up OpenGL state for 3D rendering
         unsafe {  // OpenGL calls are unsafe in Rust
@@ -33,9 +153,79 @@ impl Renderer {
         // Return new Renderer instance
         Self {
             gl,               // Store the OpenGL context
-            cube,             // Store our cube mesh
+            programs: Arc::new(Mutex::new(programs)),  // Shared with paint callbacks, which add variants
+            camera_buffer: Arc::new(camera_buffer),
+            id_program,       // And the picking one
+            id_locations: Arc::new(id_locations),
+            sky_program,
+            sky_locations: Arc::new(sky_locations),
+            shadow_program,
+            shadow_locations: Arc::new(shadow_locations),
+            shadow_debug_program,
+            shadow_debug_locations: Arc::new(shadow_debug_locations),
+            fullscreen_vao,
+            scene: Arc::new(scene),  // Shared with paint callbacks, copied on write
+            lighting: Arc::new(Lighting::default()),  // One light, like the old built-in shader
+            environment: None,                        // Until an .hdr is opened
             rotation: 0.0,    // Start with no rotation
+            depth_probe: Arc::new(Mutex::new(None)),  // Nothing probed yet
+            id_buffer: Arc::new(Mutex::new(None)),    // Sized to the viewport on first pick
+            shadow_map: Arc::new(Mutex::new(None)),   // Sized by the shadow settings on first use
+            shadow_error: Arc::new(Mutex::new(None)),
+            post_programs: Arc::new(post_programs),
+            post: PostSettings::default(),  // Neutral: looks like the scene did before the chain
+            fxaa_program,
+            fxaa_locations: Arc::new(fxaa_locations),
+            diagnostic_program,
+            diagnostic_locations: Arc::new(diagnostic_locations),
+            viewport_targets: Arc::new(Mutex::new(HashMap::new())),  // Sized to each viewport on first render
+            antialiasing: Antialiasing::default(),  // The config's is set by main
+            default_textures: Arc::new(default_textures),
+            normal_mapping: true,
+            shader_dir,
+            shader_watcher: None,  // Until watch_shaders
+            shader_parameters: Arc::new(ShaderParameters::new()),  // The config's are set by main
+        }
+    }
+
+    /// Watch the shader folder for edits; `on_change` runs on the watcher's thread (use it to wake the UI)
+    pub fn watch_shaders(&mut self, on_change: impl Fn() + Send + 'static) -> Result<(), String> {
+        let dir = self.shader_dir.as_ref().ok_or_else(|| format!("{} not found", hot_reload::SHADER_DIR))?;
+        let watcher = hot_reload::ShaderWatcher::new(dir, on_change)?;
+        self.shader_watcher = Some(Arc::new(Mutex::new(watcher)));
+        Ok(())
+    }
+
+    /// Rebuild the shader variants if any shader file changed since the last call
+    /// (any file can be #included by any program, so they all go)
+    /// Call before the viewport queues its paint callback - replaced programs are deleted right away
+    /// Returns a status line when anything was rebuilt
+    pub fn reload_changed_shaders(&mut self) -> Option<String> {
+        let changed = self.shader_watcher.as_ref()?.lock().unwrap().changed_files();
+        let is_shader = |name: &String| [".vert", ".frag", ".glsl"].iter().any(|extension| name.ends_with(extension));
+        if !changed.iter().any(is_shader) {
+            return None;
+        }
+        Some(self.programs.lock().unwrap().rebuild())
+    }
+
+    /// Rebuild every program from the shader folder (same timing rule as reload_changed_shaders)
+    pub fn reload_shaders(&mut self) -> String {
+        if self.shader_dir.is_none() {
+            return format!("{} not found - using the built-in shaders", hot_reload::SHADER_DIR);
         }
+        self.programs.lock().unwrap().rebuild()
+    }
+
+    /// Programs whose latest sources failed to build (they keep drawing with their last good version)
+    pub fn shader_errors(&self) -> Vec<ShaderError> {
+        self.programs.lock().unwrap().errors()
+    }
+
+    /// Time `draws` draws with by-name uniform lookups against cached locations and the camera block
+    /// Call while the GL context is current (it draws into the current framebuffer)
+    pub fn benchmark_draws(&self, draws: usize) -> Result<DrawCost, String> {
+        benchmark::measure(&self.gl, draws)
     }
 
     /// Update animation state (called each frame if playing)
@@ -43,6 +233,166 @@ impl Renderer {
         self.rotation = delta;  // Store new rotation value
     }
 
+    /// Load a Wavefront OBJ file, replacing the current scene
+    /// Returns a short summary for the status bar, or a readable error message
+    pub fn load_obj(&mut self, path: &Path) -> Result<String, String> {
+        let data = obj::load(path).map_err(|e| e.to_string())?;  // ObjError -> String via Display
+
+        // One object per group so each keeps its own material color
+        let mut objects = Vec::new();
+        for group in &data.groups {
+            let mesh = mesh::Mesh::new(self.gl.clone(), &data.mesh_data(group))?;
+            objects.push(scene::SceneObject {
+                name: group.name.clone(),
+                mesh: Arc::new(mesh),
+                parent: glm::Mat4::identity(),
+                transform: Transform::default(),  // OBJ has no hierarchy
+                shading: ShadingModel::BlinnPhong,
+                material: Material::default(),    // MTL colors are already in the vertex colors
+                pbr: PbrMaterial::default(),
+                textures: MaterialTextures::default(),
+                primitive: None,
+            });
+        }
+
+        let summary = format!("{} groups, {} triangles", data.groups.len(), data.triangle_count());
+        self.set_scene(scene::Scene { objects });
+        Ok(summary)
+    }
+
+    /// Load a glTF 2.0 file (.gltf or .glb), replacing the current scene with its default scene
+    pub fn load_gltf(&mut self, path: &Path) -> Result<String, String> {
+        let data = gltf_import::load(path).map_err(|e| e.to_string())?;
+
+        // Upload each image and sampler once, however many materials use them
+        let mut textures: HashMap<(usize, texture::ColorSpace), Arc<texture::Texture>> = HashMap::new();
+        let mut samplers: HashMap<SamplerSettings, Arc<texture::Sampler>> = HashMap::new();
+        let mut objects = Vec::new();
+        for object in &data.objects {
+            let mesh = mesh::Mesh::new(self.gl.clone(), &object.mesh_data())?;
+            let mut object_textures = MaterialTextures::default();
+            for (slot, source) in &object.textures {
+                let key = (source.image, slot.color_space());
+                let texture = match textures.get(&key) {
+                    Some(texture) => texture.clone(),
+                    None => {
+                        let name = format!("Image {}", source.image);
+                        let texture = Arc::new(texture::Texture::new(self.gl.clone(), &name, &data.images[source.image], key.1)?);
+                        textures.insert(key, texture.clone());
+                        texture
+                    }
+                };
+                let sampler = match samplers.get(&source.sampler) {
+                    Some(sampler) => sampler.clone(),
+                    None => {
+                        let sampler = Arc::new(texture::Sampler::new(self.gl.clone(), source.sampler)?);
+                        samplers.insert(source.sampler, sampler.clone());
+                        sampler
+                    }
+                };
+                object_textures.set(*slot, Some(texture::TextureBinding { texture, sampler }));
+            }
+            // The node's own TRS is editable; its parents stay a fixed matrix, so a non-uniform scale
+            // over a rotated child (shear, which TRS can't hold) still renders as authored
+            let (parent, transform) = match Transform::from_matrix_exact(&object.local) {
+                Some(transform) => (object.parent, transform),
+                None => (object.transform(), Transform::default()),  // Sheared node matrix - keep it whole
+            };
+            objects.push(scene::SceneObject {
+                name: object.name.clone(),
+                mesh: Arc::new(mesh),
+                parent,
+                transform,
+                shading: ShadingModel::Pbr,  // Authored for metallic-roughness
+                material: Material::default(),
+                pbr: object.material,
+                textures: object_textures,
+                primitive: None,
+            });
+        }
+
+        let summary = format!("{} objects, {} triangles", data.objects.len(), data.triangle_count());
+        self.set_scene(scene::Scene { objects });
+        Ok(summary)
+    }
+
+    /// Load an HDR environment map for image-based lighting and the skybox
+    pub fn load_environment(&mut self, path: &Path) -> Result<String, String> {
+        let environment = environment::Environment::load(self.gl.clone(), path)?;
+        self.environment = Some(Arc::new(environment));  // The previous one is freed once no frame uses it
+        Ok("environment lighting baked".to_string())
+    }
+
+    /// Load an image into a texture slot of one object, or of every object when `index` is None
+    /// A missing or corrupt file shows a checkerboard instead - the summary says why
+    pub fn load_texture(&mut self, path: &Path, index: Option<usize>, slot: TextureSlot) -> Result<String, String> {
+        let (image, error) = texture::TextureImage::open_or_checkerboard(path);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let texture = texture::Texture::new(self.gl.clone(), &name, &image, slot.color_space())?;
+        let sampler = texture::Sampler::new(self.gl.clone(), SamplerSettings::default())?;
+        let binding = texture::TextureBinding { texture: Arc::new(texture), sampler: Arc::new(sampler) };
+
+        let scene = Arc::make_mut(&mut self.scene);
+        for (i, object) in scene.objects.iter_mut().enumerate() {
+            if index.is_none_or(|index| index == i) {
+                object.textures.set(slot, Some(binding.clone()));
+            }
+        }
+        Ok(match error {
+            None => format!("{}×{} {} texture", image.width, image.height, slot.name().to_lowercase()),
+            Some(e) => format!("{} - showing a checkerboard instead", e),
+        })
+    }
+
+    /// Go back to the flat ambient term
+    pub fn clear_environment(&mut self) {
+        self.environment = None;
+    }
+
+    /// File name of the loaded environment map
+    pub fn environment_name(&self) -> Option<&str> {
+        self.environment.as_ref().map(|environment| environment.name.as_str())
+    }
+
+    /// Replace whatever is shown with a new scene (old GPU buffers are freed once unused)
+    fn set_scene(&mut self, scene: scene::Scene) {
+        self.scene = Arc::new(scene);
+    }
+
+    /// Add a procedural primitive at the origin, returning its object index
+    pub fn add_primitive(&mut self, primitive: Primitive) -> Result<usize, String> {
+        let mesh = mesh::Mesh::new(self.gl.clone(), &primitive.generate().mesh_data())?;
+        // make_mut clones the scene only if a paint callback still holds the old one
+        let scene = Arc::make_mut(&mut self.scene);
+        scene.objects.push(scene::SceneObject {
+            name: primitive.name().to_string(),
+            mesh: Arc::new(mesh),
+            parent: glm::Mat4::identity(),
+            transform: Transform::default(),
+            shading: ShadingModel::BlinnPhong,
+            material: Material::default(),
+            pbr: PbrMaterial::default(),
+            textures: MaterialTextures::default(),
+            primitive: Some(primitive),
+        });
+        Ok(scene.objects.len() - 1)
+    }
+
+    /// Parameters of a procedural object (None if the index is gone or not a primitive)
+    pub fn primitive(&self, index: usize) -> Option<Primitive> {
+        self.scene.objects.get(index).and_then(|object| object.primitive)
+    }
+
+    /// Regenerate a procedural object's mesh from new parameters
+    pub fn update_primitive(&mut self, index: usize, primitive: Primitive) -> Result<(), String> {
+        let mesh = mesh::Mesh::new(self.gl.clone(), &primitive.generate().mesh_data())?;
+        let scene = Arc::make_mut(&mut self.scene);
+        let object = scene.objects.get_mut(index).ok_or("object no longer exists")?;
+        object.mesh = Arc::new(mesh);  // Previous mesh is freed when the last frame using it ends
+        object.primitive = Some(primitive);
+        Ok(())
+    }
+
     /// Handle window resize - update OpenGL viewport
     pub fn resize(&mut self, width: u32, height: u32) {
         unsafe {
@@ -51,23 +401,38 @@ impl Renderer {
         }
     }
 
-    /// Render viewport with egui callback - this is called from the UI paint callback
-    pub fn render_viewport(&self, gl: &Arc<glow::Context>, rect: egui::Rect, rotation: f32) {
-        use glow::HasContext;  // Import trait in function scope
-        
+    /// Render the scene into viewport `viewport`'s offscreen target and return its color texture for egui to show
+    /// The size is the viewport's in physical pixels - the target follows it and the MSAA setting
+    /// `probe` is a point in the viewport (0..1 from the top-left) whose surface position to report via `take_depth_probe`
+    /// Runs outside paint callbacks while the GL context is current, like `pick`; a view's texture handle only changes when FXAA is switched on or off
+    pub fn render_offscreen(&self, viewport: usize, (width, height): (i32, i32), camera: &Camera, rotation: f32, shading: ViewShading, probe: Option<egui::Vec2>) -> Result<glow::Texture, String> {
+        let gl = &self.gl;
+        let mut targets = self.viewport_targets.lock().unwrap();
+        let samples = self.antialiasing.samples();
+        let targets = match targets.entry(viewport) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(framebuffer::ViewportTargets::new(gl.clone(), width, height, samples)?),
+        };
+        targets.scene.resize(width, height, samples)?;
+        let target = &targets.scene;
+        let (width, height) = target.size();
+
         unsafe {  // All OpenGL calls are unsafe
-            // Save current OpenGL viewport so we can restore it later
-            let mut current_viewport = [0i32; 4];  // Array to hold [x, y, width, height]
-            gl.get_parameter_i32_slice(glow::VIEWPORT, &mut current_viewport);
+            // Calculate 3D transformation matrices (the math behind 3D graphics!)
+            let aspect = width as f32 / height as f32;  // Aspect ratio prevents stretching
+            let projection = camera.projection_matrix(aspect);  // 3D to 2D (perspective projection)
+            let view = camera.view_matrix();                    // Camera position and orientation
             
-            // Set viewport to match our UI rect (coordinate transformation)
-            gl.viewport(
-                rect.min.x as i32,    // Left edge of our UI area
-                rect.min.y as i32,    // Top edge (note: OpenGL Y is flipped)
-                rect.width() as i32,  // Width of our UI area
-                rect.height() as i32, // Height of our UI area
-            );
+            // Model matrix: object transformations (rotation in this case)
+            let model = spin_matrix(rotation);
+
+            // Shadow maps first - they use their own framebuffer and viewport (only lit modes need them)
+            let materials = shading.uses_materials();
+            let shadow = if materials { self.render_shadows(gl, camera, aspect, &model) } else { None };
             
+            // Draw into the target: binds it, covers it with the viewport and clears color and depth
+            target.begin();
+
             // Set up 3D rendering state
             gl.enable(glow::DEPTH_TEST);      // Enable depth testing (closer objects hide farther ones)
             gl.depth_func(glow::LESS);        // Depth test: closer pixels win
@@ -75,49 +440,474 @@ impl Renderer {
             // Enable backface culling for performance
             gl.enable(glow::CULL_FACE);       // Don't render triangles facing away
             gl.cull_face(glow::BACK);         // Cull back-facing triangles
-            
-            // Clear only the depth buffer (color is handled by egui)
-            gl.clear(glow::DEPTH_BUFFER_BIT); // Reset depth values for our area
 
-            // Calculate 3D transformation matrices (the math behind 3D graphics!)
-            let aspect = rect.width() / rect.height();  // Aspect ratio prevents stretching
-            
-            // Projection matrix: 3D to 2D (perspective projection)
-            let projection = glm::perspective(
-                aspect,                   // Aspect ratio (width/height)
-                45.0_f32.to_radians(),   // Field of view (45 degrees converted to radians)
-                0.1,                     // Near clipping plane
-                100.0                    // Far clipping plane
-            );
-            
-            // View matrix: camera position and orientation
-            let view = glm::look_at(
-                &glm::vec3(2.0, 2.0, 2.0),   // Camera position (eye)
-                &glm::vec3(0.0, 0.0, 0.0),   // Look at point (center)
-                &glm::vec3(0.0, 1.0, 0.0),   // Up vector (which way is up)
-            );
-            
-            // Model matrix: object transformations (rotation in this case)
-            let model = glm::rotate(
-                &glm::rotate(
-                    &glm::Mat4::identity(),      // Start with identity matrix (no transformation)
-                    rotation,                    // Rotate around Y axis
-                    &glm::vec3(0.0, 1.0, 0.0),  // Y axis vector
-                ),
-                rotation * 0.7,                  // Different rotation speed for X axis
-                &glm::vec3(1.0, 0.0, 0.0),      // X axis vector
-            );
-
-            // Render the cube using the modular Cube struct
-            self.cube.render(gl, &projection, &view, &model);  // Pass matrices by reference (&)
-            
-            // Restore the original viewport (good citizen behavior!)
-            gl.viewport(
-                current_viewport[0],  // Original x
-                current_viewport[1],  // Original y
-                current_viewport[2],  // Original width
-                current_viewport[3]   // Original height
-            );
+            // Sky first, so the scene draws over it (the diagnostic modes show the objects alone)
+            let settings = &self.lighting.environment;
+            if let Some(environment) = self.environment.as_ref().filter(|_| materials && settings.show_skybox) {
+                // Perspective rays even in orthographic mode, otherwise the whole sky is one texel
+                let rotation_only = glm::mat3_to_mat4(&glm::mat4_to_mat3(&view));
+                let sky_projection = glm::perspective(aspect, camera.fov_y, 0.1, 10.0);
+                let inverse = glm::inverse(&(sky_projection * rotation_only));
+                environment.draw_skybox(gl, self.sky_program, &self.sky_locations, self.fullscreen_vao, &inverse, settings);
+            }
+
+            // The camera block is shared by every lit program - one upload for the whole frame
+            let forward = -glm::vec3(view[(2, 0)], view[(2, 1)], view[(2, 2)]);  // View matrix's third row is the eye's back axis
+            self.camera_buffer.update(&camera_block::pack(&projection, &view, &camera.eye(), &forward));
+
+            // Materials and lights, then the diagnostic pass: the wireframe overlay, or the mode that replaces them
+            if materials {
+                self.draw_shaded(gl, &model, shadow);
+            }
+            if shading != ViewShading::Shaded {
+                self.draw_diagnostic(gl, &model, camera, shading);
+            }
+
+            // MSAA samples are averaged into the texture egui shows
+            target.resolve();
+
+            // Read back the depth under the probe point from the resolved depth
+            if let Some(probe) = probe {
+                let x = (probe.x * width as f32) as i32;
+                let y = ((1.0 - probe.y) * height as f32) as i32;  // Flip to GL's bottom-up Y
+                let depth = target.read_depth(x, y);
+
+                // Depth 1.0 is the cleared far plane - nothing was hit
+                if depth < 1.0 {
+                    let ndc = glm::vec3(probe.x * 2.0 - 1.0, 1.0 - probe.y * 2.0, depth * 2.0 - 1.0);
+                    *self.depth_probe.lock().unwrap() = Some((viewport, camera.unproject(ndc, aspect)));
+                }
+            }
+
+            // Exposure, bloom, tone mapping, vignette and gamma: HDR scene -> display-ready output
+            let post_settings = if materials { self.post } else { PostSettings::display_only() };
+            post::run(gl, &self.post_programs, self.fullscreen_vao, &post_settings, targets)?;
+
+            // FXAA works on the finished image, into a second texture
+            let output = if self.antialiasing == Antialiasing::Fxaa {
+                let fxaa = match &mut targets.fxaa {
+                    Some(fxaa) => fxaa,
+                    None => targets.fxaa.insert(framebuffer::ColorTarget::new(gl.clone(), width, height, framebuffer::ColorFormat::Rgba8)?),
+                };
+                fxaa.resize(width, height)?;
+                antialiasing::apply_fxaa(gl, self.fxaa_program, &self.fxaa_locations, self.fullscreen_vao, targets.output.texture(), fxaa);
+                fxaa.texture()
+            } else {
+                targets.fxaa = None;  // Free it while FXAA is off
+                targets.output.texture()
+            };
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);  // Back to the window for egui
+            Ok(output)
         }  // End of unsafe block
-    }  // End of render_viewport function
-}  // End of impl Renderer
\ No newline at end of file
+    }  // End of render_offscreen function
+
+    /// Draw every object with its own material, lights and shadows (the camera block is already current)
+    fn draw_shaded(&self, gl: &glow::Context, model: &glm::Mat4, shadow: Option<(usize, Vec<shadows::Cascade>)>) {
+        unsafe {
+            // Each object's variant: normal mapping only where there is a map, shadows only if something casts them
+            let shadow_map = self.shadow_map.lock().unwrap();
+            let shadowed = shadow.is_some() && shadow_map.is_some();
+            let mut programs = self.programs.lock().unwrap();
+            let object_programs: Vec<Option<(programs::ProgramKey, glow::Program, Arc<uniforms::UniformLocations>)>> = self.scene.objects.iter().map(|object| {
+                let features = programs::Features {
+                    normal_map: self.normal_mapping && object.textures.get(TextureSlot::Normal).is_some(),
+                    shadows: shadowed,
+                };
+                let key = programs::ProgramKey { shading: object.shading, features };
+                programs.get(key).map(|program| (key, program, programs.locations(key)))
+            }).collect();
+
+            // Lights and shadows are the same for every object - set them once in each program in use
+            let mut prepared: Vec<glow::Program> = Vec::new();
+            for (key, program, locations) in object_programs.iter().flatten() {
+                if prepared.contains(program) {
+                    continue;
+                }
+                prepared.push(*program);
+                gl.use_program(Some(*program));
+                self.lighting.upload(gl, locations);
+                if let (Some((light, cascades)), Some(map)) = (&shadow, shadow_map.as_ref()) {
+                    map.bind(gl, locations, *light, cascades, &self.lighting.shadows);
+                }
+                if key.shading == ShadingModel::Pbr {
+                    match &self.environment {
+                        Some(environment) => environment.bind(gl, locations, &self.lighting.environment),
+                        None => gl.uniform_1_i32(locations.get("u_has_environment"), 0),
+                    }
+                }
+                if let Some(values) = self.shader_parameters.get(key.shading.name()) {
+                    uniforms::apply(gl, locations, programs.uniforms(*key), values);
+                }
+            }
+            drop(programs);
+            drop(shadow_map);
+
+            // Render every object in the scene
+            for (object, program) in self.scene.objects.iter().zip(&object_programs) {
+                let Some((_, program, locations)) = program else { continue };  // Even the built-in shader failed
+                // Spin the whole scene: animation rotation applied after the object's placement
+                let world = model * object.world_matrix();
+                gl.use_program(Some(*program));
+                match object.shading {
+                    ShadingModel::BlinnPhong => object.material.upload(gl, locations),
+                    ShadingModel::Pbr => object.pbr.upload(gl, locations),
+                }
+                object.textures.bind(gl, &self.default_textures);
+                self.draw_mesh(gl, &object.mesh, locations, &world);
+            }
+        }
+    }
+
+    /// Draw every object with the diagnostic program in `shading`'s mode
+    /// Wireframe blends its edges over what is already drawn; the other modes replace the materials
+    fn draw_diagnostic(&self, gl: &glow::Context, model: &glm::Mat4, camera: &Camera, shading: ViewShading) {
+        let program = self.diagnostic_program;
+        let locations = &self.diagnostic_locations;
+        let overlay = shading == ViewShading::Wireframe;
+        unsafe {
+            gl.use_program(Some(program));
+            gl.uniform_1_i32(locations.get("u_mode"), shading.shader_mode());
+            gl.uniform_2_f32(locations.get("u_depth_range"), camera.near, camera.far);
+            gl.disable(glow::CULL_FACE);  // Back faces show up (flat mode tints them)
+            if overlay {
+                // Same triangles again - pulled slightly forward so the edges win the depth test
+                gl.enable(glow::POLYGON_OFFSET_FILL);
+                gl.polygon_offset(-1.0, -1.0);
+                gl.depth_func(glow::LEQUAL);
+                gl.depth_mask(false);
+                gl.enable(glow::BLEND);
+                gl.blend_func_separate(glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA, glow::ZERO, glow::ONE);  // Keep the target's alpha
+            }
+            for object in &self.scene.objects {
+                let base_color = match object.shading {
+                    ShadingModel::BlinnPhong => object.material.diffuse,
+                    ShadingModel::Pbr => object.pbr.base_color,
+                };
+                gl.uniform_3_f32_slice(locations.get("u_base_color"), &base_color);
+                self.draw_mesh(gl, &object.mesh, locations, &(model * object.world_matrix()));
+            }
+            if overlay {
+                gl.disable(glow::BLEND);
+                gl.depth_mask(true);
+                gl.depth_func(glow::LESS);
+                gl.disable(glow::POLYGON_OFFSET_FILL);
+            }
+            gl.enable(glow::CULL_FACE);
+        }
+    }
+
+    /// Free the targets of viewports from `count` on (the layout shrank)
+    pub fn retain_viewports(&self, count: usize) {
+        self.viewport_targets.lock().unwrap().retain(|&view, _| view < count);
+    }
+
+    /// Viewport anti-aliasing; applied on the next render (targets are reallocated in place)
+    pub fn set_antialiasing(&mut self, antialiasing: Antialiasing) {
+        self.antialiasing = antialiasing;
+    }
+
+    pub fn antialiasing(&self) -> Antialiasing {
+        self.antialiasing
+    }
+
+    /// Post-processing passes and their parameters
+    pub fn post_settings(&self) -> &PostSettings {
+        &self.post
+    }
+
+    /// Edit the post chain; applied on the next render
+    pub fn post_settings_mut(&mut self) -> &mut PostSettings {
+        &mut self.post
+    }
+
+    /// Render the shadow caster's depth map(s) for this view
+    /// Returns the caster's index in the shader's light array and the cascades, or None if nothing casts shadows
+    fn render_shadows(&self, gl: &glow::Context, camera: &Camera, aspect: f32, model: &glm::Mat4) -> Option<(usize, Vec<shadows::Cascade>)> {
+        let settings = &self.lighting.shadows;
+        let (light, caster) = self.lighting.shadow_caster()?;
+        let scene = self.scene.bounding_sphere(model)?;
+        let cascades = shadows::cascades(camera, aspect, &caster.direction, &scene, settings);
+
+        // (Re)allocate when the resolution setting changed
+        let mut shadow_map = self.shadow_map.lock().unwrap();
+        if shadow_map.as_ref().map(|map| map.resolution()) != Some(settings.resolution) {
+            *shadow_map = None;  // Free the old one first
+            let mut error = self.shadow_error.lock().unwrap();
+            if error.as_ref().is_some_and(|(resolution, _)| *resolution == settings.resolution) {
+                return None;  // Already failed at this size - don't retry (and report) every frame
+            }
+            match shadows::ShadowMap::new(self.gl.clone(), settings.resolution) {
+                Ok(map) => {
+                    *shadow_map = Some(map);
+                    *error = None;
+                }
+                Err(e) => {
+                    eprintln!("Shadows unavailable: {}", e);
+                    *error = Some((settings.resolution, e));
+                    return None;
+                }
+            }
+        }
+        let map = shadow_map.as_ref()?;
+
+        unsafe {
+            // egui's framebuffer and clip rect, to put back afterwards
+            let framebuffer = gl.get_parameter_framebuffer(glow::DRAW_FRAMEBUFFER_BINDING);
+            let scissor = gl.is_enabled(glow::SCISSOR_TEST);
+            gl.disable(glow::SCISSOR_TEST);  // Would clip the map to the viewport's rect
+            gl.enable(glow::DEPTH_TEST);
+            gl.depth_func(glow::LESS);
+            gl.disable(glow::CULL_FACE);     // Open meshes and planes cast from both sides
+
+            let identity = glm::Mat4::identity();
+            let locations = &self.shadow_locations;
+            gl.use_program(Some(self.shadow_program));
+            for (layer, cascade) in cascades.iter().enumerate() {
+                map.begin_layer(layer);
+                gl.uniform_matrix_4_f32_slice(locations.get("u_projection"), false, cascade.matrix.as_slice());
+                gl.uniform_matrix_4_f32_slice(locations.get("u_view"), false, identity.as_slice());
+                for object in &self.scene.objects {
+                    let world = model * object.world_matrix();
+                    self.draw_mesh(gl, &object.mesh, locations, &world);
+                }
+            }
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, framebuffer);
+            if scissor {
+                gl.enable(glow::SCISSOR_TEST);
+            }
+        }
+        Some((light, cascades))
+    }
+
+    /// Draw one layer of the shadow map into a paint callback's rect (the debug window)
+    pub fn paint_shadow_map(&self, gl: &glow::Context, info: &egui::PaintCallbackInfo, layer: usize) {
+        let shadow_map = self.shadow_map.lock().unwrap();
+        let Some(map) = shadow_map.as_ref() else { return };
+        unsafe {
+            let viewport = info.viewport_in_pixels();
+            gl.viewport(viewport.left_px, viewport.from_bottom_px, viewport.width_px, viewport.height_px);
+            map.bind_texture(gl);
+            gl.use_program(Some(self.shadow_debug_program));
+            gl.uniform_1_i32(self.shadow_debug_locations.get("u_layer"), layer as i32);
+
+            gl.disable(glow::DEPTH_TEST);
+            gl.bind_vertex_array(Some(self.fullscreen_vao));
+            gl.draw_arrays(glow::TRIANGLES, 0, 3);
+            gl.bind_vertex_array(None);
+        }
+    }
+
+    /// Whether a shadow map has been rendered yet (the debug window has something to show)
+    pub fn has_shadow_map(&self) -> bool {
+        self.shadow_map.lock().unwrap().is_some()
+    }
+
+    /// Why the shadow map couldn't be created at the current resolution, if it couldn't
+    pub fn shadow_error(&self) -> Option<String> {
+        self.shadow_error.lock().unwrap().as_ref().map(|(_, e)| e.clone())
+    }
+
+    /// World-space box around the scene as drawn at this animation rotation
+    pub fn bounds(&self, rotation: f32) -> Aabb {
+        self.scene.bounds(&spin_matrix(rotation))
+    }
+
+    /// World-space sphere around the scene as drawn at this animation rotation (None if empty)
+    pub fn bounding_sphere(&self, rotation: f32) -> Option<BoundingSphere> {
+        self.scene.bounding_sphere(&spin_matrix(rotation))
+    }
+
+    /// Render object IDs offscreen and return the index of the object at the target pixel
+    /// Runs outside the paint callback (the GL context is current while the UI is built)
+    pub fn pick(&self, camera: &Camera, rotation: f32, target: PickTarget) -> Result<Option<usize>, String> {
+        let gl = &self.gl;
+        let mut id_buffer = self.id_buffer.lock().unwrap();
+
+        // (Re)allocate when the viewport size changed since the last pick
+        if id_buffer.as_ref().map(|buffer| buffer.size()) != Some((target.width, target.height)) {
+            *id_buffer = None;  // Free the old one first
+            *id_buffer = Some(picking::IdBuffer::new(gl.clone(), target.width, target.height)?);
+        }
+        let Some(buffer) = id_buffer.as_ref() else { return Ok(None) };
+
+        let aspect = target.width as f32 / target.height as f32;
+        let projection = camera.projection_matrix(aspect);
+        let view = camera.view_matrix();
+        let model = spin_matrix(rotation);
+
+        let id = unsafe {
+            // Same depth and culling state as the visible pass, so hidden surfaces can't be picked
+            gl.enable(glow::DEPTH_TEST);
+            gl.depth_func(glow::LESS);
+            gl.enable(glow::CULL_FACE);
+            gl.cull_face(glow::BACK);
+
+            buffer.begin();
+            let locations = &self.id_locations;
+            gl.use_program(Some(self.id_program));
+            gl.uniform_matrix_4_f32_slice(locations.get("u_projection"), false, projection.as_slice());
+            gl.uniform_matrix_4_f32_slice(locations.get("u_view"), false, view.as_slice());
+            for (index, object) in self.scene.objects.iter().enumerate() {
+                let world = model * object.world_matrix();
+                gl.uniform_1_u32(locations.get("u_object_id"), index as u32 + 1);  // 0 is reserved for background
+                self.draw_mesh(gl, &object.mesh, locations, &world);
+            }
+            let id = buffer.read(target.x, target.y);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);  // Back to the window for egui
+            id
+        };
+
+        Ok(id.checked_sub(1).map(|index| index as usize))
+    }
+
+    /// Placement of a scene object (inside the animation spin)
+    pub fn object_transform(&self, index: usize) -> Option<Transform> {
+        self.scene.objects.get(index).map(|object| object.transform)
+    }
+
+    /// Fixed matrix a scene object's transform sits inside (below the animation spin)
+    pub fn object_parent(&self, index: usize) -> Option<glm::Mat4> {
+        self.scene.objects.get(index).map(|object| object.parent)
+    }
+
+    /// Move, rotate or scale a scene object
+    pub fn set_object_transform(&mut self, index: usize, transform: Transform) {
+        if let Some(object) = Arc::make_mut(&mut self.scene).objects.get_mut(index) {
+            object.transform = transform;
+        }
+    }
+
+    /// Shading model and both materials of a scene object
+    pub fn object_material(&self, index: usize) -> Option<(ShadingModel, Material, PbrMaterial)> {
+        self.scene.objects.get(index).map(|object| (object.shading, object.material, object.pbr))
+    }
+
+    /// Change a scene object's shading model and materials
+    pub fn set_object_material(&mut self, index: usize, shading: ShadingModel, material: Material, pbr: PbrMaterial) {
+        if let Some(object) = Arc::make_mut(&mut self.scene).objects.get_mut(index) {
+            object.shading = shading;
+            object.material = material;
+            object.pbr = pbr;
+        }
+    }
+
+    /// Textures bound to a scene object's material slots
+    pub fn object_textures(&self, index: usize) -> Option<&MaterialTextures> {
+        self.scene.objects.get(index).map(|object| &object.textures)
+    }
+
+    /// Change how one of an object's textures is sampled (other objects sharing the image keep theirs)
+    pub fn set_texture_sampler(&mut self, index: usize, slot: TextureSlot, settings: SamplerSettings) -> Result<(), String> {
+        let sampler = Arc::new(texture::Sampler::new(self.gl.clone(), settings)?);
+        let scene = Arc::make_mut(&mut self.scene);
+        let textures = &mut scene.objects.get_mut(index).ok_or("object no longer exists")?.textures;
+        if let Some(binding) = textures.get(slot).cloned() {
+            textures.set(slot, Some(texture::TextureBinding { sampler, ..binding }));
+        }
+        Ok(())
+    }
+
+    /// Remove the texture from one of an object's slots
+    pub fn clear_texture(&mut self, index: usize, slot: TextureSlot) {
+        if let Some(object) = Arc::make_mut(&mut self.scene).objects.get_mut(index) {
+            object.textures.set(slot, None);
+        }
+    }
+
+    /// Turn normal maps on or off for every object
+    pub fn set_normal_mapping(&mut self, enabled: bool) {
+        self.normal_mapping = enabled;
+    }
+
+    /// Uniforms the shaders of a shading model declare, across the variants built so far
+    pub fn shader_uniforms(&self, shading: ShadingModel) -> Vec<UniformInfo> {
+        self.programs.lock().unwrap().shading_uniforms(shading)
+    }
+
+    /// Value of a shader parameter: the one set in the inspector, else what the program holds
+    pub fn shader_parameter(&self, shading: ShadingModel, info: &UniformInfo) -> Vec<f32> {
+        let stored = self.shader_parameters.get(shading.name()).and_then(|values| values.get(&info.name));
+        match stored {
+            Some(values) if values.len() == info.kind.components() * info.size => values.clone(),
+            _ => self.programs.lock().unwrap().read_uniform(shading, info)
+                .unwrap_or_else(|| vec![0.0; info.kind.components() * info.size]),
+        }
+    }
+
+    /// Set a shader parameter for every variant of a shading model (applied from the next frame)
+    pub fn set_shader_parameter(&mut self, shading: ShadingModel, name: &str, values: Vec<f32>) {
+        Arc::make_mut(&mut self.shader_parameters)
+            .entry(shading.name().to_string())
+            .or_default()
+            .insert(name.to_string(), values);
+    }
+
+    /// Forget a shading model's parameters - the programs are rebuilt to get their initializers back
+    /// (same timing rule as reload_changed_shaders)
+    pub fn reset_shader_parameters(&mut self, shading: ShadingModel) -> String {
+        Arc::make_mut(&mut self.shader_parameters).remove(shading.name());
+        self.programs.lock().unwrap().rebuild()
+    }
+
+    /// All inspector values, for saving
+    pub fn shader_parameters(&self) -> &ShaderParameters {
+        &self.shader_parameters
+    }
+
+    /// Replace all inspector values (e.g. from the config)
+    pub fn set_shader_parameters(&mut self, parameters: ShaderParameters) {
+        self.shader_parameters = Arc::new(parameters);
+    }
+
+    /// Current lights
+    pub fn lighting(&self) -> &Lighting {
+        &self.lighting
+    }
+
+    /// Edit the lights (copies them first if a paint callback still holds the old ones)
+    pub fn lighting_mut(&mut self) -> &mut Lighting {
+        Arc::make_mut(&mut self.lighting)
+    }
+
+    /// Display name of a scene object
+    pub fn object_name(&self, index: usize) -> Option<&str> {
+        self.scene.objects.get(index).map(|object| object.name.as_str())
+    }
+
+    /// World point hit by the last depth probe in viewport `view`, if it found a surface (cleared once taken)
+    pub fn take_depth_probe(&self, view: usize) -> Option<glm::Vec3> {
+        let mut probe = self.depth_probe.lock().unwrap();
+        let (probed, point) = (*probe)?;
+        if probed != view {
+            return None;  // Someone else's - leave it for them
+        }
+        *probe = None;
+        Some(point)
+    }
+
+    /// Draw one mesh with the program in use - projection and view are already set (camera block or uniforms),
+    /// only the model matrix changes per draw
+    fn draw_mesh(&self, gl: &glow::Context, mesh: &mesh::Mesh, locations: &uniforms::UniformLocations, model: &glm::Mat4) {
+        unsafe {
+            gl.uniform_matrix_4_f32_slice(locations.get("u_model"), false, model.as_slice());
+        }
+
+        mesh.draw(gl);
+    }
+}  // End of impl Renderer
+
+/// Animation spin applied to the whole scene: around Y, then a slower turn around X
+/// Objects' own transforms sit inside it, so it acts as their parent
+pub fn spin_matrix(rotation: f32) -> glm::Mat4 {
+    glm::rotate(
+        &glm::rotate(
+            &glm::Mat4::identity(),      // Start with identity matrix (no transformation)
+            rotation,                    // Rotate around Y axis
+            &glm::vec3(0.0, 1.0, 0.0),  // Y axis vector
+        ),
+        rotation * 0.7,                  // Different rotation speed for X axis
+        &glm::vec3(1.0, 0.0, 0.0),      // X axis vector
+    )
+}
@@ -0,0 +1,496 @@
+// Wavefront OBJ/MTL parser - turns text files into triangle lists ready for the GPU
+use std::collections::HashMap;  // Lookup tables for materials and vertex de-duplication
+use std::fmt;                   // Display trait for human-readable errors
+use std::fs;                    // Reading .obj and .mtl files
+use std::path::{Path, PathBuf}; // File paths (borrowed and owned)
+
+use super::mesh::{MeshData, PrimitiveMode};
+
+/// Default surface color for faces without a material (light grey)
+const DEFAULT_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+
+/// Everything that can go wrong while loading an OBJ file
+#[derive(Debug)]
+pub enum ObjError {
+    /// The file could not be read from disk
+    Io(PathBuf, std::io::Error),
+    /// A line in the file is malformed (line numbers start at 1)
+    Parse { line: usize, message: String },
+    /// The file parsed fine but contains no faces to draw
+    Empty,
+}
+
+// Display gives us nice messages for the status bar and stderr
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(path, e) => write!(f, "cannot read {}: {}", path.display(), e),
+            ObjError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ObjError::Empty => write!(f, "file contains no faces"),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+/// Material read from an MTL library (only the parts we can render)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,        // Name used by `usemtl`
+    pub diffuse: [f32; 3],   // Kd - base surface color
+}
+
+/// One fully resolved vertex (OBJ stores positions/UVs/normals in separate pools)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObjVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+/// A run of triangles sharing the same group name and material
+#[derive(Debug, Clone)]
+pub struct ObjGroup {
+    pub name: String,              // From `g` or `o` ("default" if none given)
+    pub material: Option<usize>,   // Index into ObjData::materials
+    pub vertices: Vec<ObjVertex>,  // Unique vertices of this group
+    pub indices: Vec<u32>,         // Triangle list (3 indices per triangle)
+}
+
+/// Parsed OBJ file - groups of triangles plus the materials they reference
+#[derive(Debug, Clone)]
+pub struct ObjData {
+    pub groups: Vec<ObjGroup>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjData {
+    /// Total number of triangles across all groups
+    pub fn triangle_count(&self) -> usize {
+        self.groups.iter().map(|g| g.indices.len() / 3).sum()
+    }
+
+    /// Convert one group into a triangle mesh, baking its material color into the vertices
+    pub fn mesh_data(&self, group: &ObjGroup) -> MeshData {
+        let positions: Vec<[f32; 3]> = group.vertices.iter().map(|v| v.position).collect();
+        let normals: Vec<[f32; 3]> = group.vertices.iter().map(|v| v.normal).collect();
+        let uvs: Vec<[f32; 2]> = group.vertices.iter().map(|v| v.uv).collect();
+        let colors = vec![self.group_color(group); group.vertices.len()];
+        MeshData::from_streams(&positions, &normals, Some(&colors), Some(&uvs), None, group.indices.clone(), PrimitiveMode::Triangles)
+    }
+
+    /// Diffuse color of a group's material (or the default grey)
+    pub fn group_color(&self, group: &ObjGroup) -> [f32; 3] {
+        group.material
+            .and_then(|i| self.materials.get(i))  // Option chaining - None if no material
+            .map(|m| m.diffuse)
+            .unwrap_or(DEFAULT_COLOR)
+    }
+}
+
+/// Load an OBJ file from disk, resolving `mtllib` paths relative to it
+pub fn load(path: &Path) -> Result<ObjData, ObjError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| ObjError::Io(path.to_path_buf(), e))?;  // Wrap io::Error with the path
+    parse(&source, path.parent())
+}
+
+/// Parse OBJ source text. `base_dir` is where MTL libraries are looked up (None = skip them)
+pub fn parse(source: &str, base_dir: Option<&Path>) -> Result<ObjData, ObjError> {
+    // Shared vertex pools - faces index into these (1-based in the file)
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+    let mut builder = GroupBuilder::new("default".to_string(), None);
+    let mut groups: Vec<ObjGroup> = Vec::new();
+
+    // enumerate() gives (index, item) pairs - we need line numbers for errors
+    for (index, raw_line) in source.lines().enumerate() {
+        let line_no = index + 1;
+        // Strip comments and surrounding whitespace
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let keyword = parts.next().unwrap_or("");  // Safe: line is not empty
+        let args: Vec<&str> = parts.collect();
+
+        match keyword {
+            "v" => positions.push(parse_floats::<3>(&args, line_no, "vertex position")?),
+            "vn" => normals.push(parse_floats::<3>(&args, line_no, "vertex normal")?),
+            "vt" => {
+                // Texture coordinates may have 1-3 components, we only keep u and v
+                let u = parse_float(args.first().copied(), line_no, "texture coordinate")?;
+                let v = match args.get(1) {
+                    Some(s) => parse_float(Some(s), line_no, "texture coordinate")?,
+                    None => 0.0,
+                };
+                uvs.push([u, v]);
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(parse_error(line_no, format!("face needs at least 3 vertices, got {}", args.len())));
+                }
+                // Resolve each corner to (position, uv, normal) pool indices
+                let corners = args.iter()
+                    .map(|s| parse_corner(s, line_no, positions.len(), uvs.len(), normals.len()))
+                    .collect::<Result<Vec<_>, _>>()?;  // Stop at the first bad corner
+                builder.add_polygon(&corners, &positions, &uvs, &normals);
+            }
+            "g" | "o" => {
+                let name = if args.is_empty() { "default".to_string() } else { args.join(" ") };
+                let material = builder.material;
+                groups.extend(builder.finish());
+                builder = GroupBuilder::new(name, material);
+            }
+            "usemtl" => {
+                let Some(name) = args.first() else {
+                    return Err(parse_error(line_no, "usemtl without a material name".to_string()));
+                };
+                // Unknown materials fall back to the default color instead of failing
+                let material = materials.iter().position(|m| m.name == *name);
+                if material.is_none() {
+                    eprintln!("Warning: OBJ line {}: unknown material '{}'", line_no, name);
+                }
+                let group_name = builder.name.clone();
+                groups.extend(builder.finish());
+                builder = GroupBuilder::new(group_name, material);
+            }
+            "mtllib" => {
+                let Some(dir) = base_dir else { continue };  // Parsing from memory - no files to load
+                for file in &args {
+                    let mtl_path = dir.join(file);
+                    // A missing library is common in exported files - warn and keep going
+                    match fs::read_to_string(&mtl_path) {
+                        Ok(text) => materials.extend(parse_mtl(&text).map_err(|e| match e {
+                            ObjError::Parse { line, message } => parse_error(
+                                line_no,
+                                format!("in {} line {}: {}", file, line, message),
+                            ),
+                            other => other,
+                        })?),
+                        Err(e) => eprintln!("Warning: cannot read material library {}: {}", mtl_path.display(), e),
+                    }
+                }
+            }
+            // Smoothing groups, lines, points and other extensions are ignored
+            _ => {}
+        }
+    }
+
+    groups.extend(builder.finish());
+    if groups.is_empty() {
+        return Err(ObjError::Empty);
+    }
+
+    Ok(ObjData { groups, materials })
+}
+
+/// Parse the contents of an MTL material library
+pub fn parse_mtl(source: &str) -> Result<Vec<ObjMaterial>, ObjError> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for (index, raw_line) in source.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let keyword = parts.next().unwrap_or("");
+        let args: Vec<&str> = parts.collect();
+
+        match keyword {
+            "newmtl" => {
+                let Some(name) = args.first() else {
+                    return Err(parse_error(line_no, "newmtl without a material name".to_string()));
+                };
+                materials.push(ObjMaterial { name: name.to_string(), diffuse: DEFAULT_COLOR });
+            }
+            "Kd" => {
+                let color = parse_floats::<3>(&args, line_no, "diffuse color")?;
+                let Some(material) = materials.last_mut() else {
+                    return Err(parse_error(line_no, "Kd before any newmtl".to_string()));
+                };
+                material.diffuse = color;
+            }
+            _ => {}  // Other material properties are not rendered (yet)
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Collects triangles for one group, de-duplicating identical vertices
+struct GroupBuilder {
+    name: String,
+    material: Option<usize>,
+    vertices: Vec<ObjVertex>,
+    indices: Vec<u32>,
+    lookup: HashMap<Corner, u32>,  // (position, uv, normal) -> vertex index
+    needs_normals: bool,           // True if any face had no normals in the file
+    authored_normals: Vec<bool>,   // Per vertex: its corner referenced a vn (kept even if it's zero)
+}
+
+/// Pool indices for one face corner (0-based, already validated)
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Corner {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+impl GroupBuilder {
+    fn new(name: String, material: Option<usize>) -> Self {
+        Self {
+            name,
+            material,
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            lookup: HashMap::new(),
+            needs_normals: false,
+            authored_normals: Vec::new(),
+        }
+    }
+
+    /// Add a polygon, triangulating it as a fan around the first corner
+    fn add_polygon(&mut self, corners: &[Corner], positions: &[[f32; 3]], uvs: &[[f32; 2]], normals: &[[f32; 3]]) {
+        let ids: Vec<u32> = corners.iter()
+            .map(|c| self.vertex_index(*c, positions, uvs, normals))
+            .collect();
+
+        // (0,1,2), (0,2,3), (0,3,4)... works for the convex polygons OBJ exporters write
+        for i in 1..ids.len() - 1 {
+            self.indices.extend_from_slice(&[ids[0], ids[i], ids[i + 1]]);
+        }
+    }
+
+    /// Return the index of an existing identical vertex or append a new one
+    fn vertex_index(&mut self, corner: Corner, positions: &[[f32; 3]], uvs: &[[f32; 2]], normals: &[[f32; 3]]) -> u32 {
+        if let Some(&index) = self.lookup.get(&corner) {
+            return index;
+        }
+
+        if corner.normal.is_none() {
+            self.needs_normals = true;
+        }
+        let index = self.vertices.len() as u32;
+        self.vertices.push(ObjVertex {
+            position: positions[corner.position],
+            normal: corner.normal.map(|n| normals[n]).unwrap_or([0.0; 3]),
+            uv: corner.uv.map(|t| uvs[t]).unwrap_or([0.0; 2]),
+        });
+        self.authored_normals.push(corner.normal.is_some());
+        self.lookup.insert(corner, index);
+        index
+    }
+
+    /// Finish the group, returning None if it has no faces
+    fn finish(mut self) -> Option<ObjGroup> {
+        if self.indices.is_empty() {
+            return None;
+        }
+        if self.needs_normals {
+            self.generate_normals();
+        }
+        Some(ObjGroup {
+            name: self.name,
+            material: self.material,
+            vertices: self.vertices,
+            indices: self.indices,
+        })
+    }
+
+    /// Fill in missing normals by averaging the face normals around each vertex
+    fn generate_normals(&mut self) {
+        let mut accumulated = vec![[0.0f32; 3]; self.vertices.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| self.vertices[i as usize].position);
+            let e1 = sub(b, a);
+            let e2 = sub(c, a);
+            let face = cross(e1, e2);  // Length is proportional to area - big faces weigh more
+            for &i in triangle {
+                let n = &mut accumulated[i as usize];
+                n[0] += face[0];
+                n[1] += face[1];
+                n[2] += face[2];
+            }
+        }
+
+        // Only fill in vertices that came without a normal
+        for ((vertex, n), authored) in self.vertices.iter_mut().zip(accumulated).zip(&self.authored_normals) {
+            if !authored {
+                vertex.normal = normalize(n);
+            }
+        }
+    }
+}
+
+/// Parse one face corner: `v`, `v/vt`, `v//vn` or `v/vt/vn`
+fn parse_corner(text: &str, line_no: usize, position_count: usize, uv_count: usize, normal_count: usize) -> Result<Corner, ObjError> {
+    let mut fields = text.split('/');
+    let position = resolve_index(fields.next(), position_count, line_no, "vertex")?
+        .ok_or_else(|| parse_error(line_no, format!("face corner '{}' has no vertex index", text)))?;
+    let uv = resolve_index(fields.next(), uv_count, line_no, "texture coordinate")?;
+    let normal = resolve_index(fields.next(), normal_count, line_no, "normal")?;
+    Ok(Corner { position, uv, normal })
+}
+
+/// Convert a 1-based (or negative, relative) OBJ index into a 0-based pool index
+fn resolve_index(field: Option<&str>, count: usize, line_no: usize, what: &str) -> Result<Option<usize>, ObjError> {
+    let Some(field) = field.filter(|f| !f.is_empty()) else {
+        return Ok(None);  // Field omitted, e.g. the middle of `v//vn`
+    };
+    let raw: i64 = field.parse()
+        .map_err(|_| parse_error(line_no, format!("invalid {} index '{}'", what, field)))?;
+
+    // Positive indices count from the start, negative ones from the end of the pool
+    let resolved = if raw > 0 { raw - 1 } else { count as i64 + raw };
+    if raw == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(parse_error(line_no, format!("{} index {} out of range (have {})", what, raw, count)));
+    }
+    Ok(Some(resolved as usize))
+}
+
+/// Parse exactly N floats from the argument list (extra values like `w` are ignored)
+fn parse_floats<const N: usize>(args: &[&str], line_no: usize, what: &str) -> Result<[f32; N], ObjError> {
+    if args.len() < N {
+        return Err(parse_error(line_no, format!("{} needs {} values, got {}", what, N, args.len())));
+    }
+    let mut out = [0.0f32; N];
+    for (slot, text) in out.iter_mut().zip(args) {
+        *slot = parse_float(Some(text), line_no, what)?;
+    }
+    Ok(out)
+}
+
+fn parse_float(text: Option<&str>, line_no: usize, what: &str) -> Result<f32, ObjError> {
+    let text = text.ok_or_else(|| parse_error(line_no, format!("missing {}", what)))?;
+    text.parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())  // Reject NaN/inf - they would poison the GPU buffers
+        .ok_or_else(|| parse_error(line_no, format!("invalid {} value '{}'", what, text)))
+}
+
+fn parse_error(line: usize, message: String) -> ObjError {
+    ObjError::Parse { line, message }
+}
+
+// Tiny vector helpers so the parser doesn't depend on the math library
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len > 0.0 { [v[0] / len, v[1] / len, v[2] / len] } else { [0.0, 1.0, 0.0] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path to a file in tests/fixtures (CARGO_MANIFEST_DIR = repo root)
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+    }
+
+    #[test]
+    fn parses_triangle_with_all_attributes() {
+        let data = load(&fixture("triangle.obj")).expect("fixture should parse");
+        assert_eq!(data.groups.len(), 1);
+        assert_eq!(data.triangle_count(), 1);
+
+        let group = &data.groups[0];
+        assert_eq!(group.vertices.len(), 3);
+        assert_eq!(group.vertices[1].position, [1.0, 0.0, 0.0]);
+        assert_eq!(group.vertices[1].uv, [1.0, 0.0]);
+        assert_eq!(group.vertices[1].normal, [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn triangulates_quads_and_ngons() {
+        let data = load(&fixture("ngon.obj")).expect("fixture should parse");
+        // One quad (2 triangles) + one pentagon (3 triangles)
+        assert_eq!(data.triangle_count(), 5);
+        // Shared corners are de-duplicated within a group
+        assert_eq!(data.groups[0].vertices.len(), 6);
+    }
+
+    #[test]
+    fn splits_groups_and_resolves_materials() {
+        let data = load(&fixture("groups.obj")).expect("fixture should parse");
+        assert_eq!(data.materials.len(), 2);
+
+        let names: Vec<&str> = data.groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["left", "right", "right"]);
+
+        // `usemtl` inside a group starts a new run with the new color
+        assert_eq!(data.group_color(&data.groups[0]), [1.0, 0.0, 0.0]);
+        assert_eq!(data.group_color(&data.groups[1]), [1.0, 0.0, 0.0]);
+        assert_eq!(data.group_color(&data.groups[2]), [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn generates_missing_normals() {
+        let data = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n", None).unwrap();
+        for vertex in &data.groups[0].vertices {
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);  // CCW triangle in the XY plane faces +Z
+        }
+    }
+
+    #[test]
+    fn keeps_authored_zero_normals() {
+        // The first face's normals come from the file, zero or not; the second face has none
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 0\nf 1//1 2//1 3//1\nf 1 2 3\n";
+        let group = &parse(obj, None).unwrap().groups[0];
+        assert_eq!(group.vertices.len(), 6);
+        assert!(group.vertices[..3].iter().all(|vertex| vertex.normal == [0.0; 3]));
+        assert!(group.vertices[3..].iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn supports_negative_indices() {
+        let data = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n", None).unwrap();
+        assert_eq!(data.groups[0].vertices[2].position, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn reports_out_of_range_index_with_line() {
+        let err = load(&fixture("bad_index.obj")).unwrap_err();
+        match err {
+            ObjError::Parse { line, ref message } => {
+                assert_eq!(line, 5);
+                assert!(message.contains("out of range"), "unexpected message: {}", message);
+            }
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_malformed_numbers() {
+        let err = parse("v 0 zero 0\n", None).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid vertex position value 'zero'");
+    }
+
+    #[test]
+    fn rejects_degenerate_faces_and_empty_files() {
+        assert!(matches!(parse("v 0 0 0\nv 1 0 0\nf 1 2\n", None), Err(ObjError::Parse { line: 3, .. })));
+        assert!(matches!(parse("# nothing here\nv 0 0 0\n", None), Err(ObjError::Empty)));
+    }
+
+    #[test]
+    fn missing_file_is_an_io_error() {
+        assert!(matches!(load(&fixture("does_not_exist.obj")), Err(ObjError::Io(..))));
+    }
+}
@@ -0,0 +1,76 @@
+// Physically based shading - the glTF 2.0 metallic-roughness model (Cook-Torrance GGX)
+use glow::HasContext;  // Trait providing OpenGL function methods
+
+use super::uniforms::UniformLocations;
+
+// Fragment shader - shares the vertex shader and the included light code with Blinn-Phong
+// Edited in shaders/pbr.frag, reloaded on save
+pub const PBR_FRAGMENT_SHADER_SOURCE: &str = include_str!("../../shaders/pbr.frag");
+
+/// Which lighting model an object is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShadingModel {
+    BlinnPhong,  // Simple diffuse + highlight, vertex colors shown as-is
+    Pbr,         // glTF metallic-roughness
+}
+
+impl ShadingModel {
+    pub const ALL: [ShadingModel; 2] = [ShadingModel::BlinnPhong, ShadingModel::Pbr];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShadingModel::BlinnPhong => "Blinn-Phong",
+            ShadingModel::Pbr => "PBR",
+        }
+    }
+}
+
+/// Metallic-roughness parameters, as in a glTF material
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PbrMaterial {
+    pub base_color: [f32; 3],  // Multiplies the vertex color
+    pub metallic: f32,         // 0..1
+    pub roughness: f32,        // 0..1, perceptual
+    pub occlusion: f32,        // Ambient occlusion 0..1 (1 = none)
+    pub emissive: [f32; 3],    // Linear
+}
+
+impl Default for PbrMaterial {
+    /// glTF's defaults are fully metallic and rough; a plain dielectric previews untextured meshes better
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.5,
+            occlusion: 1.0,
+            emissive: [0.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl PbrMaterial {
+    /// Read the factors of a glTF material (its base color is baked into the vertex colors by the importer;
+    /// textures are read separately by gltf_import)
+    pub fn from_gltf(material: &gltf::Material) -> Self {
+        let pbr = material.pbr_metallic_roughness();
+        Self {
+            base_color: [1.0, 1.0, 1.0],
+            metallic: pbr.metallic_factor(),
+            roughness: pbr.roughness_factor(),
+            occlusion: 1.0,  // Scales the occlusion texture, if any
+            emissive: material.emissive_factor(),
+        }
+    }
+
+    /// Set the `u_pbr` struct uniform of the program in use
+    pub fn upload(&self, gl: &glow::Context, locations: &UniformLocations) {
+        unsafe {
+            let location = |name: &str| locations.get(name);
+            gl.uniform_3_f32_slice(location("u_pbr.base_color"), &self.base_color);
+            gl.uniform_1_f32(location("u_pbr.metallic"), self.metallic);
+            gl.uniform_1_f32(location("u_pbr.roughness"), self.roughness);
+            gl.uniform_1_f32(location("u_pbr.occlusion"), self.occlusion);
+            gl.uniform_3_f32_slice(location("u_pbr.emissive"), &self.emissive);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// GPU picking - renders object IDs into an offscreen integer buffer and reads back one pixel
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::sync::Arc;    // Shared OpenGL context so we can free the buffer on drop
+
+// ID pass shaders - same transform as the main shader, but output the object ID instead of a color
+pub const ID_VERTEX_SHADER_SOURCE: &str = r#"#version 330 core
+layout(location = 0) in vec3 position;
+
+uniform mat4 u_projection;
+uniform mat4 u_view;
+uniform mat4 u_model;
+
+void main() {
+    gl_Position = u_projection * u_view * u_model * vec4(position, 1.0);
+}
+"#;
+
+pub const ID_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+uniform uint u_object_id;  // Object index + 1 (0 = background)
+
+out uint object_id;        // Written to the R32UI attachment
+
+void main() {
+    object_id = u_object_id;
+}
+"#;
+
+/// A pointer position converted to a pixel of a GL render target covering the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickTarget {
+    pub width: i32,   // Target size in physical pixels
+    pub height: i32,
+    pub x: i32,       // Pixel column from the left
+    pub y: i32,       // Pixel row from the bottom (GL convention)
+}
+
+impl PickTarget {
+    /// Map a pointer position (egui points, Y down from the window top) into a target covering `rect`
+    /// Returns None if the pointer is outside the rect
+    pub fn from_pointer(rect: egui::Rect, pointer: egui::Pos2, pixels_per_point: f32) -> Option<Self> {
+        let width = (rect.width() * pixels_per_point).round() as i32;
+        let height = (rect.height() * pixels_per_point).round() as i32;
+        let local = (pointer - rect.min) * pixels_per_point;  // Offset from the rect's top-left
+        let x = local.x.floor() as i32;
+        let row_from_top = local.y.floor() as i32;
+        if width <= 0 || height <= 0 || x < 0 || x >= width || row_from_top < 0 || row_from_top >= height {
+            return None;
+        }
+        // egui counts rows down from the top, GL up from the bottom
+        Some(Self { width, height, x, y: height - 1 - row_from_top })
+    }
+}
+
+/// Offscreen framebuffer with an integer ID attachment and a depth buffer
+pub struct IdBuffer {
+    gl: Arc<glow::Context>,
+    framebuffer: glow::Framebuffer,
+    ids: glow::Renderbuffer,    // R32UI - one object ID per pixel
+    depth: glow::Renderbuffer,  // So nearer objects win
+    width: i32,
+    height: i32,
+}
+
+impl IdBuffer {
+    /// Allocate a buffer of the given size in pixels
+    pub fn new(gl: Arc<glow::Context>, width: i32, height: i32) -> Result<Self, String> {
+        unsafe {
+            let framebuffer = gl.create_framebuffer()?;
+            let ids = gl.create_renderbuffer()?;
+            let depth = gl.create_renderbuffer()?;
+
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(ids));
+            gl.renderbuffer_storage(glow::RENDERBUFFER, glow::R32UI, width, height);
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+            gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, width, height);
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(ids));
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+            let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+
+            // Build the value first so Drop cleans up if the framebuffer is unusable
+            let buffer = Self { gl, framebuffer, ids, depth, width, height };
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                return Err(format!("ID framebuffer incomplete (status 0x{:X})", status));
+            }
+            Ok(buffer)
+        }
+    }
+
+    /// Size in pixels
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Bind for drawing and clear to "no object" (the caller restores the previous framebuffer)
+    pub fn begin(&self) {
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+            self.gl.viewport(0, 0, self.width, self.height);
+            self.gl.disable(glow::SCISSOR_TEST);  // egui may leave a clip rect set
+            self.gl.depth_mask(true);
+            self.gl.clear_buffer_u32_slice(glow::COLOR, 0, &[0, 0, 0, 0]);
+            self.gl.clear(glow::DEPTH_BUFFER_BIT);
+        }
+    }
+
+    /// Read the ID at a pixel (bottom-up coordinates); the buffer must still be bound
+    pub fn read(&self, x: i32, y: i32) -> u32 {
+        let mut id = [0u8; 4];
+        unsafe {
+            self.gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            self.gl.read_pixels(x, y, 1, 1, glow::RED_INTEGER, glow::UNSIGNED_INT, glow::PixelPackData::Slice(Some(&mut id)));
+        }
+        u32::from_ne_bytes(id)
+    }
+}
+
+// Free GPU memory when the buffer is replaced (viewport resized) or the renderer goes away
+impl Drop for IdBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_framebuffer(self.framebuffer);
+            self.gl.delete_renderbuffer(self.ids);
+            self.gl.delete_renderbuffer(self.depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_maps_to_bottom_up_pixels() {
+        // A 200x100 point viewport 50 points below the window top, on a 2x display
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 50.0), egui::vec2(200.0, 100.0));
+
+        let top_left = PickTarget::from_pointer(rect, egui::pos2(10.0, 50.0), 2.0).unwrap();
+        assert_eq!(top_left, PickTarget { width: 400, height: 200, x: 0, y: 199 });
+
+        let bottom_right = PickTarget::from_pointer(rect, egui::pos2(209.9, 149.9), 2.0).unwrap();
+        assert_eq!((bottom_right.x, bottom_right.y), (399, 0));
+
+        // Halfway down the rect is halfway up the target
+        let middle = PickTarget::from_pointer(rect, egui::pos2(110.0, 100.0), 1.0).unwrap();
+        assert_eq!((middle.x, middle.y), (100, 49));
+
+        assert_eq!(PickTarget::from_pointer(rect, egui::pos2(5.0, 60.0), 1.0), None);
+        assert_eq!(PickTarget::from_pointer(rect, egui::pos2(20.0, 150.0), 1.0), None);
+    }
+}
@@ -0,0 +1,376 @@
+// Post-processing chain - turns the viewport's linear HDR scene into the display image
+// Order: exposure -> bloom -> tone mapping -> vignette -> gamma. Bloom is gathered from the exposed
+// HDR image (only values above 1 can glow), so it is added before tone mapping squeezes them into 0..1
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::sync::Arc;
+
+use super::environment::FULLSCREEN_VERTEX_SHADER_SOURCE;
+use super::framebuffer::{ColorFormat, ColorTarget, ViewportTargets};
+use super::uniforms::UniformLocations;
+
+/// Curve that maps HDR values into the displayable 0..1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapOperator {
+    /// x / (1 + x) - gentle, desaturates highlights
+    Reinhard,
+    /// Narkowicz's fit of the ACES reference curve - contrasty, the common default
+    #[default]
+    Aces,
+    /// Hable's Uncharted 2 curve - soft toe and shoulder
+    Filmic,
+}
+
+impl ToneMapOperator {
+    /// Every operator, in menu order
+    pub const ALL: [ToneMapOperator; 3] = [ToneMapOperator::Reinhard, ToneMapOperator::Aces, ToneMapOperator::Filmic];
+
+    /// Display name for menus
+    pub fn name(self) -> &'static str {
+        match self {
+            ToneMapOperator::Reinhard => "Reinhard",
+            ToneMapOperator::Aces => "ACES",
+            ToneMapOperator::Filmic => "Filmic",
+        }
+    }
+
+    /// Value of the composite shader's u_tone_mapping (0 = off)
+    fn shader_index(self) -> i32 {
+        match self {
+            ToneMapOperator::Reinhard => 1,
+            ToneMapOperator::Aces => 2,
+            ToneMapOperator::Filmic => 3,
+        }
+    }
+}
+
+/// Scale the scene's brightness before anything else sees it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+    pub enabled: bool,
+    pub stops: f32,  // EV: +1 doubles the light, -1 halves it
+}
+
+/// Glow around bright areas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bloom {
+    pub enabled: bool,
+    pub threshold: f32,  // Brightness (after exposure) where glow starts
+    pub intensity: f32,  // How much of the blurred glow is added back
+    pub radius: i32,     // Blur passes at half resolution - each one widens the glow
+}
+
+/// HDR -> 0..1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMapping {
+    pub enabled: bool,  // Off = values above 1 clip
+    pub operator: ToneMapOperator,
+}
+
+/// Darkened corners
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vignette {
+    pub enabled: bool,
+    pub strength: f32,  // Darkening at the corners, 0..1
+    pub radius: f32,    // Where darkening starts: 0 = center, 1 = corners
+    pub softness: f32,  // Width of the falloff
+}
+
+/// Linear -> display encoding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma {
+    pub enabled: bool,  // Off shows the raw linear values (too dark) - for checking the chain
+    pub gamma: f32,     // 2.2 approximates sRGB
+}
+
+/// Every pass's switch and parameters
+/// The defaults reproduce the image from before the chain existed: neutral exposure, 2.2 gamma, nothing else
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostSettings {
+    pub exposure: Exposure,
+    pub bloom: Bloom,
+    pub tone_mapping: ToneMapping,
+    pub vignette: Vignette,
+    pub gamma: Gamma,
+}
+
+impl Default for PostSettings {
+    fn default() -> Self {
+        Self {
+            exposure: Exposure { enabled: true, stops: 0.0 },
+            bloom: Bloom { enabled: false, threshold: 1.0, intensity: 0.5, radius: 4 },
+            tone_mapping: ToneMapping { enabled: false, operator: ToneMapOperator::default() },
+            vignette: Vignette { enabled: false, strength: 0.4, radius: 0.5, softness: 0.5 },
+            gamma: Gamma { enabled: true, gamma: 2.2 },
+        }
+    }
+}
+
+impl PostSettings {
+    /// Gamma only - for images that are data (normals, depth...) and must not be exposed or tone mapped
+    pub fn display_only() -> Self {
+        let defaults = Self::default();
+        Self {
+            exposure: Exposure { enabled: false, ..defaults.exposure },
+            bloom: Bloom { enabled: false, ..defaults.bloom },
+            tone_mapping: ToneMapping { enabled: false, ..defaults.tone_mapping },
+            vignette: Vignette { enabled: false, ..defaults.vignette },
+            gamma: defaults.gamma,
+        }
+    }
+
+    /// Factor the scene is multiplied by (1 while exposure is off)
+    pub fn exposure_scale(&self) -> f32 {
+        if self.exposure.enabled { self.exposure.stops.exp2() } else { 1.0 }
+    }
+}
+
+// Bright-pass: keep what is above the threshold, written at half size (linear sampling averages 2x2 texels)
+const BLOOM_EXTRACT_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+uniform sampler2D u_scene;
+uniform float u_exposure;
+uniform float u_threshold;
+
+void main() {
+    vec3 color = texture(u_scene, v_ndc * 0.5 + 0.5).rgb * u_exposure;
+    float brightness = max(color.r, max(color.g, color.b));
+    // Scale rather than subtract, so the glow keeps the source's hue
+    float excess = max(brightness - u_threshold, 0.0) / max(brightness, 1e-4);
+    frag_color = vec4(color * excess, 1.0);
+}
+"#;
+
+// One direction of a 9-tap Gaussian, as 5 bilinear taps
+const BLUR_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+uniform sampler2D u_source;
+uniform vec2 u_direction;  // One texel along the blur axis
+
+const float OFFSETS[3] = float[](0.0, 1.3846153846, 3.2307692308);
+const float WEIGHTS[3] = float[](0.2270270270, 0.3162162162, 0.0702702703);
+
+void main() {
+    vec2 uv = v_ndc * 0.5 + 0.5;
+    vec3 sum = texture(u_source, uv).rgb * WEIGHTS[0];
+    for (int i = 1; i < 3; i++) {
+        sum += texture(u_source, uv + u_direction * OFFSETS[i]).rgb * WEIGHTS[i];
+        sum += texture(u_source, uv - u_direction * OFFSETS[i]).rgb * WEIGHTS[i];
+    }
+    frag_color = vec4(sum, 1.0);
+}
+"#;
+
+// The per-pixel passes in chain order - a disabled pass gets neutral uniforms from the app
+const COMPOSITE_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+uniform sampler2D u_scene;          // Linear HDR, premultiplied alpha (cleared to transparent)
+uniform sampler2D u_bloom;          // Blurred bright-pass, half size
+uniform float u_exposure;           // 1 = unchanged
+uniform float u_bloom_intensity;    // 0 = no bloom
+uniform int u_tone_mapping;         // 0 off, 1 Reinhard, 2 ACES, 3 filmic
+uniform float u_vignette_strength;  // 0 = no vignette
+uniform float u_vignette_radius;
+uniform float u_vignette_softness;
+uniform float u_gamma;              // 1 = leave linear
+
+vec3 aces(vec3 x) {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), 0.0, 1.0);
+}
+
+vec3 hable(vec3 x) {
+    const float A = 0.15, B = 0.50, C = 0.10, D = 0.20, E = 0.02, F = 0.30;
+    return (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F;
+}
+
+vec3 filmic(vec3 x) {
+    const float WHITE = 11.2;          // Input that maps to 1
+    const float EXPOSURE_BIAS = 2.0;   // The curve expects a brighter input than the others
+    return hable(x * EXPOSURE_BIAS) / hable(vec3(WHITE));
+}
+
+void main() {
+    vec2 uv = v_ndc * 0.5 + 0.5;
+    vec4 scene = texture(u_scene, uv);
+
+    vec3 color = scene.rgb * u_exposure;
+    color += texture(u_bloom, uv).rgb * u_bloom_intensity;
+
+    if (u_tone_mapping == 1) {
+        color = color / (1.0 + color);
+    } else if (u_tone_mapping == 2) {
+        color = aces(color);
+    } else if (u_tone_mapping == 3) {
+        color = filmic(color);
+    }
+
+    // 0 at the center, 1 in the corners
+    float from_center = length(v_ndc) * 0.70710678;
+    color *= 1.0 - u_vignette_strength * smoothstep(u_vignette_radius, u_vignette_radius + u_vignette_softness, from_center);
+
+    color = pow(max(color, 0.0), vec3(1.0 / u_gamma));
+    frag_color = vec4(color, scene.a);
+}
+"#;
+
+/// One pass's program and its uniforms, resolved once
+struct PostProgram {
+    program: glow::Program,
+    locations: UniformLocations,
+}
+
+impl PostProgram {
+    fn new(gl: &glow::Context, fragment: &str) -> Result<Self, String> {
+        let program = super::shader::create_program(gl, FULLSCREEN_VERTEX_SHADER_SOURCE, fragment)?;
+        Ok(Self { program, locations: UniformLocations::new(gl, program) })
+    }
+}
+
+/// The chain's programs, shared by every viewport
+pub struct PostPrograms {
+    extract: PostProgram,
+    blur: PostProgram,
+    composite: PostProgram,
+}
+
+impl PostPrograms {
+    pub fn new(gl: &glow::Context) -> Result<Self, String> {
+        let programs = Self {
+            extract: PostProgram::new(gl, BLOOM_EXTRACT_FRAGMENT_SHADER_SOURCE)?,
+            blur: PostProgram::new(gl, BLUR_FRAGMENT_SHADER_SOURCE)?,
+            composite: PostProgram::new(gl, COMPOSITE_FRAGMENT_SHADER_SOURCE)?,
+        };
+        // Sampler units never change
+        unsafe {
+            for (pass, samplers) in [
+                (&programs.extract, &[("u_scene", 0)][..]),
+                (&programs.blur, &[("u_source", 0)][..]),
+                (&programs.composite, &[("u_scene", 0), ("u_bloom", 1)][..]),
+            ] {
+                gl.use_program(Some(pass.program));
+                for (name, unit) in samplers {
+                    gl.uniform_1_i32(pass.locations.get(name), *unit);
+                }
+            }
+            gl.use_program(None);
+        }
+        Ok(programs)
+    }
+}
+
+/// Run the chain from `targets.scene` (already resolved) into `targets.output`
+/// Bloom's half-size targets are created, resized or freed here to follow the settings
+pub fn run(gl: &Arc<glow::Context>, programs: &PostPrograms, vao: glow::VertexArray, settings: &PostSettings, targets: &mut ViewportTargets) -> Result<(), String> {
+    let (width, height) = targets.scene.size();
+    targets.output.resize(width, height)?;
+    let exposure = settings.exposure_scale();
+
+    unsafe {
+        gl.disable(glow::DEPTH_TEST);
+        gl.disable(glow::BLEND);
+        gl.bind_vertex_array(Some(vao));
+        let draw = || gl.draw_arrays(glow::TRIANGLES, 0, 3);
+
+        // Bloom: bright-pass at half size, then blur back and forth between the two targets
+        let bloom = if settings.bloom.enabled {
+            let (half_width, half_height) = ((width / 2).max(1), (height / 2).max(1));
+            let bloom = match &mut targets.bloom {
+                Some(bloom) => bloom,
+                None => targets.bloom.insert([
+                    ColorTarget::new(gl.clone(), half_width, half_height, ColorFormat::Rgba16F)?,
+                    ColorTarget::new(gl.clone(), half_width, half_height, ColorFormat::Rgba16F)?,
+                ]),
+            };
+            for target in bloom.iter_mut() {
+                target.resize(half_width, half_height)?;
+            }
+
+            bloom[0].bind();
+            let extract = &programs.extract;
+            gl.use_program(Some(extract.program));
+            gl.active_texture(glow::TEXTURE0);
+            gl.bind_texture(glow::TEXTURE_2D, Some(targets.scene.texture()));
+            gl.uniform_1_f32(extract.locations.get("u_exposure"), exposure);
+            gl.uniform_1_f32(extract.locations.get("u_threshold"), settings.bloom.threshold);
+            draw();
+
+            gl.use_program(Some(programs.blur.program));
+            let direction = programs.blur.locations.get("u_direction");
+            let texel = texel_size(half_width, half_height);
+            for _ in 0..settings.bloom.radius.max(1) {
+                for (from, to, axis) in [(0, 1, [texel[0], 0.0]), (1, 0, [0.0, texel[1]])] {
+                    bloom[to].bind();
+                    gl.bind_texture(glow::TEXTURE_2D, Some(bloom[from].texture()));
+                    gl.uniform_2_f32(direction, axis[0], axis[1]);
+                    draw();
+                }
+            }
+            Some(bloom[0].texture())
+        } else {
+            targets.bloom = None;  // Free them while bloom is off
+            None
+        };
+
+        // Everything per-pixel in one pass; disabled passes get neutral values
+        targets.output.bind();
+        let composite = &programs.composite;
+        gl.use_program(Some(composite.program));
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, Some(targets.scene.texture()));
+        gl.active_texture(glow::TEXTURE1);
+        gl.bind_texture(glow::TEXTURE_2D, bloom);
+        let tone_mapping = if settings.tone_mapping.enabled { settings.tone_mapping.operator.shader_index() } else { 0 };
+        let vignette = &settings.vignette;
+        gl.uniform_1_f32(composite.locations.get("u_exposure"), exposure);
+        gl.uniform_1_f32(composite.locations.get("u_bloom_intensity"), if bloom.is_some() { settings.bloom.intensity } else { 0.0 });
+        gl.uniform_1_i32(composite.locations.get("u_tone_mapping"), tone_mapping);
+        gl.uniform_1_f32(composite.locations.get("u_vignette_strength"), if vignette.enabled { vignette.strength } else { 0.0 });
+        gl.uniform_1_f32(composite.locations.get("u_vignette_radius"), vignette.radius);
+        gl.uniform_1_f32(composite.locations.get("u_vignette_softness"), vignette.softness.max(1e-3));
+        gl.uniform_1_f32(composite.locations.get("u_gamma"), if settings.gamma.enabled { settings.gamma.gamma.max(0.1) } else { 1.0 });
+        draw();
+
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        gl.bind_vertex_array(None);
+        gl.enable(glow::DEPTH_TEST);
+    }
+    Ok(())
+}
+
+/// Size of one texel in UV units
+fn texel_size(width: i32, height: i32) -> [f32; 2] {
+    [1.0 / width as f32, 1.0 / height as f32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposure_is_in_stops_and_neutral_when_off() {
+        let mut settings = PostSettings::default();
+        assert_eq!(settings.exposure_scale(), 1.0);
+        settings.exposure.stops = 2.0;
+        assert_eq!(settings.exposure_scale(), 4.0);
+        settings.exposure.stops = -1.0;
+        assert_eq!(settings.exposure_scale(), 0.5);
+        settings.exposure.enabled = false;
+        assert_eq!(settings.exposure_scale(), 1.0);
+    }
+
+    #[test]
+    fn defaults_only_encode_gamma() {
+        // The lit shaders used to apply 2.2 gamma themselves - the default chain must match them
+        let settings = PostSettings::default();
+        assert!(!settings.bloom.enabled && !settings.tone_mapping.enabled && !settings.vignette.enabled);
+        assert!(settings.gamma.enabled);
+        assert_eq!(settings.gamma.gamma, 2.2);
+    }
+}
@@ -0,0 +1,208 @@
+// GLSL preprocessing before the driver sees a shader: #include and injected #defines
+// The driver reports errors against the flattened text, so every output line is mapped back to its file
+use std::collections::HashSet;
+
+/// Which file each line of an assembled shader came from
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    segments: Vec<(usize, String, usize)>,  // (first assembled line, file, its line number in the file), in order
+}
+
+impl SourceMap {
+    /// Lines from `assembled_line` on come from `file`, starting at its line `file_line` (both 1-based)
+    pub fn push(&mut self, assembled_line: usize, file: &str, file_line: usize) {
+        self.segments.push((assembled_line, file.to_string(), file_line));
+    }
+
+    /// File and line within it for a line of the assembled source
+    pub fn locate(&self, line: usize) -> Option<(&str, usize)> {
+        let (start, file, first) = self.segments.iter().rev().find(|(start, _, _)| *start <= line)?;
+        Some((file.as_str(), first + line - start))
+    }
+}
+
+/// Shader text as handed to the driver, and where its lines came from
+#[derive(Debug, Clone)]
+pub struct AssembledSource {
+    pub text: String,
+    pub map: SourceMap,
+}
+
+impl AssembledSource {
+    /// Text of a 1-based line
+    pub fn line(&self, line: usize) -> Option<&str> {
+        self.text.lines().nth(line.checked_sub(1)?)
+    }
+}
+
+/// Why a shader couldn't be assembled
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreprocessError {
+    NotFound { file: String, from: Option<(String, usize)> },  // Missing file, and the #include naming it
+    Cycle { chain: Vec<String>, at: (String, usize) },          // Files that include each other, and where it closes
+    Malformed { at: (String, usize) },                          // #include without a "quoted" name
+}
+
+impl PreprocessError {
+    /// File and line the error points at, if it came from an #include
+    pub fn location(&self) -> Option<&(String, usize)> {
+        match self {
+            PreprocessError::NotFound { from, .. } => from.as_ref(),
+            PreprocessError::Cycle { at, .. } | PreprocessError::Malformed { at } => Some(at),
+        }
+    }
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreprocessError::NotFound { file, .. } => write!(f, "\"{}\" not found in the shader search path", file),
+            PreprocessError::Cycle { chain, .. } => write!(f, "include cycle: {}", chain.join(" -> ")),
+            PreprocessError::Malformed { .. } => write!(f, "expected #include \"file\""),
+        }
+    }
+}
+
+/// Flatten `entry` and everything it includes, with `defines` ("NAME" or "NAME value") right after #version
+/// `read` finds a file by the name used in #include (the search path is up to the caller)
+/// A file is only inlined once - later includes of it are dropped, so shared headers need no guards
+pub fn preprocess(entry: &str, defines: &[&str], read: &dyn Fn(&str) -> Option<String>) -> Result<AssembledSource, PreprocessError> {
+    let text = read(entry).ok_or_else(|| PreprocessError::NotFound { file: entry.to_string(), from: None })?;
+    let mut assembler = Assembler { read, out: String::new(), map: SourceMap::default(), lines: 0, stack: Vec::new(), done: HashSet::new() };
+    assembler.file(entry, &text, Some(defines))?;
+    Ok(AssembledSource { text: assembler.out, map: assembler.map })
+}
+
+/// Output so far, plus the include stack for cycle detection
+struct Assembler<'a> {
+    read: &'a dyn Fn(&str) -> Option<String>,
+    out: String,
+    map: SourceMap,
+    lines: usize,            // Lines written to `out`
+    stack: Vec<String>,      // Files being inlined, outermost first
+    done: HashSet<String>,   // Files already inlined
+}
+
+impl Assembler<'_> {
+    /// Inline one file; `defines` is Some only for the entry point
+    fn file(&mut self, name: &str, text: &str, defines: Option<&[&str]>) -> Result<(), PreprocessError> {
+        self.stack.push(name.to_string());
+        let has_version = text.lines().next().is_some_and(|line| line.trim_start().starts_with("#version"));
+        if let Some(defines) = defines.filter(|_| !has_version) {
+            self.defines(defines);  // No #version to go after - they lead instead
+        }
+
+        let mut resume = true;  // Start a new map segment at the next line written
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            if let Some(rest) = line.trim_start().strip_prefix("#include") {
+                let at = (name.to_string(), number);
+                let target = rest.trim().strip_prefix('"').and_then(|rest| rest.strip_suffix('"'))
+                    .filter(|target| !target.is_empty())
+                    .ok_or_else(|| PreprocessError::Malformed { at: at.clone() })?;
+                if self.stack.iter().any(|open| open == target) {
+                    let mut chain = self.stack.clone();
+                    chain.push(target.to_string());
+                    return Err(PreprocessError::Cycle { chain, at });
+                }
+                if !self.done.contains(target) {
+                    let included = (self.read)(target)
+                        .ok_or_else(|| PreprocessError::NotFound { file: target.to_string(), from: Some(at) })?;
+                    self.file(target, &included, None)?;
+                }
+                resume = true;
+                continue;  // The directive itself is dropped
+            }
+
+            if resume {
+                self.map.push(self.lines + 1, name, number);
+                resume = false;
+            }
+            self.line(line);
+            if index == 0 && has_version {
+                if let Some(defines) = defines {
+                    self.defines(defines);
+                    resume = true;
+                }
+            }
+        }
+
+        self.stack.pop();
+        self.done.insert(name.to_string());
+        Ok(())
+    }
+
+    /// The injected #defines, mapped to a pseudo-file so errors in them still say where they came from
+    fn defines(&mut self, defines: &[&str]) {
+        if defines.is_empty() {
+            return;
+        }
+        self.map.push(self.lines + 1, "<defines>", 1);
+        for define in defines {
+            self.line(&format!("#define {}", define));
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        self.out.push_str(line);
+        self.out.push('\n');
+        self.lines += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader over an in-memory set of files
+    fn files(files: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| files.iter().find(|(file, _)| *file == name).map(|(_, text)| text.to_string())
+    }
+
+    #[test]
+    fn inlines_includes_and_maps_lines_back() {
+        let read = files(&[
+            ("main.frag", "#version 330 core\n#include \"common.glsl\"\nvoid main() {}\n"),
+            ("common.glsl", "// common\nfloat helper() { return 1.0; }\n"),
+        ]);
+        let source = preprocess("main.frag", &["SHADOWS", "LEVEL 2"], &read).unwrap();
+        assert_eq!(source.text, "#version 330 core\n#define SHADOWS\n#define LEVEL 2\n// common\nfloat helper() { return 1.0; }\nvoid main() {}\n");
+        assert_eq!(source.map.locate(1), Some(("main.frag", 1)));
+        assert_eq!(source.map.locate(3), Some(("<defines>", 2)));
+        assert_eq!(source.map.locate(5), Some(("common.glsl", 2)));
+        assert_eq!(source.map.locate(6), Some(("main.frag", 3)));  // After the dropped #include line
+        assert_eq!(source.line(5), Some("float helper() { return 1.0; }"));
+    }
+
+    #[test]
+    fn detects_cycles_but_inlines_shared_headers_once() {
+        let read = files(&[
+            ("a.glsl", "#include \"b.glsl\"\n"),
+            ("b.glsl", "// b\n#include \"a.glsl\"\n"),
+        ]);
+        let error = preprocess("a.glsl", &[], &read).unwrap_err();
+        assert_eq!(error, PreprocessError::Cycle {
+            chain: vec!["a.glsl".to_string(), "b.glsl".to_string(), "a.glsl".to_string()],
+            at: ("b.glsl".to_string(), 2),
+        });
+
+        let read = files(&[
+            ("main.frag", "#include \"x.glsl\"\n#include \"y.glsl\"\n"),
+            ("x.glsl", "#include \"shared.glsl\"\nx\n"),
+            ("y.glsl", "#include \"shared.glsl\"\ny\n"),
+            ("shared.glsl", "shared\n"),
+        ]);
+        assert_eq!(preprocess("main.frag", &[], &read).unwrap().text, "shared\nx\ny\n");
+    }
+
+    #[test]
+    fn reports_missing_and_malformed_includes_where_they_are() {
+        let read = files(&[("main.frag", "#version 330 core\n\n#include \"nope.glsl\"\n#include <angle.glsl>\n")]);
+        let error = preprocess("main.frag", &[], &read).unwrap_err();
+        assert_eq!(error.location(), Some(&("main.frag".to_string(), 3)));
+        assert_eq!(error.to_string(), "\"nope.glsl\" not found in the shader search path");
+
+        let read = files(&[("main.frag", "#include <angle.glsl>\n")]);
+        assert_eq!(preprocess("main.frag", &[], &read).unwrap_err(), PreprocessError::Malformed { at: ("main.frag".to_string(), 1) });
+    }
+}
@@ -0,0 +1,403 @@
+// Procedural primitives - spheres, tori, cylinders and friends with adjustable detail
+use std::collections::HashMap;  // Midpoint cache for icosphere subdivision
+use std::f32::consts::{PI, TAU};  // TAU = 2 * PI (one full turn)
+
+use super::mesh::{MeshData, PrimitiveMode};
+
+/// Parameters for one procedural shape - the variant picks the shape, the fields its size and detail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Primitive {
+    UvSphere { radius: f32, segments: u32, rings: u32 },
+    IcoSphere { radius: f32, subdivisions: u32 },
+    Torus { major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32 },
+    Cylinder { radius: f32, height: f32, segments: u32 },
+    Cone { radius: f32, height: f32, segments: u32 },
+    Plane { width: f32, depth: f32, subdivisions: u32 },
+    Capsule { radius: f32, height: f32, segments: u32, rings: u32 },
+}
+
+/// Generated vertex streams - one entry per vertex in each Vec, plus a triangle list
+#[derive(Debug, Clone, Default)]
+pub struct Geometry {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl Geometry {
+    /// Interleave into a mesh ready for upload (no vertex colors - drawn white)
+    pub fn mesh_data(&self) -> MeshData {
+        MeshData::from_streams(
+            &self.positions,
+            &self.normals,
+            None,
+            Some(&self.uvs),
+            None,  // Tangents are generated from the UVs
+            self.indices.clone(),
+            PrimitiveMode::Triangles,
+        )
+    }
+
+    fn push_vertex(&mut self, position: [f32; 3], normal: [f32; 3], uv: [f32; 2]) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push(uv);
+        (self.positions.len() - 1) as u32
+    }
+}
+
+impl Primitive {
+    /// One unit-sized instance of every shape, in menu order
+    pub fn defaults() -> [Primitive; 7] {
+        [
+            Primitive::UvSphere { radius: 0.5, segments: 32, rings: 16 },
+            Primitive::IcoSphere { radius: 0.5, subdivisions: 2 },
+            Primitive::Torus { major_radius: 0.5, minor_radius: 0.2, major_segments: 32, minor_segments: 16 },
+            Primitive::Cylinder { radius: 0.5, height: 1.0, segments: 32 },
+            Primitive::Cone { radius: 0.5, height: 1.0, segments: 32 },
+            Primitive::Plane { width: 2.0, depth: 2.0, subdivisions: 1 },
+            Primitive::Capsule { radius: 0.3, height: 0.8, segments: 32, rings: 8 },
+        ]
+    }
+
+    /// Human-readable shape name for menus and object names
+    pub fn name(&self) -> &'static str {
+        match self {
+            Primitive::UvSphere { .. } => "UV Sphere",
+            Primitive::IcoSphere { .. } => "Ico Sphere",
+            Primitive::Torus { .. } => "Torus",
+            Primitive::Cylinder { .. } => "Cylinder",
+            Primitive::Cone { .. } => "Cone",
+            Primitive::Plane { .. } => "Plane",
+            Primitive::Capsule { .. } => "Capsule",
+        }
+    }
+
+    /// Build the geometry. Segment counts are clamped to the minimum that still makes a solid
+    pub fn generate(&self) -> Geometry {
+        let mut geometry = Geometry::default();
+        match *self {  // * copies the enum out so the fields bind by value
+            Primitive::UvSphere { radius, segments, rings } => {
+                let rings = rings.max(2);
+                let profile: Vec<ProfilePoint> = (0..=rings)
+                    .map(|i| {
+                        let theta = PI * i as f32 / rings as f32;  // 0 at the north pole, PI at the south
+                        let (sin, cos) = theta.sin_cos();
+                        ProfilePoint { radius: radius * sin, y: radius * cos, normal: [sin, cos], v: 1.0 - i as f32 / rings as f32 }
+                    })
+                    .collect();
+                lathe(&mut geometry, &profile, segments.max(3));
+            }
+            Primitive::IcoSphere { radius, subdivisions } => {
+                icosphere(&mut geometry, radius, subdivisions.min(6));  // 6 levels = 81920 triangles
+            }
+            Primitive::Torus { major_radius, minor_radius, major_segments, minor_segments } => {
+                let minor_segments = minor_segments.max(3);
+                // Walk the tube's cross-section clockwise, starting at the top
+                let profile: Vec<ProfilePoint> = (0..=minor_segments)
+                    .map(|i| {
+                        let t = i as f32 / minor_segments as f32;
+                        let (sin, cos) = (PI / 2.0 - TAU * t).sin_cos();
+                        ProfilePoint {
+                            radius: major_radius + minor_radius * cos,
+                            y: minor_radius * sin,
+                            normal: [cos, sin],
+                            v: 1.0 - t,
+                        }
+                    })
+                    .collect();
+                lathe(&mut geometry, &profile, major_segments.max(3));
+            }
+            Primitive::Cylinder { radius, height, segments } => {
+                let (top, bottom) = (height / 2.0, -height / 2.0);
+                let segments = segments.max(3);
+                // Caps and side are separate strips so the rim gets a hard edge
+                lathe(&mut geometry, &[
+                    ProfilePoint { radius: 0.0, y: top, normal: [0.0, 1.0], v: 1.0 },
+                    ProfilePoint { radius, y: top, normal: [0.0, 1.0], v: 0.0 },
+                ], segments);
+                lathe(&mut geometry, &[
+                    ProfilePoint { radius, y: top, normal: [1.0, 0.0], v: 1.0 },
+                    ProfilePoint { radius, y: bottom, normal: [1.0, 0.0], v: 0.0 },
+                ], segments);
+                lathe(&mut geometry, &[
+                    ProfilePoint { radius, y: bottom, normal: [0.0, -1.0], v: 1.0 },
+                    ProfilePoint { radius: 0.0, y: bottom, normal: [0.0, -1.0], v: 0.0 },
+                ], segments);
+            }
+            Primitive::Cone { radius, height, segments } => {
+                let (top, bottom) = (height / 2.0, -height / 2.0);
+                let segments = segments.max(3);
+                // Side normal is perpendicular to the slope from apex to rim
+                let slope = normalize2([height, radius]);
+                lathe(&mut geometry, &[
+                    ProfilePoint { radius: 0.0, y: top, normal: slope, v: 1.0 },
+                    ProfilePoint { radius, y: bottom, normal: slope, v: 0.0 },
+                ], segments);
+                lathe(&mut geometry, &[
+                    ProfilePoint { radius, y: bottom, normal: [0.0, -1.0], v: 1.0 },
+                    ProfilePoint { radius: 0.0, y: bottom, normal: [0.0, -1.0], v: 0.0 },
+                ], segments);
+            }
+            Primitive::Plane { width, depth, subdivisions } => {
+                plane(&mut geometry, width, depth, subdivisions.max(1));
+            }
+            Primitive::Capsule { radius, height, segments, rings } => {
+                let rings = rings.max(1);
+                let half = height / 2.0;
+                let total = height + 2.0 * radius;
+                // Top hemisphere, then bottom hemisphere - the gap between them is the cylinder
+                let mut profile = Vec::new();
+                for (offset, start) in [(half, 0.0), (-half, PI / 2.0)] {
+                    for i in 0..=rings {
+                        let theta = start + PI / 2.0 * i as f32 / rings as f32;
+                        let (sin, cos) = theta.sin_cos();
+                        let y = offset + radius * cos;
+                        profile.push(ProfilePoint { radius: radius * sin, y, normal: [sin, cos], v: y / total + 0.5 });
+                    }
+                }
+                lathe(&mut geometry, &profile, segments.max(3));
+            }
+        }
+        geometry
+    }
+}
+
+/// One point of a 2D outline (distance from the Y axis, height) swept around Y by `lathe`
+struct ProfilePoint {
+    radius: f32,
+    y: f32,
+    normal: [f32; 2],  // Outline normal in (radial, y) space
+    v: f32,            // Texture V coordinate for this row
+}
+
+/// Sweep a top-to-bottom outline around the Y axis (surface of revolution)
+/// The seam column is duplicated so U can run from 0 to 1 without wrapping
+fn lathe(geometry: &mut Geometry, profile: &[ProfilePoint], segments: u32) {
+    let base = geometry.positions.len() as u32;
+    let columns = segments + 1;
+
+    for point in profile {
+        for s in 0..columns {
+            let u = s as f32 / segments as f32;
+            let (sin, cos) = (TAU * u).sin_cos();
+            // -sin for Z so rows run counter-clockwise seen from +Y, giving outward-facing triangles
+            geometry.push_vertex(
+                [point.radius * cos, point.y, -point.radius * sin],
+                [point.normal[0] * cos, point.normal[1], -point.normal[0] * sin],
+                [u, point.v],
+            );
+        }
+    }
+
+    for (row, pair) in profile.windows(2).enumerate() {
+        for s in 0..segments {
+            let a = base + row as u32 * columns + s;  // Upper row
+            let b = a + columns;                        // Lower row
+            // Skip the triangle that collapses onto a pole (radius 0)
+            if pair[1].radius > 0.0 {
+                geometry.indices.extend_from_slice(&[a, b, b + 1]);
+            }
+            if pair[0].radius > 0.0 {
+                geometry.indices.extend_from_slice(&[a, b + 1, a + 1]);
+            }
+        }
+    }
+}
+
+/// Flat grid on the XZ plane facing +Y, centered on the origin
+fn plane(geometry: &mut Geometry, width: f32, depth: f32, subdivisions: u32) {
+    let columns = subdivisions + 1;
+    for row in 0..columns {
+        for col in 0..columns {
+            let (u, v) = (col as f32 / subdivisions as f32, row as f32 / subdivisions as f32);
+            geometry.push_vertex([(u - 0.5) * width, 0.0, (v - 0.5) * depth], [0.0, 1.0, 0.0], [u, 1.0 - v]);
+        }
+    }
+    for row in 0..subdivisions {
+        for col in 0..subdivisions {
+            let a = row * columns + col;
+            let b = a + columns;
+            geometry.indices.extend_from_slice(&[a, b, b + 1, a, b + 1, a + 1]);
+        }
+    }
+}
+
+/// Subdivided icosahedron - evenly spaced triangles, no poles
+fn icosphere(geometry: &mut Geometry, radius: f32, subdivisions: u32) {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;  // Golden ratio
+    let mut points: Vec<[f32; 3]> = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ].iter().map(|p| normalize3(*p)).collect();
+
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    // Each pass splits every triangle into 4, pushing new midpoints out onto the sphere
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32, points: &mut Vec<[f32; 3]>| -> u32 {
+            let key = (a.min(b), a.max(b));  // Shared edges must produce the same vertex
+            *midpoints.entry(key).or_insert_with(|| {
+                let (pa, pb) = (points[a as usize], points[b as usize]);
+                points.push(normalize3([pa[0] + pb[0], pa[1] + pb[1], pa[2] + pb[2]]));
+                (points.len() - 1) as u32
+            })
+        };
+
+        let mut next = Vec::with_capacity(faces.len() * 4);
+        for [a, b, c] in faces {
+            let ab = midpoint(a, b, &mut points);
+            let bc = midpoint(b, c, &mut points);
+            let ca = midpoint(c, a, &mut points);
+            next.extend_from_slice(&[[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        faces = next;
+    }
+
+    // Spherical UV mapping from the direction
+    let base = geometry.positions.len() as u32;
+    for &n in &points {
+        let uv = [0.5 + n[2].atan2(n[0]) / TAU, 0.5 + n[1].asin() / PI];
+        geometry.push_vertex([n[0] * radius, n[1] * radius, n[2] * radius], n, uv);
+    }
+
+    // u wraps from 1 back to 0 at the seam. A triangle spans the circle minus the widest gap between
+    // its corners' u - if that gap isn't the one across the seam, the corners below it get copies at
+    // u + 1 (shared between triangles), so no triangle interpolates across the whole texture
+    let is_pole = |n: [f32; 3]| n[1].abs() > 1.0 - 1e-6;
+    let mut wrapped: HashMap<u32, u32> = HashMap::new();
+    for face in faces {
+        let mut corners = face.map(|i| base + i);
+        let mut us: Vec<f32> = face.iter()
+            .filter(|&&i| !is_pole(points[i as usize]))
+            .map(|&i| geometry.uvs[(base + i) as usize][0])
+            .collect();
+        us.sort_by(f32::total_cmp);
+        let seam_gap = us.first().zip(us.last()).map_or(1.0, |(first, last)| first + 1.0 - last);
+        let widest = us.windows(2).map(|pair| (pair[1] - pair[0], pair[0])).max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((_, below)) = widest.filter(|&(gap, _)| gap > seam_gap) {
+            for (corner, &i) in corners.iter_mut().zip(&face) {
+                let n = points[i as usize];
+                let [u, v] = geometry.uvs[*corner as usize];
+                if !is_pole(n) && u <= below {
+                    *corner = *wrapped.entry(i).or_insert_with(|| {
+                        geometry.push_vertex([n[0] * radius, n[1] * radius, n[2] * radius], n, [u + 1.0, v])
+                    });
+                }
+            }
+        }
+
+        // A pole has no longitude - each triangle gets its own copy, centered over its other two corners
+        for k in 0..3 {
+            let n = points[face[k] as usize];
+            if is_pole(n) {
+                let others = [corners[(k + 1) % 3], corners[(k + 2) % 3]];
+                let u = (geometry.uvs[others[0] as usize][0] + geometry.uvs[others[1] as usize][0]) / 2.0;
+                let v = geometry.uvs[corners[k] as usize][1];
+                corners[k] = geometry.push_vertex([n[0] * radius, n[1] * radius, n[2] * radius], n, [u, v]);
+            }
+        }
+        geometry.indices.extend_from_slice(&corners);
+    }
+}
+
+fn normalize2(v: [f32; 2]) -> [f32; 2] {
+    let len = (v[0] * v[0] + v[1] * v[1]).sqrt();
+    [v[0] / len, v[1] / len]
+}
+
+fn normalize3(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    [v[0] / len, v[1] / len, v[2] / len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+    }
+
+    fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    #[test]
+    fn every_primitive_is_well_formed() {
+        for primitive in Primitive::defaults() {
+            let g = primitive.generate();
+            let name = primitive.name();
+            assert!(!g.indices.is_empty(), "{} has no triangles", name);
+            assert_eq!(g.indices.len() % 3, 0, "{} index count", name);
+            assert_eq!(g.normals.len(), g.positions.len());
+            assert_eq!(g.uvs.len(), g.positions.len());
+            assert!(g.indices.iter().all(|&i| (i as usize) < g.positions.len()), "{} index out of range", name);
+
+            for n in &g.normals {
+                assert!((dot(*n, *n) - 1.0).abs() < 1e-4, "{} normal not unit length", name);
+            }
+
+            // Counter-clockwise winding must agree with the vertex normals (outward facing)
+            for tri in g.indices.chunks_exact(3) {
+                let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| i as usize);
+                let face = cross(sub(g.positions[b], g.positions[a]), sub(g.positions[c], g.positions[a]));
+                if dot(face, face) < 1e-12 {
+                    continue;  // Degenerate sliver - no meaningful orientation
+                }
+                let n = [0, 1, 2].map(|k| g.normals[a][k] + g.normals[b][k] + g.normals[c][k]);
+                assert!(dot(face, n) > 0.0, "{} has an inward-facing triangle", name);
+            }
+        }
+    }
+
+    #[test]
+    fn segment_counts_control_detail() {
+        let low = Primitive::UvSphere { radius: 1.0, segments: 8, rings: 4 }.generate();
+        let high = Primitive::UvSphere { radius: 1.0, segments: 16, rings: 8 }.generate();
+        assert!(high.indices.len() > low.indices.len());
+
+        // Poles are fans: 2 * segments * (rings - 1) triangles
+        assert_eq!(low.indices.len() / 3, 2 * 8 * 3);
+
+        // Each icosphere subdivision quadruples the triangle count
+        let ico = Primitive::IcoSphere { radius: 1.0, subdivisions: 1 }.generate();
+        assert_eq!(ico.indices.len() / 3, 80);
+    }
+
+    #[test]
+    fn icosphere_uvs_do_not_wrap_inside_a_triangle() {
+        for subdivisions in 0..=3 {
+            let ico = Primitive::IcoSphere { radius: 1.0, subdivisions }.generate();
+            for triangle in ico.indices.chunks_exact(3) {
+                let us = triangle.iter().map(|&i| ico.uvs[i as usize][0]);
+                let range = us.clone().fold(f32::MIN, f32::max) - us.fold(f32::MAX, f32::min);
+                assert!(range <= 0.5 + 1e-5, "u spans {} in a triangle at {} subdivisions", range, subdivisions);
+            }
+        }
+    }
+
+    #[test]
+    fn sizes_follow_parameters() {
+        let sphere = Primitive::UvSphere { radius: 2.0, segments: 12, rings: 6 }.generate();
+        for p in &sphere.positions {
+            assert!((dot(*p, *p).sqrt() - 2.0).abs() < 1e-4);
+        }
+
+        let capsule = Primitive::Capsule { radius: 0.5, height: 1.0, segments: 12, rings: 4 }.generate();
+        let top = capsule.positions.iter().map(|p| p[1]).fold(f32::MIN, f32::max);
+        assert!((top - 1.0).abs() < 1e-5);  // height / 2 + radius
+    }
+}
@@ -0,0 +1,235 @@
+// Lit shader programs - one per shading model and feature set, built on first use and cached
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;    // Shared OpenGL context so the programs can be freed on drop
+
+use super::hot_reload::{self, ShaderError};
+use super::pbr::ShadingModel;
+use super::preprocess;
+use super::shader::{self, ShaderStage};
+use super::uniforms::{self, UniformInfo, UniformLocations};
+use super::{camera_block, environment, shadows, texture};
+
+/// Optional parts of the lit shaders, compiled in with #defines so unused ones cost nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Features {
+    pub normal_map: bool,  // HAS_NORMAL_MAP - bend the normal with the object's normal map
+    pub shadows: bool,     // SHADOWS - look up the shadow caster's depth map
+}
+
+impl Features {
+    /// Names defined for this feature set
+    pub fn defines(&self) -> Vec<&'static str> {
+        let mut defines = Vec::new();
+        if self.normal_map {
+            defines.push("HAS_NORMAL_MAP");
+        }
+        if self.shadows {
+            defines.push("SHADOWS");
+        }
+        defines
+    }
+}
+
+/// One shader variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramKey {
+    pub shading: ShadingModel,
+    pub features: Features,
+}
+
+impl ProgramKey {
+    /// Entry files for the vertex and fragment stages
+    fn files(&self) -> (&'static str, &'static str) {
+        match self.shading {
+            ShadingModel::BlinnPhong => ("mesh.vert", "blinn_phong.frag"),
+            ShadingModel::Pbr => ("mesh.vert", "pbr.frag"),
+        }
+    }
+
+    /// Display name, e.g. "PBR (SHADOWS)"
+    pub fn label(&self) -> String {
+        let defines = self.features.defines();
+        if defines.is_empty() {
+            self.shading.name().to_string()
+        } else {
+            format!("{} ({})", self.shading.name(), defines.join(", "))
+        }
+    }
+}
+
+/// Compiled variants, keyed by shading model and define set
+pub struct ProgramCache {
+    gl: Arc<glow::Context>,
+    search_path: Vec<PathBuf>,  // Folders #include and the entry files are looked up in (embedded copies after)
+    programs: HashMap<ProgramKey, glow::Program>,
+    uniforms: HashMap<ProgramKey, Vec<UniformInfo>>,  // Reflected after each successful link
+    locations: HashMap<ProgramKey, Arc<UniformLocations>>,  // Resolved with them, shared with the draw loop
+    errors: HashMap<ProgramKey, ShaderError>,  // Variants whose latest sources failed to build
+}
+
+impl ProgramCache {
+    pub fn new(gl: Arc<glow::Context>, search_path: Vec<PathBuf>) -> Self {
+        Self { gl, search_path, programs: HashMap::new(), uniforms: HashMap::new(), locations: HashMap::new(), errors: HashMap::new() }
+    }
+
+    /// The program for `key`, building it on first use
+    /// If the shader folder's sources don't build, the embedded ones are used and the error is kept
+    /// None only if the embedded sources fail too
+    pub fn get(&mut self, key: ProgramKey) -> Option<glow::Program> {
+        if let Some(&program) = self.programs.get(&key) {
+            return Some(program);
+        }
+        let built = match build(&self.gl, key, &self.search_path) {
+            Ok(built) => built,
+            Err(error) => {
+                eprintln!("{} shader failed to build: {}", error.program, error.summary());
+                self.errors.insert(key, error);
+                build(&self.gl, key, &[]).map_err(|e| eprintln!("Built-in {} shader failed: {}", e.program, e.summary())).ok()?
+            }
+        };
+        let program = built.0;
+        self.insert(key, built);
+        Some(program)
+    }
+
+    /// Rebuild every variant built so far from the current sources, keeping the old program wherever that fails
+    /// Returns a status line. Call outside paint callbacks - replaced programs are deleted right away
+    pub fn rebuild(&mut self) -> String {
+        let mut keys: Vec<ProgramKey> = self.programs.keys().copied().collect();
+        keys.sort_by_key(|key| key.label());
+        let mut failed = Vec::new();
+        for key in &keys {
+            self.errors.remove(key);
+            match build(&self.gl, *key, &self.search_path) {
+                Ok(built) => {
+                    if let Some(old) = self.insert(*key, built) {
+                        unsafe { self.gl.delete_program(old) };
+                    }
+                }
+                Err(error) => {
+                    eprintln!("{} shader failed to build: {}", error.program, error.summary());
+                    failed.push(error.summary());
+                    self.errors.insert(*key, error);
+                }
+            }
+        }
+        match failed.first() {
+            None => format!("Reloaded {} shader variants", keys.len()),
+            Some(summary) => format!("{} of {} shader variants failed, keeping their last good build: {}", failed.len(), keys.len(), summary),
+        }
+    }
+
+    /// Store a new build of `key` with its reflected uniforms and their locations; returns the program it replaces
+    fn insert(&mut self, key: ProgramKey, (program, reflected, locations): Built) -> Option<glow::Program> {
+        self.locations.insert(key, Arc::new(locations));
+        self.uniforms.insert(key, reflected);
+        self.programs.insert(key, program)
+    }
+
+    /// Uniform locations of a built variant (empty if it was never built)
+    pub fn locations(&self, key: ProgramKey) -> Arc<UniformLocations> {
+        self.locations.get(&key).cloned().unwrap_or_default()
+    }
+
+    /// Active uniforms of a built variant
+    pub fn uniforms(&self, key: ProgramKey) -> &[UniformInfo] {
+        self.uniforms.get(&key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Every uniform any built variant of `shading` declares, sorted by name
+    pub fn shading_uniforms(&self, shading: ShadingModel) -> Vec<UniformInfo> {
+        let mut table: Vec<UniformInfo> = Vec::new();
+        for (key, uniforms) in &self.uniforms {
+            if key.shading != shading {
+                continue;
+            }
+            for info in uniforms {
+                if !table.iter().any(|seen| seen.name == info.name) {
+                    table.push(info.clone());
+                }
+            }
+        }
+        table.sort_by(|a, b| a.name.cmp(&b.name));
+        table
+    }
+
+    /// Value a variant of `shading` currently holds for a uniform (its initializer if the app never set it)
+    pub fn read_uniform(&self, shading: ShadingModel, info: &UniformInfo) -> Option<Vec<f32>> {
+        let (key, _) = self.uniforms.iter()
+            .find(|(key, uniforms)| key.shading == shading && uniforms.iter().any(|uniform| uniform.name == info.name))?;
+        Some(uniforms::read(&self.gl, *self.programs.get(key)?, info))
+    }
+
+    /// Variants whose latest sources failed - one per distinct message, since every variant
+    /// of a broken file usually fails the same way
+    pub fn errors(&self) -> Vec<ShaderError> {
+        let mut errors: Vec<&ShaderError> = self.errors.values().collect();
+        errors.sort_by(|a, b| a.program.cmp(&b.program));
+        let mut unique: Vec<ShaderError> = Vec::new();
+        for error in errors {
+            if !unique.iter().any(|seen| seen.summary() == error.summary()) {
+                unique.push(error.clone());
+            }
+        }
+        unique
+    }
+}
+
+impl Drop for ProgramCache {
+    fn drop(&mut self) {
+        for program in self.programs.values() {
+            unsafe { self.gl.delete_program(*program) };
+        }
+    }
+}
+
+/// A linked variant with its reflected uniforms and their locations
+type Built = (glow::Program, Vec<UniformInfo>, UniformLocations);
+
+/// Preprocess, compile and link one variant from `search_path` (empty = the embedded sources)
+fn build(gl: &glow::Context, key: ProgramKey, search_path: &[PathBuf]) -> Result<Built, ShaderError> {
+    let (vertex_file, fragment_file) = key.files();
+    let defines = key.features.defines();
+    let read = |name: &str| hot_reload::read_shader(search_path, name);
+    let vertex = preprocess::preprocess(vertex_file, &defines, &read)
+        .map_err(|e| ShaderError::from_preprocess(key.label(), ShaderStage::Vertex, &e))?;
+    let fragment = preprocess::preprocess(fragment_file, &defines, &read)
+        .map_err(|e| ShaderError::from_preprocess(key.label(), ShaderStage::Fragment, &e))?;
+    let program = shader::compile_program(gl, &vertex.text, &fragment.text)
+        .map_err(|e| ShaderError::new(key.label(), &e, &vertex, &fragment))?;
+
+    // Texture units and block bindings are program state, so every new build needs them
+    camera_block::init_program(gl, program);
+    if key.shading == ShadingModel::Pbr {
+        environment::init_pbr_program(gl, program);
+    }
+    let reflected = uniforms::reflect(gl, program);
+    let locations = UniformLocations::resolve(gl, program, &reflected);
+    shadows::init_program(gl, program, &locations);
+    texture::init_program(gl, program);
+    Ok((program, reflected, locations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_assembles_from_the_embedded_sources() {
+        let read = |name: &str| hot_reload::read_shader(&[], name);
+        for shading in ShadingModel::ALL {
+            for (normal_map, shadows) in [(false, false), (true, false), (false, true), (true, true)] {
+                let key = ProgramKey { shading, features: Features { normal_map, shadows } };
+                let (vertex, fragment) = key.files();
+                assert!(preprocess::preprocess(vertex, &key.features.defines(), &read).is_ok());
+                let fragment = preprocess::preprocess(fragment, &key.features.defines(), &read).unwrap();
+                assert!(fragment.text.starts_with("#version"), "{}", key.label());
+                assert_eq!(fragment.text.contains("#define SHADOWS"), shadows);
+                assert_eq!(fragment.text.contains("#define HAS_NORMAL_MAP"), normal_map);
+                assert!(!fragment.text.lines().any(|line| line.trim_start().starts_with("#include")));
+            }
+        }
+    }
+}
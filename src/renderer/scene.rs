@@ -0,0 +1,60 @@
+// Scene graph (flattened) - the list of meshes the viewport draws
+use nalgebra_glm as glm;  // Matrices for object placement
+use std::sync::Arc;       // Meshes are shared with in-flight paint callbacks
+
+use super::bounds::{Aabb, BoundingSphere};
+use super::lighting::Material;
+use super::mesh::Mesh;
+use super::pbr::{PbrMaterial, ShadingModel};
+use super::primitives::Primitive;
+use super::texture::MaterialTextures;
+use super::transform::Transform;
+
+/// A mesh placed in the world
+#[derive(Clone)]
+pub struct SceneObject {
+    pub name: String,          // Shown in logs and the UI
+    pub mesh: Arc<Mesh>,       // GPU buffers (shared, freed when the last user drops it)
+    pub parent: glm::Mat4,     // Fixed placement above it - a glTF node's parents, identity otherwise
+    pub transform: Transform,  // Object-to-parent placement (edited by the gizmo)
+    pub shading: ShadingModel, // Which of the two materials below is used
+    pub material: Material,    // Blinn-Phong response (edited in the Lights panel)
+    pub pbr: PbrMaterial,      // Metallic-roughness response
+    pub textures: MaterialTextures,  // Image per material slot (shared by both shading models)
+    pub primitive: Option<Primitive>,  // Generator parameters for procedural objects
+}
+
+impl SceneObject {
+    /// Object-to-world matrix: the editable transform inside its parent
+    pub fn world_matrix(&self) -> glm::Mat4 {
+        self.parent * self.transform.matrix()
+    }
+}
+
+/// Everything the viewport draws - replaced wholesale when a file is opened
+#[derive(Clone, Default)]
+pub struct Scene {
+    pub objects: Vec<SceneObject>,
+}
+
+impl Scene {
+    /// World-space box around every object, with `model` applied on top of each object's transform
+    pub fn bounds(&self, model: &glm::Mat4) -> Aabb {
+        let mut bounds = Aabb::empty();
+        for object in &self.objects {
+            let world = object.mesh.bounds.transformed(&(model * object.world_matrix()));
+            if !world.is_empty() {
+                bounds.extend(&world.min);
+                bounds.extend(&world.max);
+            }
+        }
+        bounds
+    }
+
+    /// World-space sphere around every object (None if the scene has no vertices)
+    pub fn bounding_sphere(&self, model: &glm::Mat4) -> Option<BoundingSphere> {
+        self.objects.iter()
+            .filter_map(|object| object.mesh.sphere.map(|sphere| sphere.transformed(&(model * object.world_matrix()))))
+            .reduce(|a, b| a.merge(&b))
+    }
+}
@@ -1,6 +1,56 @@
 // Import OpenGL context trait
 use glow::HasContext;
 
+// Default shader used for every mesh - Blinn-Phong with a material and a list of lights, on top of vertex colors
+// The sources live in shaders/ so they can be edited while the app runs (hot_reload.rs)
+// These copies are compiled in, for when that folder isn't around and as the fallback if it doesn't compile
+
+// Vertex shader source code in GLSL (OpenGL Shading Language)
+pub const VERTEX_SHADER_SOURCE: &str = include_str!("../../shaders/mesh.vert");
+
+// Fragment shader for Blinn-Phong - runs once per pixel
+// Lights, shadows and normal mapping come in through #include (preprocess.rs)
+pub const FRAGMENT_SHADER_SOURCE: &str = include_str!("../../shaders/blinn_phong.frag");
+
+// Tangent-space normal mapping shared by the lit fragment shaders (#include "normal_mapping.glsl")
+pub const NORMAL_MAPPING_GLSL: &str = include_str!("../../shaders/normal_mapping.glsl");
+
+/// Which step of building a program failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Geometry,
+    Fragment,
+    Link,
+}
+
+impl ShaderStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShaderStage::Vertex => "vertex shader",
+            ShaderStage::Geometry => "geometry shader",
+            ShaderStage::Fragment => "fragment shader",
+            ShaderStage::Link => "program link",
+        }
+    }
+}
+
+/// A failed compile or link, with the driver's info log
+#[derive(Debug, Clone)]
+pub struct ProgramError {
+    pub stage: ShaderStage,
+    pub log: String,
+}
+
+impl std::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.stage {
+            ShaderStage::Link => write!(f, "Program linking failed: {}", self.log),
+            stage => write!(f, "Shader compilation failed ({}): {}", stage.name(), self.log),
+        }
+    }
+}
+
 /// Compile and link shader program from vertex and fragment shader source code
 /// Returns Result<Program, String> - either success with program or error message
 pub fn create_program(
@@ -8,41 +58,77 @@ pub fn create_program(
     vertex_source: &str,   // Vertex shader GLSL source code (&str = string slice)
     fragment_source: &str, // Fragment shader GLSL source code
 ) -> Result<glow::Program, String> {  // Result = either Ok(Program) or Err(String)
+    compile_program(gl, vertex_source, fragment_source).map_err(|e| e.to_string())
+}
+
+/// Like create_program, with a geometry shader between the two stages
+pub fn create_program_with_geometry(gl: &glow::Context, vertex_source: &str, geometry_source: &str, fragment_source: &str) -> Result<glow::Program, String> {
+    link_program(gl, &[
+        (ShaderStage::Vertex, vertex_source),
+        (ShaderStage::Geometry, geometry_source),
+        (ShaderStage::Fragment, fragment_source),
+    ]).map_err(|e| e.to_string())
+}
+
+/// Like create_program, but keeps which stage failed and the raw log (for the error overlay)
+pub fn compile_program(gl: &glow::Context, vertex_source: &str, fragment_source: &str) -> Result<glow::Program, ProgramError> {
+    link_program(gl, &[(ShaderStage::Vertex, vertex_source), (ShaderStage::Fragment, fragment_source)])
+}
+
+/// Compile each stage and link them into one program
+fn link_program(gl: &glow::Context, stages: &[(ShaderStage, &str)]) -> Result<glow::Program, ProgramError> {
     unsafe {  // OpenGL calls are unsafe
+        // Compile every stage (? operator propagates errors up)
+        let mut shaders = Vec::new();
+        for (stage, source) in stages {
+            match compile_shader(gl, *stage, source) {
+                Ok(shader) => shaders.push(shader),
+                Err(e) => {
+                    for shader in shaders {
+                        gl.delete_shader(shader);  // Don't leak the stages that did compile
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
         // Create a new shader program object
         let program = gl.create_program().expect("Cannot create program");
 
-        // Compile both shaders (? operator propagates errors up)
-        let vertex_shader = compile_shader(gl, glow::VERTEX_SHADER, vertex_source)?;
-        let fragment_shader = compile_shader(gl, glow::FRAGMENT_SHADER, fragment_source)?;
+        // Attach shaders to the program and link them together into executable
+        for &shader in &shaders {
+            gl.attach_shader(program, shader);
+        }
+        gl.link_program(program);
 
-        // Attach shaders to the program
-        gl.attach_shader(program, vertex_shader);    // Add vertex shader
-        gl.attach_shader(program, fragment_shader);  // Add fragment shader
-        gl.link_program(program);                    // Link them together into executable
+        // Clean up individual shaders (program keeps the compiled code)
+        for shader in shaders {
+            gl.delete_shader(shader);  // Flagged for deletion, freed with the program
+        }
 
         // Check if linking succeeded
         if !gl.get_program_link_status(program) {  // Returns false if linking failed
             let log = gl.get_program_info_log(program);  // Get error details
             gl.delete_program(program);                  // Clean up failed program
-            return Err(format!("Program linking failed: {}", log));  // Return error
+            return Err(ProgramError { stage: ShaderStage::Link, log });
         }
 
-        // Clean up individual shaders (program keeps the compiled code)
-        gl.delete_shader(vertex_shader);    // Delete vertex shader object
-        gl.delete_shader(fragment_shader);  // Delete fragment shader object
-
         Ok(program)  // Return successfully linked program
     }  // End of unsafe block
-}  // End of create_program function
+}  // End of link_program function
 
 /// Compile individual shader from GLSL source code
-/// Private function (no pub) - only used internally by create_program
+/// Private function (no pub) - only used internally by link_program
 fn compile_shader(
     gl: &glow::Context,  // OpenGL context
-    shader_type: u32,    // Either VERTEX_SHADER or FRAGMENT_SHADER
+    stage: ShaderStage,  // Vertex, Geometry or Fragment
     source: &str,        // GLSL source code as string
-) -> Result<glow::Shader, String> {  // Returns compiled shader or error message
+) -> Result<glow::Shader, ProgramError> {  // Returns compiled shader or the compiler's log
+    let shader_type = match stage {
+        ShaderStage::Vertex => glow::VERTEX_SHADER,
+        ShaderStage::Geometry => glow::GEOMETRY_SHADER,
+        _ => glow::FRAGMENT_SHADER,
+    };
     unsafe {
         // Create a new shader object of the specified type
         let shader = gl.create_shader(shader_type).expect("Cannot create shader");
@@ -57,9 +143,9 @@ fn compile_shader(
         if !gl.get_shader_compile_status(shader) {  // Returns false if compilation failed
             let log = gl.get_shader_info_log(shader);  // Get compiler error details
             gl.delete_shader(shader);                  // Clean up failed shader
-            return Err(format!("Shader compilation failed: {}", log));  // Return error
+            return Err(ProgramError { stage, log });
         }
 
         Ok(shader)  // Return successfully compiled shader
     }  // End of unsafe block
-}  // End of compile_shader function
\ No newline at end of file
+}  // End of compile_shader function
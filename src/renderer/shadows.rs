@@ -0,0 +1,316 @@
+// Shadow mapping for the main directional light - depth rendered from the light, optionally in cascades
+use glow::HasContext;     // Trait providing OpenGL function methods
+use nalgebra_glm as glm;  // Light-space matrices
+use std::sync::Arc;       // Shared OpenGL context so the map can be freed on drop
+
+use super::bounds::BoundingSphere;
+use super::camera::Camera;
+use super::uniforms::UniformLocations;
+
+/// Most cascades (must match MAX_CASCADES in SHADOWS_GLSL)
+pub const MAX_CASCADES: usize = 4;
+
+// Element names of the per-cascade uniform arrays, spelled out so binding doesn't format them every frame
+const SHADOW_MATRIX_NAMES: [&str; MAX_CASCADES] = ["u_shadow_matrices[0]", "u_shadow_matrices[1]", "u_shadow_matrices[2]", "u_shadow_matrices[3]"];
+const CASCADE_END_NAMES: [&str; MAX_CASCADES] = ["u_cascade_ends[0]", "u_cascade_ends[1]", "u_cascade_ends[2]", "u_cascade_ends[3]"];
+const TEXEL_SIZE_NAMES: [&str; MAX_CASCADES] = ["u_shadow_texel_sizes[0]", "u_shadow_texel_sizes[1]", "u_shadow_texel_sizes[2]", "u_shadow_texel_sizes[3]"];
+
+/// Texture unit the shadow map is bound to while drawing (after the environment's units)
+pub const SHADOW_UNIT: u32 = 5;
+
+/// Shadow lookup with PCF, shared by the lit fragment shaders (#include "shadows.glsl")
+pub const SHADOWS_GLSL: &str = include_str!("../../shaders/shadows.glsl");
+
+// Depth pass - only the vertex transform matters
+pub const DEPTH_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+void main() {
+}
+"#;
+
+// Debug view - one shadow map layer as grayscale (used with environment::FULLSCREEN_VERTEX_SHADER_SOURCE)
+pub const DEBUG_FRAGMENT_SHADER_SOURCE: &str = r#"#version 330 core
+in vec2 v_ndc;
+out vec4 frag_color;
+
+uniform sampler2DArray u_shadow_map;
+uniform int u_layer;
+
+void main() {
+    float depth = texture(u_shadow_map, vec3(v_ndc * 0.5 + 0.5, float(u_layer))).r;
+    frag_color = vec4(vec3(depth), 1.0);  // Near = black, far/empty = white
+}
+"#;
+
+/// Shadow quality and cascade controls
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+    pub resolution: i32,    // Width and height of each cascade's depth map
+    pub bias: f32,          // Depth offset (in 0..1 shadow depth)
+    pub normal_bias: f32,   // Lookup offset along the normal, in texels
+    pub pcf_radius: i32,    // 0 = hard edges, 1 = 3x3 taps, 2 = 5x5...
+    pub cascaded: bool,     // Split the view into depth ranges, each with its own map
+    pub cascades: usize,    // 2..=MAX_CASCADES
+    pub split_lambda: f32,  // 0 = even splits, 1 = logarithmic (more detail near the camera)
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolution: 2048,
+            bias: 0.002,
+            normal_bias: 1.5,
+            pcf_radius: 1,
+            cascaded: false,
+            cascades: 3,
+            split_lambda: 0.75,
+        }
+    }
+}
+
+impl ShadowSettings {
+    /// Number of depth maps in use
+    pub fn cascade_count(&self) -> usize {
+        if self.cascaded { self.cascades.clamp(2, MAX_CASCADES) } else { 1 }
+    }
+}
+
+/// One shadow map layer: what it covers and where its range of the view ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cascade {
+    pub matrix: glm::Mat4,  // World -> light clip space
+    pub end: f32,           // View depth covered up to
+    pub texel_size: f32,    // World size of one texel
+}
+
+/// Far ends of `count` view depth ranges between near and far, blending log and even spacing
+pub fn split_depths(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    (1..=count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            let logarithmic = near * (far / near).powf(t);
+            let uniform = near + (far - near) * t;
+            lambda * logarithmic + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+/// Light view-projection covering `focus` across the map and every caster in `scene` in depth
+/// Returns the matrix and the world size of one texel
+pub fn fit_light(direction: &glm::Vec3, focus: &BoundingSphere, scene: &BoundingSphere, resolution: i32) -> (glm::Mat4, f32) {
+    let direction = direction.try_normalize(1e-6).unwrap_or(-glm::Vec3::y());
+    let up = if direction.y.abs() > 0.99 { glm::Vec3::z() } else { glm::Vec3::y() };
+    let view = glm::look_at(&glm::Vec3::zeros(), &direction, &up);  // Orientation only
+
+    // Snap the center to whole texels so edges don't shimmer as the camera moves
+    let radius = focus.radius.max(1e-3);
+    let texel = 2.0 * radius / resolution as f32;
+    let center = (view * focus.center.push(1.0)).xyz();
+    let center = glm::vec3((center.x / texel).floor() * texel, (center.y / texel).floor() * texel, center.z);
+
+    // The view looks down -Z, so distance from the light is -z; casters outside the focus still count
+    let scene_center = (view * scene.center.push(1.0)).xyz();
+    let near = (-scene_center.z - scene.radius).min(-center.z - radius);
+    let far = (-scene_center.z + scene.radius).max(-center.z + radius);
+
+    let projection = glm::ortho(center.x - radius, center.x + radius, center.y - radius, center.y + radius, near, far);
+    (projection * view, texel)
+}
+
+/// Cascades for the camera's current view (a single map over the whole scene when not cascaded)
+pub fn cascades(camera: &Camera, aspect: f32, direction: &glm::Vec3, scene: &BoundingSphere, settings: &ShadowSettings) -> Vec<Cascade> {
+    if !settings.cascaded {
+        let (matrix, texel_size) = fit_light(direction, scene, scene, settings.resolution);
+        return vec![Cascade { matrix, end: f32::MAX, texel_size }];
+    }
+
+    // Frustum corners on the near and far planes; points in between lie on the lines joining them
+    let corners = |z: f32| [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+        .map(|(x, y)| camera.unproject(glm::vec3(x, y, z), aspect));
+    let (near_corners, far_corners) = (corners(-1.0), corners(1.0));
+    let depth_range = camera.far - camera.near;
+
+    let mut start = camera.near;
+    split_depths(camera.near, camera.far, settings.cascade_count(), settings.split_lambda)
+        .into_iter()
+        .map(|end| {
+            let mut points = Vec::with_capacity(8);
+            for t in [(start - camera.near) / depth_range, (end - camera.near) / depth_range] {
+                for (near, far) in near_corners.iter().zip(&far_corners) {
+                    points.push(glm::lerp(near, far, t).into());
+                }
+            }
+            start = end;
+            let focus = BoundingSphere::from_points(&points).unwrap_or(*scene);
+            let (matrix, texel_size) = fit_light(direction, &focus, scene, settings.resolution);
+            Cascade { matrix, end, texel_size }
+        })
+        .collect()
+}
+
+/// Depth texture array (one layer per cascade) and the framebuffer that renders into it
+pub struct ShadowMap {
+    gl: Arc<glow::Context>,
+    texture: glow::Texture,
+    framebuffer: glow::Framebuffer,
+    resolution: i32,
+}
+
+impl ShadowMap {
+    /// Allocate MAX_CASCADES layers of `resolution` squared
+    pub fn new(gl: Arc<glow::Context>, resolution: i32) -> Result<Self, String> {
+        unsafe {
+            let texture = gl.create_texture()?;
+            let framebuffer = gl.create_framebuffer()?;
+            let map = Self { gl: gl.clone(), texture, framebuffer, resolution };  // Drop frees both from here on
+
+            gl.bind_texture(glow::TEXTURE_2D_ARRAY, Some(texture));
+            gl.tex_image_3d(glow::TEXTURE_2D_ARRAY, 0, glow::DEPTH_COMPONENT24 as i32, resolution, resolution, MAX_CASCADES as i32, 0, glow::DEPTH_COMPONENT, glow::UNSIGNED_INT, glow::PixelUnpackData::Slice(None));
+            gl.tex_parameter_i32(glow::TEXTURE_2D_ARRAY, glow::TEXTURE_MIN_FILTER, glow::NEAREST as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D_ARRAY, glow::TEXTURE_MAG_FILTER, glow::NEAREST as i32);
+            // Outside the map counts as lit
+            gl.tex_parameter_i32(glow::TEXTURE_2D_ARRAY, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_BORDER as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D_ARRAY, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_BORDER as i32);
+            gl.tex_parameter_f32_slice(glow::TEXTURE_2D_ARRAY, glow::TEXTURE_BORDER_COLOR, &[1.0, 1.0, 1.0, 1.0]);
+            gl.bind_texture(glow::TEXTURE_2D_ARRAY, None);
+
+            // Depth only - no color buffer to draw to or read from
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.framebuffer_texture_layer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, Some(texture), 0, 0);
+            gl.draw_buffer(glow::NONE);
+            gl.read_buffer(glow::NONE);
+            let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                return Err(format!("Shadow framebuffer incomplete (status 0x{:X})", status));
+            }
+            Ok(map)
+        }
+    }
+
+    pub fn resolution(&self) -> i32 {
+        self.resolution
+    }
+
+    /// Bind one layer for drawing and clear it (the caller restores the previous framebuffer)
+    pub fn begin_layer(&self, layer: usize) {
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+            self.gl.framebuffer_texture_layer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, Some(self.texture), 0, layer as i32);
+            self.gl.viewport(0, 0, self.resolution, self.resolution);
+            self.gl.depth_mask(true);
+            self.gl.clear(glow::DEPTH_BUFFER_BIT);
+        }
+    }
+
+    /// Bind the map and set the shadow uniforms of the lit program in use
+    pub fn bind(&self, gl: &glow::Context, locations: &UniformLocations, light: usize, cascades: &[Cascade], settings: &ShadowSettings) {
+        unsafe {
+            self.bind_texture(gl);
+            let location = |name: &str| locations.get(name);
+            gl.uniform_1_i32(location("u_shadow_light"), light as i32);
+            gl.uniform_1_i32(location("u_cascade_count"), cascades.len() as i32);
+            for (i, cascade) in cascades.iter().enumerate().take(MAX_CASCADES) {
+                gl.uniform_matrix_4_f32_slice(location(SHADOW_MATRIX_NAMES[i]), false, cascade.matrix.as_slice());
+                gl.uniform_1_f32(location(CASCADE_END_NAMES[i]), cascade.end);
+                gl.uniform_1_f32(location(TEXEL_SIZE_NAMES[i]), cascade.texel_size);
+            }
+            gl.uniform_1_f32(location("u_shadow_bias"), settings.bias);
+            gl.uniform_1_f32(location("u_shadow_normal_bias"), settings.normal_bias);
+            gl.uniform_1_i32(location("u_pcf_radius"), settings.pcf_radius.max(0));
+        }
+    }
+
+    /// Bind the depth array to SHADOW_UNIT
+    pub fn bind_texture(&self, gl: &glow::Context) {
+        unsafe {
+            gl.active_texture(glow::TEXTURE0 + SHADOW_UNIT);
+            gl.bind_texture(glow::TEXTURE_2D_ARRAY, Some(self.texture));
+            gl.active_texture(glow::TEXTURE0);
+        }
+    }
+}
+
+/// Point a program's shadow sampler at its unit and start with shadows off
+pub fn init_program(gl: &glow::Context, program: glow::Program, locations: &UniformLocations) {
+    unsafe {
+        gl.use_program(Some(program));
+        gl.uniform_1_i32(locations.get("u_shadow_map"), SHADOW_UNIT as i32);
+        gl.uniform_1_i32(locations.get("u_shadow_light"), -1);
+    }
+}
+
+// Free GPU memory when the resolution changes or the renderer goes away
+impl Drop for ShadowMap {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_framebuffer(self.framebuffer);
+            self.gl.delete_texture(self.texture);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cascade_uniform_names_match_their_index() {
+        for i in 0..MAX_CASCADES {
+            assert_eq!(SHADOW_MATRIX_NAMES[i], format!("u_shadow_matrices[{}]", i));
+            assert_eq!(CASCADE_END_NAMES[i], format!("u_cascade_ends[{}]", i));
+            assert_eq!(TEXEL_SIZE_NAMES[i], format!("u_shadow_texel_sizes[{}]", i));
+        }
+    }
+
+    #[test]
+    fn splits_end_at_far_and_increase() {
+        let splits = split_depths(0.1, 100.0, 4, 0.75);
+        assert_eq!(splits.len(), 4);
+        assert!((splits[3] - 100.0).abs() < 1e-3);
+        assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // lambda 0 is plain even spacing
+        let even = split_depths(10.0, 100.0, 3, 0.0);
+        assert!((even[0] - 40.0).abs() < 1e-4 && (even[1] - 70.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn light_frustum_covers_focus_and_casters() {
+        let focus = BoundingSphere { center: glm::vec3(1.0, 0.0, 2.0), radius: 1.5 };
+        let scene = BoundingSphere { center: glm::vec3(0.0, 3.0, 0.0), radius: 6.0 };
+        let direction = glm::vec3(-1.0, -2.0, -0.5);
+        let (matrix, texel) = fit_light(&direction, &focus, &scene, 1024);
+        assert!((texel - 3.0 / 1024.0).abs() < 1e-6);
+
+        let to_clip = |p: glm::Vec3| (matrix * p.push(1.0)).xyz();
+        // Focus extremes fit the map (a texel of slack for the snapping)
+        for axis in [glm::Vec3::x(), glm::Vec3::y(), glm::Vec3::z()] {
+            for sign in [-1.0, 1.0] {
+                let clip = to_clip(focus.center + axis * sign * focus.radius * 0.99);
+                assert!(clip.x.abs() <= 1.0 + 2.0 / 1024.0 && clip.y.abs() <= 1.0 + 2.0 / 1024.0, "{:?}", clip);
+            }
+        }
+        // Every caster in the scene is between the light's near and far planes
+        let toward_light = -direction.normalize();
+        for sign in [-1.0, 1.0] {
+            let clip = to_clip(scene.center + toward_light * sign * scene.radius);
+            assert!(clip.z.abs() <= 1.0 + 1e-4, "{:?}", clip);
+        }
+    }
+
+    #[test]
+    fn single_map_unless_cascaded() {
+        let camera = Camera::default();
+        let scene = BoundingSphere { center: glm::Vec3::zeros(), radius: 1.0 };
+        let mut settings = ShadowSettings::default();
+        let direction = glm::vec3(-1.0, -1.0, -1.0);
+        assert_eq!(cascades(&camera, 1.5, &direction, &scene, &settings).len(), 1);
+
+        settings.cascaded = true;
+        let split = cascades(&camera, 1.5, &direction, &scene, &settings);
+        assert_eq!(split.len(), settings.cascades);
+        assert!((split.last().unwrap().end - camera.far).abs() < 1e-3);
+    }
+}
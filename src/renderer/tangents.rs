@@ -0,0 +1,92 @@
+// Tangent generation - MikkTSpace, the convention bakers use, so baked normal maps line up exactly
+use bevy_mikktspace::Geometry;  // Callback interface of the MikkTSpace port
+
+/// Indexed triangle list viewed through the MikkTSpace callbacks
+struct Triangles<'a> {
+    positions: &'a [[f32; 3]],
+    normals: &'a [[f32; 3]],
+    uvs: &'a [[f32; 2]],
+    indices: &'a [u32],
+    tangents: Vec<[f32; 4]>,
+}
+
+impl Triangles<'_> {
+    fn vertex(&self, face: usize, corner: usize) -> usize {
+        self.indices[face * 3 + corner] as usize
+    }
+}
+
+impl Geometry for Triangles<'_> {
+    fn num_faces(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    fn num_vertices_of_face(&self, _face: usize) -> usize {
+        3
+    }
+
+    fn position(&self, face: usize, corner: usize) -> [f32; 3] {
+        self.positions[self.vertex(face, corner)]
+    }
+
+    fn normal(&self, face: usize, corner: usize) -> [f32; 3] {
+        self.normals[self.vertex(face, corner)]
+    }
+
+    fn tex_coord(&self, face: usize, corner: usize) -> [f32; 2] {
+        self.uvs[self.vertex(face, corner)]
+    }
+
+    // Corners sharing a vertex share position, normal and UV, so MikkTSpace gives them one tangent
+    fn set_tangent_encoded(&mut self, tangent: [f32; 4], face: usize, corner: usize) {
+        let vertex = self.vertex(face, corner);
+        self.tangents[vertex] = tangent;
+    }
+}
+
+/// Per-vertex tangents for a triangle list: xyz along +U, w = handedness (bitangent = w * cross(normal, tangent))
+/// None if MikkTSpace gives up (e.g. every triangle is degenerate)
+pub fn generate(positions: &[[f32; 3]], normals: &[[f32; 3]], uvs: &[[f32; 2]], indices: &[u32]) -> Option<Vec<[f32; 4]>> {
+    let mut triangles = Triangles {
+        positions,
+        normals,
+        uvs,
+        indices,
+        tangents: vec![[1.0, 0.0, 0.0, 1.0]; positions.len()],  // Unreferenced vertices keep a harmless default
+    };
+    bevy_mikktspace::generate_tangents(&mut triangles).then_some(triangles.tangents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit quad in the XY plane facing +Z, with UVs from `uv`
+    fn quad(uv: impl Fn([f32; 3]) -> [f32; 2]) -> Vec<[f32; 4]> {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+        let uvs: Vec<[f32; 2]> = positions.iter().map(|&p| uv(p)).collect();
+        generate(&positions, &[[0.0, 0.0, 1.0]; 4], &uvs, &[0, 1, 2, 2, 3, 0]).unwrap()
+    }
+
+    fn assert_close(a: [f32; 4], b: [f32; 4]) {
+        assert!(a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-5), "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn tangent_follows_u_and_bitangent_follows_v() {
+        for tangent in quad(|p| [p[0], p[1]]) {
+            assert_close(tangent, [1.0, 0.0, 0.0, 1.0]);
+        }
+        // U along +Y: tangent turns with it, V along +X is then -cross(n, t)
+        for tangent in quad(|p| [p[1], p[0]]) {
+            assert_close(tangent, [0.0, 1.0, 0.0, -1.0]);
+        }
+    }
+
+    #[test]
+    fn mirrored_uvs_flip_handedness() {
+        for tangent in quad(|p| [1.0 - p[0], p[1]]) {
+            assert_close(tangent, [-1.0, 0.0, 0.0, -1.0]);
+        }
+    }
+}
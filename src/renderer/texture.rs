@@ -0,0 +1,465 @@
+// Textures - image decoding, GPU upload with mipmaps, sampler state and per-object material slots
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::fmt;          // Display trait for readable errors
+use std::path::Path;   // Borrowed file path
+use std::sync::Arc;    // Textures and samplers are shared between objects and paint callbacks
+
+use super::shadows::SHADOW_UNIT;
+
+/// First texture unit used by material slots (after the environment and shadow units)
+const FIRST_SLOT_UNIT: u32 = SHADOW_UNIT + 1;
+
+/// Highest anisotropy offered - hardware commonly tops out here
+pub const MAX_ANISOTROPY: u32 = 16;
+
+/// Everything that can go wrong while reading an image file
+#[derive(Debug)]
+pub enum TextureError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The bytes are not an image in a supported format
+    Decode(image::ImageError),
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextureError::Io(e) => write!(f, "{}", e),
+            TextureError::Decode(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
+
+/// Whether texels are gamma-encoded colors or raw data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    Srgb,    // Colors as painted (base color, emissive) - the GPU linearizes them when sampling
+    Linear,  // Data (roughness, metallic, occlusion, normals) - used as stored
+}
+
+/// What happens to coordinates outside 0..1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+impl Wrap {
+    pub const ALL: [Wrap; 3] = [Wrap::Repeat, Wrap::MirroredRepeat, Wrap::ClampToEdge];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Wrap::Repeat => "Repeat",
+            Wrap::MirroredRepeat => "Mirrored repeat",
+            Wrap::ClampToEdge => "Clamp to edge",
+        }
+    }
+
+    fn gl_wrap(self) -> i32 {
+        (match self {
+            Wrap::Repeat => glow::REPEAT,
+            Wrap::MirroredRepeat => glow::MIRRORED_REPEAT,
+            Wrap::ClampToEdge => glow::CLAMP_TO_EDGE,
+        }) as i32
+    }
+}
+
+/// How texels are blended when magnified or minified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Filter {
+    Nearest,    // Blocky, no mipmaps
+    Bilinear,   // Smooth within the full-size image only
+    Trilinear,  // Smooth, blending between mip levels
+}
+
+impl Filter {
+    pub const ALL: [Filter; 3] = [Filter::Nearest, Filter::Bilinear, Filter::Trilinear];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Filter::Nearest => "Nearest",
+            Filter::Bilinear => "Bilinear",
+            Filter::Trilinear => "Trilinear",
+        }
+    }
+
+    /// (minification, magnification) filters
+    fn gl_filters(self) -> (i32, i32) {
+        let (min, mag) = match self {
+            Filter::Nearest => (glow::NEAREST, glow::NEAREST),
+            Filter::Bilinear => (glow::LINEAR, glow::LINEAR),
+            Filter::Trilinear => (glow::LINEAR_MIPMAP_LINEAR, glow::LINEAR),
+        };
+        (min as i32, mag as i32)
+    }
+}
+
+/// Sampling state for a texture binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SamplerSettings {
+    pub wrap: Wrap,
+    pub filter: Filter,
+    pub anisotropy: u32,  // 1 = off, up to MAX_ANISOTROPY (clamped to what the GPU supports)
+}
+
+impl Default for SamplerSettings {
+    fn default() -> Self {
+        Self { wrap: Wrap::Repeat, filter: Filter::Trilinear, anisotropy: 8 }
+    }
+}
+
+impl SamplerSettings {
+    /// Closest settings for a glTF sampler (only the S wrap mode is kept)
+    pub fn from_gltf(sampler: &gltf::texture::Sampler) -> Self {
+        use gltf::texture::{MagFilter, MinFilter, WrappingMode};
+        let wrap = match sampler.wrap_s() {
+            WrappingMode::Repeat => Wrap::Repeat,
+            WrappingMode::MirroredRepeat => Wrap::MirroredRepeat,
+            WrappingMode::ClampToEdge => Wrap::ClampToEdge,
+        };
+        let filter = match (sampler.mag_filter(), sampler.min_filter()) {
+            (Some(MagFilter::Nearest), _) => Filter::Nearest,
+            (_, Some(MinFilter::Nearest | MinFilter::Linear)) => Filter::Bilinear,
+            _ => Filter::Trilinear,  // Unspecified or any mipmapped filter
+        };
+        Self { wrap, filter, ..Self::default() }
+    }
+}
+
+/// Decoded image: RGBA, 8 bits per channel, top row first
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,  // width * height * 4 bytes
+}
+
+impl TextureImage {
+    /// Decode a PNG, JPEG, BMP or GIF (first frame) from memory
+    pub fn decode(bytes: &[u8]) -> Result<Self, TextureError> {
+        let image = image::load_from_memory(bytes).map_err(TextureError::Decode)?.into_rgba8();
+        Ok(Self { width: image.width(), height: image.height(), pixels: image.into_raw() })
+    }
+
+    /// Read and decode an image file
+    pub fn open(path: &Path) -> Result<Self, TextureError> {
+        let bytes = std::fs::read(path).map_err(TextureError::Io)?;
+        Self::decode(&bytes)
+    }
+
+    /// Read an image file, or stand in a checkerboard and report why
+    pub fn open_or_checkerboard(path: &Path) -> (Self, Option<TextureError>) {
+        match Self::open(path) {
+            Ok(image) => (image, None),
+            Err(e) => (Self::checkerboard(), Some(e)),
+        }
+    }
+
+    /// Magenta and dark gray squares - obviously "texture missing" on any model
+    pub fn checkerboard() -> Self {
+        const SIZE: u32 = 64;
+        const CELL: u32 = 8;
+        let mut pixels = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let odd = (x / CELL + y / CELL) % 2 == 1;
+                pixels.extend_from_slice(if odd { &[255, 0, 255, 255] } else { &[40, 40, 40, 255] });
+            }
+        }
+        Self { width: SIZE, height: SIZE, pixels }
+    }
+
+    /// One texel of a color (stands in for unbound slots)
+    pub fn solid(rgba: [u8; 4]) -> Self {
+        Self { width: 1, height: 1, pixels: rgba.to_vec() }
+    }
+
+    /// Convert an image decoded by the gltf crate (None for float formats)
+    pub fn from_gltf(data: &gltf::image::Data) -> Option<Self> {
+        use gltf::image::Format;
+        // Expand to RGBA the way the image crate does: gray fills RGB, missing alpha is opaque
+        // 16-bit channels are little-endian - keep the high byte
+        let pixels: Vec<u8> = match data.format {
+            Format::R8 => data.pixels.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+            Format::R8G8 => data.pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            Format::R8G8B8 => data.pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            Format::R8G8B8A8 => data.pixels.clone(),
+            Format::R16 => data.pixels.chunks_exact(2).flat_map(|p| [p[1], p[1], p[1], 255]).collect(),
+            Format::R16G16 => data.pixels.chunks_exact(4).flat_map(|p| [p[1], p[1], p[1], p[3]]).collect(),
+            Format::R16G16B16 => data.pixels.chunks_exact(6).flat_map(|p| [p[1], p[3], p[5], 255]).collect(),
+            Format::R16G16B16A16 => data.pixels.chunks_exact(8).flat_map(|p| [p[1], p[3], p[5], p[7]]).collect(),
+            Format::R32G32B32FLOAT | Format::R32G32B32A32FLOAT => return None,
+        };
+        Some(Self { width: data.width, height: data.height, pixels })
+    }
+}
+
+/// Image uploaded to the GPU with a full mip chain
+pub struct Texture {
+    gl: Arc<glow::Context>,
+    texture: glow::Texture,
+    pub name: String,              // File name (or what it stands in for) for the UI
+    pub width: u32,
+    pub height: u32,
+    pub color_space: ColorSpace,
+}
+
+impl Texture {
+    /// Upload an image; sRGB images get an sRGB format so sampling returns linear values
+    pub fn new(gl: Arc<glow::Context>, name: &str, image: &TextureImage, color_space: ColorSpace) -> Result<Self, String> {
+        let internal_format = match color_space {
+            ColorSpace::Srgb => glow::SRGB8_ALPHA8,
+            ColorSpace::Linear => glow::RGBA8,
+        };
+        // GL's first row is the bottom one, so v = 0 is the bottom of the image as in OBJ files
+        // (the glTF importer flips its top-down texture coordinates to match)
+        let row = image.width as usize * 4;
+        let pixels: Vec<u8> = image.pixels.chunks_exact(row).rev().flatten().copied().collect();
+        unsafe {
+            let texture = gl.create_texture()?;
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.tex_image_2d(glow::TEXTURE_2D, 0, internal_format as i32, image.width as i32, image.height as i32, 0, glow::RGBA, glow::UNSIGNED_BYTE, glow::PixelUnpackData::Slice(Some(&pixels)));
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+            gl.generate_mipmap(glow::TEXTURE_2D);  // Averages in linear space for sRGB formats
+
+            // Texture's own state, used when no sampler object is bound to the unit
+            let (min, mag) = Filter::Trilinear.gl_filters();
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, min);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, mag);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+
+            Ok(Self { gl, texture, name: name.to_string(), width: image.width, height: image.height, color_space })
+        }
+    }
+
+    /// Bind to a texture unit (leaves that unit active)
+    pub fn bind(&self, gl: &glow::Context, unit: u32) {
+        unsafe {
+            gl.active_texture(glow::TEXTURE0 + unit);
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.texture));
+        }
+    }
+}
+
+// Free GPU memory when the last object using the texture is gone
+impl Drop for Texture {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_texture(self.texture);
+        }
+    }
+}
+
+/// GL sampler object - wrap, filter and anisotropy applied on top of whatever texture is bound
+pub struct Sampler {
+    gl: Arc<glow::Context>,
+    sampler: glow::Sampler,
+    pub settings: SamplerSettings,
+}
+
+impl Sampler {
+    pub fn new(gl: Arc<glow::Context>, settings: SamplerSettings) -> Result<Self, String> {
+        unsafe {
+            let sampler = gl.create_sampler()?;
+            let (min, mag) = settings.filter.gl_filters();
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_MIN_FILTER, min);
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_MAG_FILTER, mag);
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_S, settings.wrap.gl_wrap());
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_T, settings.wrap.gl_wrap());
+
+            // Anisotropic filtering is an extension before GL 4.6 - skip it where missing
+            let extensions = gl.supported_extensions();
+            if extensions.contains("GL_EXT_texture_filter_anisotropic") || extensions.contains("GL_ARB_texture_filter_anisotropic") {
+                let supported = gl.get_parameter_f32(glow::MAX_TEXTURE_MAX_ANISOTROPY);
+                let anisotropy = (settings.anisotropy.clamp(1, MAX_ANISOTROPY) as f32).min(supported);
+                gl.sampler_parameter_f32(sampler, glow::TEXTURE_MAX_ANISOTROPY, anisotropy);
+            }
+            Ok(Self { gl, sampler, settings })
+        }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_sampler(self.sampler);
+        }
+    }
+}
+
+/// Material inputs an object can have a texture for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureSlot {
+    BaseColor,          // Multiplies the base/diffuse color
+    MetallicRoughness,  // glTF packing: G = roughness, B = metallic
+    Occlusion,          // R = ambient occlusion
+    Emissive,           // Multiplies the emissive color
+    Normal,             // Tangent-space normal map (OpenGL convention, +Y up)
+}
+
+impl TextureSlot {
+    pub const ALL: [TextureSlot; 5] = [
+        TextureSlot::BaseColor,
+        TextureSlot::MetallicRoughness,
+        TextureSlot::Occlusion,
+        TextureSlot::Emissive,
+        TextureSlot::Normal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextureSlot::BaseColor => "Base color",
+            TextureSlot::MetallicRoughness => "Metallic-roughness",
+            TextureSlot::Occlusion => "Occlusion",
+            TextureSlot::Emissive => "Emissive",
+            TextureSlot::Normal => "Normal",
+        }
+    }
+
+    /// Sampler uniform in the lit shaders
+    fn uniform(&self) -> &'static str {
+        match self {
+            TextureSlot::BaseColor => "u_base_color_map",
+            TextureSlot::MetallicRoughness => "u_metallic_roughness_map",
+            TextureSlot::Occlusion => "u_occlusion_map",
+            TextureSlot::Emissive => "u_emissive_map",
+            TextureSlot::Normal => "u_normal_map",
+        }
+    }
+
+    /// Texture unit the slot is always bound to
+    fn unit(&self) -> u32 {
+        FIRST_SLOT_UNIT + *self as u32
+    }
+
+    /// Colors are authored in sRGB, everything else is data
+    pub fn color_space(&self) -> ColorSpace {
+        match self {
+            TextureSlot::BaseColor | TextureSlot::Emissive => ColorSpace::Srgb,
+            TextureSlot::MetallicRoughness | TextureSlot::Occlusion | TextureSlot::Normal => ColorSpace::Linear,
+        }
+    }
+
+    /// Whether Blinn-Phong shading samples this slot (PBR uses them all)
+    pub fn used_by_blinn_phong(&self) -> bool {
+        matches!(self, TextureSlot::BaseColor | TextureSlot::Normal)
+    }
+}
+
+/// A texture and how to sample it
+#[derive(Clone)]
+pub struct TextureBinding {
+    pub texture: Arc<Texture>,
+    pub sampler: Arc<Sampler>,
+}
+
+/// Per-object textures, one optional binding per slot
+#[derive(Clone, Default)]
+pub struct MaterialTextures {
+    slots: [Option<TextureBinding>; TextureSlot::ALL.len()],
+}
+
+impl MaterialTextures {
+    pub fn get(&self, slot: TextureSlot) -> Option<&TextureBinding> {
+        self.slots[slot as usize].as_ref()
+    }
+
+    pub fn set(&mut self, slot: TextureSlot, binding: Option<TextureBinding>) {
+        self.slots[slot as usize] = binding;
+    }
+
+    /// Bind every slot to its unit; empty slots get a neutral default texture
+    pub fn bind(&self, gl: &glow::Context, defaults: &DefaultTextures) {
+        for slot in TextureSlot::ALL {
+            unsafe {
+                match self.get(slot) {
+                    Some(binding) => {
+                        binding.texture.bind(gl, slot.unit());
+                        gl.bind_sampler(slot.unit(), Some(binding.sampler.sampler));
+                    }
+                    None => {
+                        defaults.for_slot(slot).bind(gl, slot.unit());
+                        gl.bind_sampler(slot.unit(), None);
+                    }
+                }
+            }
+        }
+        unsafe { gl.active_texture(glow::TEXTURE0) };  // egui expects unit 0 active
+    }
+}
+
+/// Stand-ins for empty slots that leave the material unchanged
+pub struct DefaultTextures {
+    white: Texture,        // Factors multiply by one
+    flat_normal: Texture,  // Straight up in tangent space
+}
+
+impl DefaultTextures {
+    pub fn new(gl: Arc<glow::Context>) -> Result<Self, String> {
+        Ok(Self {
+            white: Texture::new(gl.clone(), "White", &TextureImage::solid([255; 4]), ColorSpace::Linear)?,
+            flat_normal: Texture::new(gl, "Flat normal", &TextureImage::solid([128, 128, 255, 255]), ColorSpace::Linear)?,
+        })
+    }
+
+    fn for_slot(&self, slot: TextureSlot) -> &Texture {
+        match slot {
+            TextureSlot::Normal => &self.flat_normal,
+            _ => &self.white,
+        }
+    }
+}
+
+/// Point a lit program's slot samplers at their units (they never change)
+pub fn init_program(gl: &glow::Context, program: glow::Program) {
+    unsafe {
+        gl.use_program(Some(program));
+        for slot in TextureSlot::ALL {
+            gl.uniform_1_i32(gl.get_uniform_location(program, slot.uniform()).as_ref(), slot.unit() as i32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_png_to_rgba() {
+        let mut image = image::RgbImage::new(2, 1);
+        image.put_pixel(0, 0, image::Rgb([255, 0, 0]));
+        image.put_pixel(1, 0, image::Rgb([0, 0, 255]));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+
+        let decoded = TextureImage::decode(bytes.get_ref()).unwrap();
+        assert_eq!((decoded.width, decoded.height), (2, 1));
+        assert_eq!(decoded.pixels, vec![255, 0, 0, 255, 0, 0, 255, 255]);  // Alpha filled in
+    }
+
+    #[test]
+    fn missing_or_corrupt_files_fall_back_to_checkerboard() {
+        let (image, error) = TextureImage::open_or_checkerboard(Path::new("does/not/exist.png"));
+        assert!(matches!(error, Some(TextureError::Io(_))));
+        assert_eq!(image, TextureImage::checkerboard());
+
+        assert!(matches!(TextureImage::decode(b"\x89PNG\r\n\x1a\nnot really"), Err(TextureError::Decode(_))));
+
+        // Neighboring cells differ
+        let board = TextureImage::checkerboard();
+        assert_ne!(board.pixels[0..4], board.pixels[8 * 4..8 * 4 + 4]);
+    }
+
+    #[test]
+    fn slots_use_distinct_units_after_shadows() {
+        let units: Vec<u32> = TextureSlot::ALL.iter().map(|slot| slot.unit()).collect();
+        assert!(units.iter().all(|&unit| unit > SHADOW_UNIT && unit < 16));  // GL 3.3 guarantees 16
+        assert!(units.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(TextureSlot::BaseColor.color_space(), ColorSpace::Srgb);
+        assert_eq!(TextureSlot::MetallicRoughness.color_space(), ColorSpace::Linear);
+    }
+}
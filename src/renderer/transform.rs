@@ -0,0 +1,108 @@
+// Object transforms - translation, rotation and scale kept separately so they can be edited
+use nalgebra_glm as glm;  // Vectors, quaternions and matrices
+
+/// Placement of an object: scaled, then rotated, then moved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: glm::Vec3,
+    pub rotation: glm::Quat,  // Unit quaternion
+    pub scale: glm::Vec3,     // Per-axis, along the object's own axes
+}
+
+impl Default for Transform {
+    /// Identity - the object stays where its vertices put it
+    fn default() -> Self {
+        Self {
+            translation: glm::Vec3::zeros(),
+            rotation: glm::Quat::identity(),
+            scale: glm::vec3(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl Transform {
+    /// Object-to-parent matrix: T * R * S
+    pub fn matrix(&self) -> glm::Mat4 {
+        glm::translation(&self.translation) * glm::quat_to_mat4(&self.rotation) * glm::scaling(&self.scale)
+    }
+
+    /// Split an affine matrix into translation, rotation and scale
+    /// Shear (rare, from non-uniform scale under a rotated parent) is dropped
+    pub fn from_matrix(matrix: &glm::Mat4) -> Self {
+        let translation = matrix.fixed_view::<3, 1>(0, 3).into_owned();
+        let mut basis = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+        let mut scale = glm::vec3(basis.column(0).norm(), basis.column(1).norm(), basis.column(2).norm());
+
+        // A mirrored basis can't be a rotation - fold the flip into the X scale
+        if basis.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+        for axis in 0..3 {
+            if scale[axis] != 0.0 {
+                let column = basis.column(axis) / scale[axis];
+                basis.set_column(axis, &column);
+            }
+        }
+
+        Self {
+            translation,
+            rotation: glm::mat3_to_quat(&basis).normalize(),
+            scale,
+        }
+    }
+
+    /// Split a matrix only if translation, rotation and scale rebuild it - None if it has shear or projection
+    pub fn from_matrix_exact(matrix: &glm::Mat4) -> Option<Self> {
+        let transform = Self::from_matrix(matrix);
+        let rebuilt = transform.matrix();
+        let exact = matrix.iter().zip(rebuilt.iter()).all(|(a, b)| (a - b).abs() <= 1e-4 * a.abs().max(1.0));
+        exact.then_some(transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_round_trips_through_decomposition() {
+        let original = Transform {
+            translation: glm::vec3(1.0, -2.0, 3.0),
+            rotation: glm::quat_angle_axis(0.7, &glm::vec3(1.0, 2.0, 0.5).normalize()),
+            scale: glm::vec3(2.0, 0.5, 1.5),
+        };
+        let decomposed = Transform::from_matrix(&original.matrix());
+
+        assert!((decomposed.translation - original.translation).norm() < 1e-5);
+        assert!((decomposed.scale - original.scale).norm() < 1e-5);
+        // q and -q are the same rotation
+        assert!(glm::quat_dot(&decomposed.rotation, &original.rotation).abs() > 1.0 - 1e-5);
+    }
+
+    #[test]
+    fn exact_decomposition_refuses_shear() {
+        let trs = Transform {
+            translation: glm::vec3(0.5, 0.0, -1.0),
+            rotation: glm::quat_angle_axis(0.4, &glm::Vec3::y()),
+            scale: glm::vec3(1.0, 3.0, 0.5),
+        };
+        assert!(Transform::from_matrix_exact(&trs.matrix()).is_some());
+
+        // Non-uniform scale above a rotated child - the world matrix skews the child's axes
+        let child = Transform { rotation: glm::quat_angle_axis(0.8, &glm::Vec3::z()), ..Transform::default() };
+        let sheared = glm::scaling(&glm::vec3(3.0, 1.0, 1.0)) * child.matrix();
+        assert!(Transform::from_matrix_exact(&sheared).is_none());
+    }
+
+    #[test]
+    fn scale_applies_before_rotation() {
+        let transform = Transform {
+            rotation: glm::quat_angle_axis(std::f32::consts::FRAC_PI_2, &glm::Vec3::z()),
+            scale: glm::vec3(2.0, 1.0, 1.0),
+            ..Transform::default()
+        };
+        // Local +X is stretched to 2, then turned onto +Y
+        let point = transform.matrix() * glm::vec4(1.0, 0.0, 0.0, 1.0);
+        assert!((point.xyz() - glm::vec3(0.0, 2.0, 0.0)).norm() < 1e-5);
+    }
+}
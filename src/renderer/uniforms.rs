@@ -0,0 +1,222 @@
+// Uniform reflection - what a linked program declares, and user-set values for the ones the app doesn't drive
+// Any uniform a shader declares beyond the app's own becomes an editable parameter in the inspector
+use glow::HasContext;  // Trait providing OpenGL function methods
+use std::collections::{BTreeMap, HashMap};
+
+/// Parameter values by program name, then uniform name - components in order, arrays flattened
+/// (bools and ints are stored as floats). Saved in the config so tweaks survive restarts
+pub type ShaderParameters = BTreeMap<String, BTreeMap<String, Vec<f32>>>;
+
+/// Uniform names (or prefixes) the renderer sets itself every frame
+const APP_UNIFORMS: [&str; 16] = [
+    "u_projection", "u_view", "u_model", "u_camera_", "u_ambient", "u_lights", "u_light_count",
+    "u_material", "u_pbr", "u_shadow", "u_cascade", "u_pcf_radius", "u_has_environment",
+    "u_environment_", "u_prefiltered_max_lod", "u_object_id",
+];
+
+/// GLSL type of a uniform, as far as the inspector cares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniformKind {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+    Int,
+    Bool,
+    Mat3,
+    Mat4,
+    Sampler,
+    Other,  // Anything else (uints, other matrices...) - listed, not editable
+}
+
+impl UniformKind {
+    /// From the type enum glGetActiveUniform reports
+    pub fn from_gl(utype: u32) -> Self {
+        match utype {
+            glow::FLOAT => UniformKind::Float,
+            glow::FLOAT_VEC2 => UniformKind::Vec2,
+            glow::FLOAT_VEC3 => UniformKind::Vec3,
+            glow::FLOAT_VEC4 => UniformKind::Vec4,
+            glow::INT => UniformKind::Int,
+            glow::BOOL => UniformKind::Bool,
+            glow::FLOAT_MAT3 => UniformKind::Mat3,
+            glow::FLOAT_MAT4 => UniformKind::Mat4,
+            glow::SAMPLER_2D | glow::SAMPLER_2D_ARRAY | glow::SAMPLER_CUBE | glow::SAMPLER_3D => UniformKind::Sampler,
+            _ => UniformKind::Other,
+        }
+    }
+
+    /// GLSL spelling, for the table
+    pub fn name(&self) -> &'static str {
+        match self {
+            UniformKind::Float => "float",
+            UniformKind::Vec2 => "vec2",
+            UniformKind::Vec3 => "vec3",
+            UniformKind::Vec4 => "vec4",
+            UniformKind::Int => "int",
+            UniformKind::Bool => "bool",
+            UniformKind::Mat3 => "mat3",
+            UniformKind::Mat4 => "mat4",
+            UniformKind::Sampler => "sampler",
+            UniformKind::Other => "other",
+        }
+    }
+
+    /// Values per array element (0 = not editable)
+    pub fn components(&self) -> usize {
+        match self {
+            UniformKind::Float | UniformKind::Int | UniformKind::Bool => 1,
+            UniformKind::Vec2 => 2,
+            UniformKind::Vec3 => 3,
+            UniformKind::Vec4 => 4,
+            _ => 0,
+        }
+    }
+}
+
+/// One active uniform of a linked program
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformInfo {
+    pub name: String,  // Without the "[0]" drivers add to arrays
+    pub kind: UniformKind,
+    pub size: usize,   // Array length, 1 for plain uniforms
+}
+
+impl UniformInfo {
+    /// Whether the renderer sets this one itself (samplers included - they're bound to fixed units)
+    pub fn is_app_driven(&self) -> bool {
+        self.kind == UniformKind::Sampler || APP_UNIFORMS.iter().any(|prefix| self.name.starts_with(prefix))
+    }
+
+    /// Editable in the inspector
+    pub fn is_parameter(&self) -> bool {
+        !self.is_app_driven() && self.kind.components() > 0
+    }
+
+    /// Name of array element `index` (the plain name when not an array)
+    fn element(&self, index: usize) -> String {
+        if self.size > 1 { format!("{}[{}]", self.name, index) } else { self.name.clone() }
+    }
+}
+
+/// Query the active uniforms of a linked program, sorted by name
+pub fn reflect(gl: &glow::Context, program: glow::Program) -> Vec<UniformInfo> {
+    let mut uniforms: Vec<UniformInfo> = unsafe {
+        (0..gl.get_active_uniforms(program))
+            .filter_map(|index| gl.get_active_uniform(program, index))
+            .map(|uniform| UniformInfo {
+                name: uniform.name.strip_suffix("[0]").unwrap_or(&uniform.name).to_string(),
+                kind: UniformKind::from_gl(uniform.utype),
+                size: uniform.size.max(1) as usize,
+            })
+            .collect()
+    };
+    uniforms.sort_by(|a, b| a.name.cmp(&b.name));
+    uniforms
+}
+
+/// Locations of a program's uniforms, resolved once after linking so draws don't ask the driver by name
+/// Array elements are listed individually ("u_shadow_matrices[2]"); block members have none
+#[derive(Debug, Clone, Default)]
+pub struct UniformLocations {
+    locations: HashMap<String, glow::UniformLocation>,
+}
+
+impl UniformLocations {
+    /// Resolve every active uniform of a linked program
+    pub fn new(gl: &glow::Context, program: glow::Program) -> Self {
+        Self::resolve(gl, program, &reflect(gl, program))
+    }
+
+    /// Resolve already reflected uniforms
+    pub fn resolve(gl: &glow::Context, program: glow::Program, uniforms: &[UniformInfo]) -> Self {
+        let mut locations = HashMap::new();
+        for info in uniforms {
+            for index in 0..info.size {
+                let Some(location) = (unsafe { gl.get_uniform_location(program, &info.element(index)) }) else { continue };
+                if index == 0 {
+                    // An array's first element answers to both spellings, like glGetUniformLocation
+                    locations.insert(info.name.clone(), location);
+                    locations.insert(format!("{}[0]", info.name), location);
+                } else {
+                    locations.insert(info.element(index), location);
+                }
+            }
+        }
+        Self { locations }
+    }
+
+    /// Location of a uniform or array element - None if the program doesn't use it (setting None is a no-op)
+    pub fn get(&self, name: &str) -> Option<&glow::UniformLocation> {
+        self.locations.get(name)
+    }
+}
+
+/// Current value of a parameter in `program` - its GLSL initializer until something is uploaded
+pub fn read(gl: &glow::Context, program: glow::Program, info: &UniformInfo) -> Vec<f32> {
+    let components = info.kind.components();
+    let mut values = vec![0.0; components * info.size];
+    for (index, element) in values.chunks_mut(components.max(1)).enumerate() {
+        let Some(location) = (unsafe { gl.get_uniform_location(program, &info.element(index)) }) else { continue };
+        unsafe {
+            match info.kind {
+                UniformKind::Int | UniformKind::Bool => {
+                    let mut ints = [0i32; 1];
+                    gl.get_uniform_i32(program, &location, &mut ints);
+                    element[0] = ints[0] as f32;
+                }
+                _ if components > 0 => gl.get_uniform_f32(program, &location, element),
+                _ => {}
+            }
+        }
+    }
+    values
+}
+
+/// Upload stored parameter values to the program in use, skipping ones it doesn't declare
+pub fn apply(gl: &glow::Context, locations: &UniformLocations, uniforms: &[UniformInfo], values: &BTreeMap<String, Vec<f32>>) {
+    for (name, values) in values {
+        let Some(info) = uniforms.iter().find(|info| &info.name == name && info.is_parameter()) else { continue };
+        if values.len() != info.kind.components() * info.size {
+            continue;  // The declaration changed since this was stored
+        }
+        unsafe {
+            let location = locations.get(&info.element(0));
+            match info.kind {
+                UniformKind::Float => gl.uniform_1_f32_slice(location, values),
+                UniformKind::Vec2 => gl.uniform_2_f32_slice(location, values),
+                UniformKind::Vec3 => gl.uniform_3_f32_slice(location, values),
+                UniformKind::Vec4 => gl.uniform_4_f32_slice(location, values),
+                UniformKind::Int | UniformKind::Bool => {
+                    let ints: Vec<i32> = values.iter().map(|&value| value.round() as i32).collect();
+                    gl.uniform_1_i32_slice(location, &ints);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_gl_types_to_editable_kinds() {
+        assert_eq!(UniformKind::from_gl(glow::FLOAT_VEC3), UniformKind::Vec3);
+        assert_eq!(UniformKind::from_gl(glow::SAMPLER_CUBE), UniformKind::Sampler);
+        assert_eq!(UniformKind::from_gl(glow::UNSIGNED_INT), UniformKind::Other);
+        assert_eq!(UniformKind::Vec4.components(), 4);
+        assert_eq!(UniformKind::Mat4.components(), 0);
+    }
+
+    #[test]
+    fn only_free_uniforms_are_parameters() {
+        let uniform = |name: &str, kind| UniformInfo { name: name.to_string(), kind, size: 1 };
+        assert!(uniform("u_rim_power", UniformKind::Float).is_parameter());
+        assert!(!uniform("u_lights[3].color", UniformKind::Vec3).is_parameter());
+        assert!(!uniform("u_camera_position", UniformKind::Vec3).is_parameter());
+        assert!(!uniform("u_detail_map", UniformKind::Sampler).is_parameter());
+        assert!(!uniform("u_rim_matrix", UniformKind::Mat4).is_parameter());
+    }
+}
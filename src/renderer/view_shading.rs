@@ -0,0 +1,218 @@
+// Per-viewport display modes - how a view draws the shared scene, independent of the objects' materials
+// Everything except Shaded goes through one diagnostic program: a geometry shader adds barycentrics
+// (for the wireframe overlay) and the face normal (for flat shading), the fragment shader picks the mode
+use super::{camera_block, shader};
+
+/// How a viewport draws the scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewShading {
+    /// Each object's own material and lighting
+    #[default]
+    Shaded,
+    /// Shaded, with every triangle's edges drawn over it
+    Wireframe,
+    /// One normal per triangle, lit from the eye - shows faceting and flipped faces
+    Flat,
+    /// World-space normal as color (x -> red, y -> green, z -> blue)
+    Normals,
+    /// Distance from the eye between the clip planes, near = white
+    Depth,
+    /// Checkerboard over the UVs, tinted by U and V - shows stretching, seams and missing UVs
+    UvChecker,
+    /// Clay look from the view-space normal alone - shows surface shape without lights or materials
+    Matcap,
+}
+
+impl ViewShading {
+    /// Every mode, in menu order
+    pub const ALL: [ViewShading; 7] = [
+        ViewShading::Shaded,
+        ViewShading::Wireframe,
+        ViewShading::Flat,
+        ViewShading::Normals,
+        ViewShading::Depth,
+        ViewShading::UvChecker,
+        ViewShading::Matcap,
+    ];
+
+    /// Display name for menus
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewShading::Shaded => "Shaded",
+            ViewShading::Wireframe => "Wireframe",
+            ViewShading::Flat => "Flat",
+            ViewShading::Normals => "Normals",
+            ViewShading::Depth => "Depth",
+            ViewShading::UvChecker => "UV Checker",
+            ViewShading::Matcap => "Matcap",
+        }
+    }
+
+    /// Drawn with the objects' materials and lights (and the sky, shadows and full post chain)
+    /// The other modes show data, so exposure, bloom and tone mapping stay out of them
+    pub fn uses_materials(self) -> bool {
+        matches!(self, ViewShading::Shaded | ViewShading::Wireframe)
+    }
+
+    /// Value of the diagnostic shader's u_mode for this mode's pass (0 = none)
+    /// Wireframe is an overlay drawn after the shaded pass
+    pub fn shader_mode(self) -> i32 {
+        match self {
+            ViewShading::Shaded => 0,
+            ViewShading::Wireframe => 1,
+            ViewShading::Flat => 2,
+            ViewShading::Normals => 3,
+            ViewShading::Depth => 4,
+            ViewShading::UvChecker => 5,
+            ViewShading::Matcap => 6,
+        }
+    }
+}
+
+// Same attributes and camera block as mesh.vert - the Camera block is spliced in from camera.glsl
+const DIAGNOSTIC_VERTEX_HEADER: &str = "#version 330 core\n";
+const DIAGNOSTIC_VERTEX_BODY: &str = r#"
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec3 color;
+layout(location = 3) in vec2 uv;
+
+uniform mat4 u_model;
+
+out vec3 vs_position;  // World space
+out vec3 vs_normal;
+out vec3 vs_color;
+out vec2 vs_uv;
+
+void main() {
+    vec4 world_pos = u_model * vec4(position, 1.0);
+    vs_position = world_pos.xyz;
+    vs_normal = mat3(transpose(inverse(u_model))) * normal;
+    vs_color = color;
+    vs_uv = uv;
+    gl_Position = u_projection * u_view * world_pos;
+}
+"#;
+
+// Per triangle: barycentric corners for the wireframe and the true face normal for flat shading
+const DIAGNOSTIC_GEOMETRY_SHADER_SOURCE: &str = r#"#version 330 core
+layout(triangles) in;
+layout(triangle_strip, max_vertices = 3) out;
+
+in vec3 vs_position[];
+in vec3 vs_normal[];
+in vec3 vs_color[];
+in vec2 vs_uv[];
+
+out vec3 v_position;
+out vec3 v_normal;
+out vec3 v_color;
+out vec2 v_uv;
+out vec3 v_barycentric;
+flat out vec3 v_face_normal;
+
+void main() {
+    vec3 face_normal = normalize(cross(vs_position[1] - vs_position[0], vs_position[2] - vs_position[0]));
+    for (int i = 0; i < 3; i++) {
+        v_position = vs_position[i];
+        v_normal = vs_normal[i];
+        v_color = vs_color[i];
+        v_uv = vs_uv[i];
+        v_barycentric = vec3(i == 0, i == 1, i == 2);
+        v_face_normal = face_normal;
+        gl_Position = gl_in[i].gl_Position;
+        EmitVertex();
+    }
+    EndPrimitive();
+}
+"#;
+
+const DIAGNOSTIC_FRAGMENT_HEADER: &str = "#version 330 core\n";
+const DIAGNOSTIC_FRAGMENT_BODY: &str = r#"
+in vec3 v_position;
+in vec3 v_normal;
+in vec3 v_color;
+in vec2 v_uv;
+in vec3 v_barycentric;
+flat in vec3 v_face_normal;
+
+uniform int u_mode;          // ViewShading::shader_mode
+uniform vec3 u_base_color;   // The material's diffuse / base color, times the vertex color for flat shading
+uniform vec2 u_depth_range;  // Camera near and far
+
+out vec4 frag_color;
+
+const float WIRE_WIDTH = 1.0;  // Pixels
+
+// Lighting-free clay, looked up by the view-space normal like a matcap image would be
+vec3 matcap(vec3 view_normal) {
+    vec3 clay = vec3(0.78, 0.72, 0.66);
+    float key = max(dot(view_normal, normalize(vec3(-0.4, 0.6, 0.7))), 0.0);
+    float fill = max(dot(view_normal, normalize(vec3(0.6, -0.2, 0.5))), 0.0);
+    float rim = pow(1.0 - max(view_normal.z, 0.0), 3.0);
+    float highlight = pow(max(dot(reflect(vec3(0.0, 0.0, -1.0), view_normal), normalize(vec3(-0.4, 0.6, 0.7))), 0.0), 24.0);
+    return clay * (0.15 + 0.7 * key + 0.2 * fill) + vec3(0.25 * rim + 0.35 * highlight);
+}
+
+void main() {
+    vec3 color;
+    if (u_mode == 1) {
+        // Edge factor from the distance to the nearest edge in pixels, so lines stay one width at any zoom
+        vec3 width = fwidth(v_barycentric) * WIRE_WIDTH;
+        vec3 edge = smoothstep(vec3(0.0), width, v_barycentric);
+        float line = 1.0 - min(edge.x, min(edge.y, edge.z));
+        if (line < 0.01) {
+            discard;
+        }
+        frag_color = vec4(vec3(0.0), line * 0.85);  // Blended over the shaded pass
+        return;
+    } else if (u_mode == 2) {
+        vec3 normal = gl_FrontFacing ? v_face_normal : -v_face_normal;
+        float facing = max(dot(normal, normalize(u_camera_position - v_position)), 0.0);
+        color = v_color * u_base_color * (0.15 + 0.85 * facing);
+        if (!gl_FrontFacing) {
+            color *= vec3(1.0, 0.25, 0.25);  // Seen from behind - usually a flipped winding
+        }
+    } else if (u_mode == 3) {
+        color = normalize(v_normal) * 0.5 + 0.5;
+    } else if (u_mode == 4) {
+        float depth = dot(v_position - u_camera_position, u_camera_forward);
+        color = vec3(1.0 - clamp((depth - u_depth_range.x) / (u_depth_range.y - u_depth_range.x), 0.0, 1.0));
+    } else if (u_mode == 5) {
+        vec2 cell = floor(v_uv * 8.0);
+        float checker = mod(cell.x + cell.y, 2.0);
+        vec2 tint = fract(v_uv);
+        color = mix(vec3(0.2), vec3(0.9), checker) * vec3(0.55 + 0.45 * tint.x, 0.55 + 0.45 * tint.y, 0.75);
+    } else {
+        vec3 view_normal = normalize(mat3(u_view) * normalize(v_normal));
+        color = matcap(gl_FrontFacing ? view_normal : -view_normal);
+    }
+    // These colors are meant as shown - decode so the post chain's gamma gives them back
+    frag_color = vec4(pow(color, vec3(2.2)), 1.0);
+}
+"#;
+
+/// Build the diagnostic program (camera block included and bound)
+pub fn create_diagnostic_program(gl: &glow::Context) -> Result<glow::Program, String> {
+    let vertex = [DIAGNOSTIC_VERTEX_HEADER, camera_block::CAMERA_GLSL, DIAGNOSTIC_VERTEX_BODY].concat();
+    let fragment = [DIAGNOSTIC_FRAGMENT_HEADER, camera_block::CAMERA_GLSL, DIAGNOSTIC_FRAGMENT_BODY].concat();
+    let program = shader::create_program_with_geometry(gl, &vertex, DIAGNOSTIC_GEOMETRY_SHADER_SOURCE, &fragment)?;
+    camera_block::init_program(gl, program);
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_mode_has_its_own_shader_branch() {
+        let mut modes: Vec<i32> = ViewShading::ALL.iter().map(|shading| shading.shader_mode()).collect();
+        modes.sort();
+        modes.dedup();
+        assert_eq!(modes.len(), ViewShading::ALL.len());
+        // Only the shaded modes keep the materials - the rest must not be touched by tone mapping
+        let material: Vec<ViewShading> = ViewShading::ALL.into_iter().filter(|shading| shading.uses_materials()).collect();
+        assert_eq!(material, [ViewShading::Shaded, ViewShading::Wireframe]);
+    }
+}
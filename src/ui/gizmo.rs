@@ -0,0 +1,418 @@
+// Transform gizmo - drag handles drawn over the viewport to move, rotate and scale the selection
+use crate::app::{Gizmo, GizmoMode, GizmoSpace};
+use crate::renderer::{Camera, Transform};
+use nalgebra_glm as glm;  // Ray and plane math
+
+/// A grabbable part of the gizmo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Handle {
+    Axis(usize),   // Arrow / ring / scale bar for axis 0-2
+    Plane(usize),  // Square for the plane perpendicular to axis 0-2
+    Center,        // Uniform scale
+}
+
+/// State captured when a drag starts - edits are always computed from here, so snapping doesn't drift
+/// Kept in egui's memory between frames: there is one pointer, so one drag at a time
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    handle: Handle,
+    start: Transform,        // Object transform at drag start
+    frame: Frame,            // Gizmo position and axes at drag start
+    start_hit: glm::Vec3,    // Where the pointer ray met the handle's line or plane
+    start_pointer: egui::Pos2,
+}
+
+/// Where the gizmo sits this frame
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    origin: glm::Vec3,        // Object origin in world space
+    axes: [glm::Vec3; 3],     // Unit handle directions in world space
+    length: f32,              // World length of an axis handle (constant size on screen)
+}
+
+/// Screen length of an axis handle in points
+const HANDLE_POINTS: f32 = 90.0;
+
+/// How close (in points) the pointer must be to grab a handle
+const GRAB_DISTANCE: f32 = 8.0;
+
+/// Segments used to draw and hit-test rotation rings
+const RING_SEGMENTS: usize = 64;
+
+const AXIS_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(230, 70, 70),
+    egui::Color32::from_rgb(80, 200, 80),
+    egui::Color32::from_rgb(80, 120, 240),
+];
+const ACTIVE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 210, 60);
+
+/// Where the drag in progress is kept in egui's memory
+fn drag_id() -> egui::Id {
+    egui::Id::new("gizmo_drag")
+}
+
+/// Viewport geometry shared by the interaction and drawing code
+pub struct View<'a> {
+    pub camera: &'a Camera,
+    pub rect: egui::Rect,      // Viewport rect in points
+    pub parent: glm::Mat4,     // Matrix the object's transform sits inside (the animation spin and any fixed parents)
+}
+
+impl View<'_> {
+    fn view_projection(&self) -> glm::Mat4 {
+        self.camera.projection_matrix(self.rect.aspect_ratio()) * self.camera.view_matrix()
+    }
+
+    /// World point to screen position (None behind the camera)
+    fn to_screen(&self, point: &glm::Vec3) -> Option<egui::Pos2> {
+        let clip = self.view_projection() * point.push(1.0);
+        if clip.w <= 1e-6 {
+            return None;
+        }
+        let ndc = clip.xyz() / clip.w;
+        Some(egui::pos2(
+            self.rect.min.x + (ndc.x + 1.0) * 0.5 * self.rect.width(),
+            self.rect.min.y + (1.0 - ndc.y) * 0.5 * self.rect.height(),  // NDC Y up, screen Y down
+        ))
+    }
+
+    /// World-space ray through a screen position: (origin, unit direction)
+    fn ray(&self, pointer: egui::Pos2) -> (glm::Vec3, glm::Vec3) {
+        let local = (pointer - self.rect.min) / self.rect.size();
+        let (x, y) = (local.x * 2.0 - 1.0, 1.0 - local.y * 2.0);
+        let aspect = self.rect.aspect_ratio();
+        let near = self.camera.unproject(glm::vec3(x, y, -1.0), aspect);
+        let far = self.camera.unproject(glm::vec3(x, y, 1.0), aspect);
+        (near, (far - near).normalize())
+    }
+}
+
+/// Is a handle being dragged? (The camera should ignore the pointer meanwhile)
+pub fn is_dragging(ctx: &egui::Context) -> bool {
+    ctx.data(|data| data.get_temp::<Drag>(drag_id()).is_some())
+}
+
+/// Start, continue or finish a drag; returns the edited transform while dragging
+pub fn interact(gizmo: &Gizmo, response: &egui::Response, view: &View, transform: &Transform) -> Option<Transform> {
+    if response.drag_started_by(egui::PointerButton::Primary) {
+        // Hit-test where the button went down - the pointer has moved a little by the time a drag registers
+        let pointer = response.ctx.input(|i| i.pointer.press_origin()).or(response.interact_pointer_pos())?;
+        let frame = frame(gizmo, view, transform);
+        let handle = hit_test(gizmo, view, &frame, pointer)?;
+        let start_hit = handle_hit(gizmo, view, &frame, handle, pointer)?;
+        let drag = Drag { handle, start: *transform, frame, start_hit, start_pointer: pointer };
+        response.ctx.data_mut(|data| data.insert_temp(drag_id(), drag));
+    }
+
+    if !response.dragged_by(egui::PointerButton::Primary) {
+        response.ctx.data_mut(|data| data.remove::<Drag>(drag_id()));  // Released (or another button took over)
+        return None;
+    }
+
+    let drag = response.ctx.data(|data| data.get_temp::<Drag>(drag_id()))?;
+    let pointer = response.interact_pointer_pos()?;
+    let hit = handle_hit(gizmo, view, &drag.frame, drag.handle, pointer)?;
+    Some(apply(gizmo, view, &drag, hit, pointer))
+}
+
+/// Draw the handles, highlighting the hovered or dragged one
+pub fn paint(gizmo: &Gizmo, ui: &egui::Ui, response: &egui::Response, view: &View, transform: &Transform) {
+    let frame = frame(gizmo, view, transform);
+    let Some(origin) = view.to_screen(&frame.origin) else { return };
+    let active = match response.ctx.data(|data| data.get_temp::<Drag>(drag_id())) {
+        Some(drag) => Some(drag.handle),
+        None => response.hover_pos().and_then(|pointer| hit_test(gizmo, view, &frame, pointer)),
+    };
+    let color = |handle: Handle, axis: usize| if active == Some(handle) { ACTIVE_COLOR } else { AXIS_COLORS[axis] };
+    let painter = ui.painter().with_clip_rect(view.rect);
+
+    match gizmo.mode {
+        GizmoMode::Translate | GizmoMode::Scale => {
+            for (axis, direction) in frame.axes.iter().enumerate() {
+                let Some(end) = view.to_screen(&(frame.origin + direction * frame.length)) else { continue };
+                let color = color(Handle::Axis(axis), axis);
+                painter.line_segment([origin, end], egui::Stroke::new(3.0, color));
+                if gizmo.mode == GizmoMode::Translate {
+                    // Arrow head: triangle pointing along the projected axis
+                    let dir = (end - origin).normalized();
+                    let side = egui::vec2(-dir.y, dir.x) * 5.0;
+                    let tip = end + dir * 12.0;
+                    painter.add(egui::Shape::convex_polygon(vec![tip, end + side, end - side], color, egui::Stroke::NONE));
+                } else {
+                    painter.rect_filled(egui::Rect::from_center_size(end, egui::vec2(9.0, 9.0)), 0.0, color);
+                }
+            }
+            for axis in 0..3 {
+                if let Some(quad) = plane_quad(view, &frame, axis) {
+                    let color = color(Handle::Plane(axis), axis).gamma_multiply(0.6);
+                    painter.add(egui::Shape::convex_polygon(quad.to_vec(), color, egui::Stroke::NONE));
+                }
+            }
+            if gizmo.mode == GizmoMode::Scale {
+                let color = if active == Some(Handle::Center) { ACTIVE_COLOR } else { egui::Color32::WHITE };
+                painter.circle_filled(origin, GRAB_DISTANCE * 0.8, color);
+            }
+        }
+        GizmoMode::Rotate => {
+            for axis in 0..3 {
+                let points = ring_points(view, &frame, axis);
+                let stroke = egui::Stroke::new(2.5, color(Handle::Axis(axis), axis));
+                for pair in points.windows(2) {
+                    if let (Some(a), Some(b)) = (pair[0], pair[1]) {
+                        painter.line_segment([a, b], stroke);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Gizmo origin, axes and size for the object as currently placed
+fn frame(gizmo: &Gizmo, view: &View, transform: &Transform) -> Frame {
+    let origin = (view.parent * transform.translation.push(1.0)).xyz();
+    let axes = if gizmo.space == GizmoSpace::Local || gizmo.mode == GizmoMode::Scale {
+        // Object axes in world space: the parent's rotation applied to the object's
+        let rotation = glm::quat_to_mat4(&transform.rotation);
+        let basis = view.parent * rotation;
+        [0, 1, 2].map(|axis| basis.fixed_view::<3, 1>(0, axis).into_owned().normalize())
+    } else {
+        [glm::Vec3::x(), glm::Vec3::y(), glm::Vec3::z()]
+    };
+    let length = view.camera.pixel_size_at(&origin, view.rect.height()) * HANDLE_POINTS;
+    Frame { origin, axes, length }
+}
+
+/// The handle under the pointer, if any (the center and planes win over axes)
+fn hit_test(gizmo: &Gizmo, view: &View, frame: &Frame, pointer: egui::Pos2) -> Option<Handle> {
+    let origin = view.to_screen(&frame.origin)?;
+    match gizmo.mode {
+        GizmoMode::Translate | GizmoMode::Scale => {
+            if gizmo.mode == GizmoMode::Scale && pointer.distance(origin) <= GRAB_DISTANCE {
+                return Some(Handle::Center);
+            }
+            for axis in 0..3 {
+                if plane_quad(view, frame, axis).is_some_and(|quad| inside_convex(&quad, pointer)) {
+                    return Some(Handle::Plane(axis));
+                }
+            }
+            (0..3)
+                .filter_map(|axis| {
+                    let end = view.to_screen(&(frame.origin + frame.axes[axis] * frame.length))?;
+                    Some((axis, distance_to_segment(pointer, origin, end)))
+                })
+                .filter(|(_, distance)| *distance <= GRAB_DISTANCE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(axis, _)| Handle::Axis(axis))
+        }
+        GizmoMode::Rotate => (0..3)
+            .filter_map(|axis| {
+                let points = ring_points(view, frame, axis);
+                points.windows(2)
+                    .filter_map(|pair| Some(distance_to_segment(pointer, pair[0]?, pair[1]?)))
+                    .min_by(f32::total_cmp)
+                    .map(|distance| (axis, distance))
+            })
+            .filter(|(_, distance)| *distance <= GRAB_DISTANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| Handle::Axis(axis)),
+    }
+}
+
+/// Where the pointer ray meets the line or plane a handle moves along
+fn handle_hit(gizmo: &Gizmo, view: &View, frame: &Frame, handle: Handle, pointer: egui::Pos2) -> Option<glm::Vec3> {
+    let (ray_origin, ray_direction) = view.ray(pointer);
+    match (gizmo.mode, handle) {
+        // Axis drags in translate/scale follow the closest point on the axis line
+        (GizmoMode::Translate | GizmoMode::Scale, Handle::Axis(axis)) => {
+            let t = closest_on_axis(&frame.origin, &frame.axes[axis], &ray_origin, &ray_direction)?;
+            Some(frame.origin + frame.axes[axis] * t)
+        }
+        // Rings rotate in the plane perpendicular to their axis
+        (GizmoMode::Rotate, Handle::Axis(axis)) | (_, Handle::Plane(axis)) => {
+            ray_plane(&frame.origin, &frame.axes[axis], &ray_origin, &ray_direction)
+        }
+        // Uniform scale works from screen distance, no 3D hit needed
+        (_, Handle::Center) => Some(frame.origin),
+    }
+}
+
+/// New object transform for the drag so far
+fn apply(gizmo: &Gizmo, view: &View, drag: &Drag, hit: glm::Vec3, pointer: egui::Pos2) -> Transform {
+    let frame = &drag.frame;
+    let mut transform = drag.start;
+    // World directions -> the parent's space, where the object's transform lives
+    let parent_inverse = view.parent.try_inverse().unwrap_or_else(glm::Mat4::identity);
+    let to_parent = |world: glm::Vec3| (parent_inverse * world.push(0.0)).xyz();
+    let snap = |value: f32, step: f32| if gizmo.snap { snap_to(value, step) } else { value };
+
+    match (gizmo.mode, drag.handle) {
+        (GizmoMode::Translate, Handle::Axis(axis)) => {
+            let direction = frame.axes[axis];
+            let distance = snap(glm::dot(&(hit - drag.start_hit), &direction), gizmo.translate_step);
+            transform.translation += to_parent(direction * distance);
+        }
+        (GizmoMode::Translate, Handle::Plane(axis)) => {
+            // Snap each in-plane axis separately
+            let delta = hit - drag.start_hit;
+            let offset: glm::Vec3 = (0..3)
+                .filter(|other| *other != axis)
+                .map(|other| frame.axes[other] * snap(glm::dot(&delta, &frame.axes[other]), gizmo.translate_step))
+                .sum();
+            transform.translation += to_parent(offset);
+        }
+        (GizmoMode::Rotate, Handle::Axis(axis)) => {
+            let from = drag.start_hit - frame.origin;
+            let to = hit - frame.origin;
+            let angle = snap(signed_angle(&from, &to, &frame.axes[axis]).to_degrees(), gizmo.rotate_step).to_radians();
+            let parent_axis = to_parent(frame.axes[axis]).normalize();
+            transform.rotation = glm::quat_angle_axis(angle, &parent_axis) * drag.start.rotation;
+        }
+        (GizmoMode::Scale, Handle::Axis(axis)) => {
+            let start = glm::dot(&(drag.start_hit - frame.origin), &frame.axes[axis]);
+            let now = glm::dot(&(hit - frame.origin), &frame.axes[axis]);
+            if start.abs() > 1e-6 {
+                transform.scale[axis] *= snap(now / start, gizmo.scale_step);
+            }
+        }
+        (GizmoMode::Scale, Handle::Plane(axis)) => {
+            let start = (drag.start_hit - frame.origin).norm();
+            if start > 1e-6 {
+                let factor = snap((hit - frame.origin).norm() / start, gizmo.scale_step);
+                for other in (0..3).filter(|other| *other != axis) {
+                    transform.scale[other] *= factor;
+                }
+            }
+        }
+        (GizmoMode::Scale, Handle::Center) => {
+            // 100 points to the right doubles the size, to the left halves it
+            let factor = snap(2.0_f32.powf((pointer.x - drag.start_pointer.x) / 100.0), gizmo.scale_step);
+            transform.scale *= factor;
+        }
+        _ => {}
+    }
+    transform
+}
+
+/// Screen corners of the small square handle for the plane perpendicular to `axis`
+fn plane_quad(view: &View, frame: &Frame, axis: usize) -> Option<[egui::Pos2; 4]> {
+    let a = frame.axes[(axis + 1) % 3] * frame.length;
+    let b = frame.axes[(axis + 2) % 3] * frame.length;
+    let (near, far) = (0.2, 0.4);  // Fractions of the handle length
+    let corners = [a * near + b * near, a * far + b * near, a * far + b * far, a * near + b * far];
+    let mut quad = [egui::Pos2::ZERO; 4];
+    for (screen, corner) in quad.iter_mut().zip(corners) {
+        *screen = view.to_screen(&(frame.origin + corner))?;
+    }
+    Some(quad)
+}
+
+/// Screen points around the rotation ring for `axis` (None where behind the camera)
+fn ring_points(view: &View, frame: &Frame, axis: usize) -> Vec<Option<egui::Pos2>> {
+    let u = frame.axes[(axis + 1) % 3];
+    let v = frame.axes[(axis + 2) % 3];
+    (0..=RING_SEGMENTS)
+        .map(|i| {
+            let angle = i as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+            view.to_screen(&(frame.origin + (u * angle.cos() + v * angle.sin()) * frame.length))
+        })
+        .collect()
+}
+
+/// Parameter along the line `origin + t * direction` closest to a ray (None when they are parallel)
+fn closest_on_axis(origin: &glm::Vec3, direction: &glm::Vec3, ray_origin: &glm::Vec3, ray_direction: &glm::Vec3) -> Option<f32> {
+    let w = origin - ray_origin;
+    let b = glm::dot(direction, ray_direction);
+    let denominator = 1.0 - b * b;  // Both directions are unit length
+    if denominator.abs() < 1e-6 {
+        return None;
+    }
+    let d = glm::dot(direction, &w);
+    let e = glm::dot(ray_direction, &w);
+    Some((b * e - d) / denominator)
+}
+
+/// Intersection of a ray with the plane through `origin` with `normal` (None if parallel or behind)
+fn ray_plane(origin: &glm::Vec3, normal: &glm::Vec3, ray_origin: &glm::Vec3, ray_direction: &glm::Vec3) -> Option<glm::Vec3> {
+    let facing = glm::dot(normal, ray_direction);
+    if facing.abs() < 1e-6 {
+        return None;
+    }
+    let t = glm::dot(&(origin - ray_origin), normal) / facing;
+    (t >= 0.0).then(|| ray_origin + ray_direction * t)
+}
+
+/// Angle from `from` to `to` around `axis`, positive counter-clockwise looking down the axis
+fn signed_angle(from: &glm::Vec3, to: &glm::Vec3, axis: &glm::Vec3) -> f32 {
+    glm::dot(&from.cross(to), axis).atan2(glm::dot(from, to))
+}
+
+/// Round to the nearest multiple of `step` (unchanged if step is not positive)
+fn snap_to(value: f32, step: f32) -> f32 {
+    if step > 0.0 { (value / step).round() * step } else { value }
+}
+
+fn distance_to_segment(point: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_sq() > 0.0 { ((point - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0) } else { 0.0 };
+    point.distance(a + ab * t)
+}
+
+/// Is the point inside a convex polygon (either winding)?
+fn inside_convex(polygon: &[egui::Pos2], point: egui::Pos2) -> bool {
+    let mut sign = 0.0;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        let cross = (b - *a).x * (point - *a).y - (b - *a).y * (point - *a).x;
+        if cross != 0.0 {
+            if sign != 0.0 && cross.signum() != sign {
+                return false;
+            }
+            sign = cross.signum();
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_point_on_axis_to_ray() {
+        // Ray straight down at x = 2 crosses the X axis at t = 2
+        let t = closest_on_axis(&glm::Vec3::zeros(), &glm::Vec3::x(), &glm::vec3(2.0, 5.0, 0.0), &-glm::Vec3::y());
+        assert!((t.unwrap() - 2.0).abs() < 1e-5);
+        // Looking along the axis gives no answer
+        assert!(closest_on_axis(&glm::Vec3::zeros(), &glm::Vec3::x(), &glm::vec3(5.0, 0.0, 0.0), &-glm::Vec3::x()).is_none());
+    }
+
+    #[test]
+    fn ray_meets_plane_in_front_only() {
+        let hit = ray_plane(&glm::Vec3::zeros(), &glm::Vec3::y(), &glm::vec3(1.0, 3.0, 2.0), &-glm::Vec3::y());
+        assert_eq!(hit, Some(glm::vec3(1.0, 0.0, 2.0)));
+        assert!(ray_plane(&glm::Vec3::zeros(), &glm::Vec3::y(), &glm::vec3(1.0, 3.0, 2.0), &glm::Vec3::y()).is_none());
+    }
+
+    #[test]
+    fn angles_and_snapping() {
+        let angle = signed_angle(&glm::Vec3::x(), &glm::Vec3::y(), &glm::Vec3::z());
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!((signed_angle(&glm::Vec3::y(), &glm::Vec3::x(), &glm::Vec3::z()) + angle).abs() < 1e-6);
+
+        assert_eq!(snap_to(0.37, 0.25), 0.25);
+        assert_eq!(snap_to(-22.0, 15.0), -15.0);
+        assert_eq!(snap_to(0.37, 0.0), 0.37);
+    }
+
+    #[test]
+    fn point_in_quad_and_segment_distance() {
+        let quad = [egui::pos2(0.0, 0.0), egui::pos2(10.0, 0.0), egui::pos2(10.0, 10.0), egui::pos2(0.0, 10.0)];
+        assert!(inside_convex(&quad, egui::pos2(5.0, 5.0)));
+        assert!(!inside_convex(&quad, egui::pos2(15.0, 5.0)));
+        let reversed: Vec<_> = quad.iter().rev().copied().collect();
+        assert!(inside_convex(&reversed, egui::pos2(5.0, 5.0)));
+
+        assert_eq!(distance_to_segment(egui::pos2(5.0, 3.0), egui::pos2(0.0, 0.0), egui::pos2(10.0, 0.0)), 3.0);
+        assert_eq!(distance_to_segment(egui::pos2(13.0, 4.0), egui::pos2(0.0, 0.0), egui::pos2(10.0, 0.0)), 5.0);
+    }
+}
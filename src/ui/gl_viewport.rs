@@ -1,36 +1,328 @@
 // Import our app state, renderer, and Arc for shared ownership
-use crate::app::AppState;
-use crate::renderer::Renderer;
-use std::sync::Arc;  // Atomic Reference Counter for thread-safe shared ownership
+use crate::app::{AppState, FlyKeys, Selection, ViewportLayout};
+use crate::renderer::{self, Camera, CameraMode, PickTarget, Projection, Renderer, ShaderError, ViewShading};
+use super::gizmo::{self, View};
+use nalgebra_glm as glm;  // Camera math takes glm vectors
 
-/// Display the OpenGL viewport with proper callback rendering
+/// Display the viewports of the current layout, all showing the same scene
+/// `painter` maps each view's offscreen texture to an egui texture id (registered once per view)
+pub fn show_viewports(ui: &mut egui::Ui, renderer: &mut Renderer, painter: &mut egui_glow::Painter, app_state: &mut AppState, rect: egui::Rect) {
+    let rects = split(app_state.layout, rect);
+    // Views the layout no longer shows: release their egui texture ids, then their targets
+    for viewport in &mut app_state.viewports[rects.len()..] {
+        if let Some((id, _)) = viewport.texture.take() {
+            // egui deletes the GL texture with the id - the target's own delete right after finds the name unused
+            painter.free_texture(id);
+        }
+    }
+    renderer.retain_viewports(rects.len());
+    for (index, view_rect) in rects.into_iter().enumerate() {
+        show_viewport(ui, renderer, painter, app_state, index, view_rect);
+    }
+
+    // Broken shader edits are listed over the scene (drawn with the last good programs)
+    let shader_errors = renderer.shader_errors();
+    if !shader_errors.is_empty() {
+        show_shader_errors(ui, &shader_errors, rect);
+    }
+}
+
+/// Gap between neighbouring viewports, in points
+const VIEWPORT_GAP: f32 = 2.0;
+
+/// Divide the panel into the layout's viewports, in index order (left to right, then top to bottom)
+fn split(layout: ViewportLayout, rect: egui::Rect) -> Vec<egui::Rect> {
+    let (columns, rows) = match layout {
+        ViewportLayout::Single => return vec![rect],
+        ViewportLayout::SideBySide => (2, 1),
+        ViewportLayout::Quad => (2, 2),
+    };
+    let size = egui::vec2(
+        (rect.width() - VIEWPORT_GAP * (columns - 1) as f32) / columns as f32,
+        (rect.height() - VIEWPORT_GAP * (rows - 1) as f32) / rows as f32,
+    );
+    let mut rects = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            let min = rect.min + egui::vec2(column as f32 * (size.x + VIEWPORT_GAP), row as f32 * (size.y + VIEWPORT_GAP));
+            rects.push(egui::Rect::from_min_size(min, size));
+        }
+    }
+    rects
+}
+
+/// Display one OpenGL viewport: the scene is rendered offscreen and shown as an image
 /// This is where our 3D cube gets rendered within the egui UI!
-pub fn show_viewport(ui: &mut egui::Ui, renderer: &Renderer, app_state: &AppState, rect: egui::Rect) {
+/// Only the active viewport takes camera input, gizmo drags and picks - clicking another one activates it
+fn show_viewport(ui: &mut egui::Ui, renderer: &mut Renderer, painter: &mut egui_glow::Painter, app_state: &mut AppState, index: usize, rect: egui::Rect) {
     // Allocate space in the UI for our 3D viewport
-    let response = ui.allocate_rect(rect, egui::Sense::hover());  // Sense::hover = track mouse hover
-    
+    let response = ui.allocate_rect(rect, egui::Sense::click_and_drag());  // Clicks and drags drive the camera
+
+    // Any press into an inactive view hands it the input - the same press already acts on it
+    if index != app_state.active_viewport && (response.clicked() || response.secondary_clicked() || response.drag_started()) {
+        app_state.set_active_viewport(index);
+    }
+    let active = index == app_state.active_viewport;
+
     // Calculate rotation based on frame count (makes cube spin)
     let rotation = app_state.frame_count as f32 * 0.01;  // Convert to f32 and scale down
-    
-    // Clone renderer for use in the callback closure
-    let renderer_clone = renderer.clone();  // Clone is cheap because Renderer uses Arc internally
-    
-    // Create egui paint callback - this is where OpenGL rendering happens!
-    let callback = egui::PaintCallback {
-        rect: response.rect,  // Where to render in screen coordinates
-        callback: Arc::new(egui_glow::CallbackFn::new(move |_info, painter| {
-            // This closure runs during egui's paint phase
-            // move = take ownership of renderer_clone and rotation
-            
-            // Get the OpenGL context from egui's painter
-            let gl = painter.gl();  // This is our glow::Context
-            
-            // Render our 3D cube using the modular renderer
-            renderer_clone.render_viewport(gl, response.rect, rotation);
-        })),
+
+    // The gizmo gets first go at a drag - grabbing a handle edits the selection instead of orbiting
+    let selected = app_state.selection.as_ref()
+        .and_then(|selection| Some((selection.index, renderer.object_transform(selection.index)?, renderer.object_parent(selection.index)?)));
+    if let (true, Some((object, transform, parent))) = (active, selected) {
+        let view = View { camera: &app_state.viewports[index].camera, rect, parent: renderer::spin_matrix(rotation) * parent };
+        if let Some(edited) = gizmo::interact(&app_state.gizmo, &response, &view, &transform) {
+            renderer.set_object_transform(object, edited);
+        }
+    }
+
+    // Apply this frame's mouse input to the camera; inactive views just finish any glide
+    let probe = if active {
+        handle_camera_input(ui, &response, app_state, index)
+    } else {
+        let dt = ui.input(|i| i.stable_dt).min(0.1);
+        app_state.viewports[index].camera.update(dt);
+        None
     };
-    
-    // Add our callback to egui's paint list
-    ui.painter().add(callback);  // egui will call our callback during rendering
+
+    // A surface point found by last frame's double-click becomes the new pivot
+    if let Some(pivot) = renderer.take_depth_probe(index) {
+        app_state.viewports[index].camera.set_pivot(pivot);
+        app_state.status_text = format!("Pivot set to ({:.2}, {:.2}, {:.2})", pivot.x, pivot.y, pivot.z);
+    }
+
+    // Fit the active view to the scene if asked, and every view's clip planes to it every frame
+    let sphere = renderer.bounding_sphere(rotation);
+    if active && app_state.frame_all_requested {
+        app_state.frame_all_requested = false;  // Reset flag immediately
+        let status = match sphere {
+            Some(sphere) => {
+                app_state.viewports[index].camera.frame(&sphere, rect.aspect_ratio());
+                let size = renderer.bounds(rotation).size();
+                format!("Framed scene ({:.2} x {:.2} x {:.2})", size.x, size.y, size.z)
+            }
+            None => "Nothing to frame".to_string(),
+        };
+        if !std::mem::take(&mut app_state.frame_all_quiet) {
+            app_state.status_text = status;
+        }
+    }
+    if let Some(sphere) = &sphere {
+        app_state.viewports[index].camera.fit_clip_planes(sphere);
+    }
+    let camera = app_state.viewports[index].camera;  // Copy - the camera as it renders this frame
+    let shading = app_state.viewports[index].shading;
+
+    // Left-click selects the object under the cursor (a drag orbits instead)
+    if active && response.clicked() {
+        if let Some(pointer) = response.interact_pointer_pos() {
+            pick_object(ui, renderer, app_state, &camera, rect, pointer, rotation);
+        }
+    }
+
+    // Render the scene now, at the rect's size in physical pixels (the GL context is current while the UI is built)
+    let pixels_per_point = ui.ctx().pixels_per_point();
+    let width = (rect.width() * pixels_per_point).round() as i32;
+    let height = (rect.height() * pixels_per_point).round() as i32;
+    match renderer.render_offscreen(index, (width, height), &camera, rotation, shading, probe) {
+        Ok(texture) => {
+            // The texture handle is stable, so it is only registered again if the renderer ever swaps it
+            let id = match app_state.viewports[index].texture {
+                Some((id, registered)) if registered == texture => id,
+                Some((id, _)) => {
+                    painter.replace_native_texture(id, texture);
+                    id
+                }
+                None => painter.register_native_texture(texture),
+            };
+            app_state.viewports[index].texture = Some((id, texture));
+
+            // GL's rows start at the bottom - flip V so the image is upright
+            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 1.0), egui::pos2(1.0, 0.0));
+            egui::Image::new(egui::load::SizedTexture::new(id, rect.size())).uv(uv).paint_at(ui, rect);
+        }
+        Err(e) => {
+            ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, format!("Viewport unavailable: {}", e),
+                egui::FontId::proportional(14.0), egui::Color32::from_rgb(255, 110, 110));
+        }
+    }
+
+    // Draw the handles over the image, in the view that edits them
+    if let (true, Some(object)) = (active, app_state.selection.as_ref().map(|selection| selection.index)) {
+        if let (Some(transform), Some(parent)) = (renderer.object_transform(object), renderer.object_parent(object)) {
+            let view = View { camera: &camera, rect, parent: renderer::spin_matrix(rotation) * parent };
+            gizmo::paint(&app_state.gizmo, ui, &response, &view, &transform);
+        }
+    }
+
+    // With several views on screen, outline them and highlight the one taking input
+    if app_state.layout.count() > 1 {
+        let stroke = if active {
+            egui::Stroke::new(2.0, ui.visuals().selection.bg_fill)
+        } else {
+            ui.visuals().widgets.noninteractive.bg_stroke
+        };
+        ui.painter().rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Inside);
+    }
+    show_view_header(ui, app_state, index, rect);
 }  // End of show_viewport function
 
+/// The view's own settings in its top-left corner: projection and display mode
+fn show_view_header(ui: &mut egui::Ui, app_state: &mut AppState, index: usize, rect: egui::Rect) {
+    let mut header = ui.new_child(egui::UiBuilder::new().max_rect(rect.shrink(6.0)).layout(egui::Layout::left_to_right(egui::Align::Min)));
+    let viewport = &mut app_state.viewports[index];
+    let projection = match viewport.camera.projection {
+        Projection::Perspective => "Perspective",
+        Projection::Orthographic => "Orthographic",
+    };
+    if header.small_button(projection).on_hover_text("Switch this view between perspective and orthographic").clicked() {
+        viewport.camera.toggle_projection();
+    }
+    egui::ComboBox::from_id_salt(("view_shading", index))
+        .selected_text(viewport.shading.name())
+        .width(90.0)
+        .show_ui(&mut header, |ui| {
+            for option in ViewShading::ALL {
+                ui.selectable_value(&mut viewport.shading, option, option.name());
+            }
+        });
+}
+
+/// Compiler and linker messages as file:line with the offending code, in the viewport's top-left corner
+fn show_shader_errors(ui: &mut egui::Ui, errors: &[ShaderError], rect: egui::Rect) {
+    let error_color = egui::Color32::from_rgb(255, 110, 110);
+    let mut overlay = ui.new_child(egui::UiBuilder::new().max_rect(rect.shrink(8.0)));
+    egui::Frame::new()
+        .fill(egui::Color32::from_black_alpha(200))
+        .corner_radius(4.0)
+        .inner_margin(8.0)
+        .show(&mut overlay, |ui| {
+            egui::ScrollArea::vertical().max_height(rect.height() * 0.5).show(ui, |ui| {
+                for error in errors {
+                    ui.label(egui::RichText::new(format!("⚠ {} {} failed - showing the last good version", error.program, error.stage.name()))
+                        .color(error_color).strong());
+                    for line in &error.lines {
+                        let location = match &line.location {
+                            Some((file, number)) => format!("{}:{}: ", file, number),
+                            None => String::new(),
+                        };
+                        ui.label(egui::RichText::new(format!("{}{}", location, line.message)).monospace().color(egui::Color32::WHITE));
+                        if let (Some(code), Some((_, number))) = (&line.code, &line.location) {
+                            ui.label(egui::RichText::new(format!("{:>5} | {}", number, code)).monospace().color(egui::Color32::GRAY));
+                        }
+                    }
+                }
+            });
+        });
+}
+
+/// Select whatever is drawn under `pointer`, or clear the selection when clicking empty space
+fn pick_object(ui: &egui::Ui, renderer: &Renderer, app_state: &mut AppState, camera: &Camera, rect: egui::Rect, pointer: egui::Pos2, rotation: f32) {
+    // egui points with Y down -> physical pixels with Y up, as the ID buffer stores them
+    let Some(target) = PickTarget::from_pointer(rect, pointer, ui.ctx().pixels_per_point()) else { return };
+
+    match renderer.pick(camera, rotation, target) {
+        Ok(Some(index)) => {
+            let name = renderer.object_name(index).unwrap_or_default().to_string();
+            app_state.status_text = format!("Selected {}", name);
+            app_state.selection = Some(Selection { index, name });
+        }
+        Ok(None) => app_state.selection = None,
+        Err(e) => {
+            eprintln!("Picking failed: {}", e);
+            app_state.status_text = format!("Picking failed: {}", e);
+        }
+    }
+}
+
+/// Orbit on left-drag (or fly with WASD and right-drag look), pan on middle-drag, zoom on scroll
+/// Returns the double-clicked point (0..1 within the rect) for the renderer to probe
+fn handle_camera_input(ui: &egui::Ui, response: &egui::Response, app_state: &mut AppState, index: usize) -> Option<egui::Vec2> {
+    // Clicking into the viewport gives it keyboard focus, so fly keys only apply while it is "active"
+    if response.clicked() || response.secondary_clicked() || response.drag_started() {
+        response.request_focus();
+    }
+    if response.has_focus() {
+        // Arrow keys are playback shortcuts - don't let egui use them to move focus away
+        ui.memory_mut(|memory| memory.set_focus_lock_filter(response.id, egui::EventFilter {
+            horizontal_arrows: true,
+            vertical_arrows: true,
+            ..Default::default()
+        }));
+    }
+    app_state.viewport_focused = response.has_focus();
+    if !app_state.viewport_focused {
+        app_state.fly_keys = FlyKeys::default();  // Key releases go elsewhere once focus is lost
+    }
+
+    let rect = response.rect;
+    let camera = &mut app_state.viewports[index].camera;
+    let dt = ui.input(|i| i.stable_dt).min(0.1);  // Clamp so a hitch doesn't fling the camera
+    let delta = response.drag_delta();
+    let delta = glm::vec2(delta.x, delta.y);
+
+    match camera.mode {
+        CameraMode::Orbit => {
+            if gizmo::is_dragging(ui.ctx()) {
+                camera.stop();  // The drag belongs to the gizmo
+            } else if response.dragged_by(egui::PointerButton::Primary) {
+                camera.orbit(delta, dt);
+            } else if response.dragged_by(egui::PointerButton::Middle) {
+                camera.pan(delta, rect.height(), dt);
+            } else {
+                camera.update(dt);  // Keep gliding after the button is released
+            }
+        }
+        CameraMode::Fly => {
+            if response.dragged_by(egui::PointerButton::Secondary) {
+                camera.look(delta);
+            } else if response.dragged_by(egui::PointerButton::Middle) {
+                camera.pan(delta, rect.height(), dt);
+            } else {
+                camera.update(dt);
+            }
+            let keys = app_state.fly_keys;
+            camera.fly(keys.direction(), keys.fast, dt);
+        }
+    }
+
+    // Cursor position within the viewport, 0..1 from the top-left
+    let cursor = response.hover_pos().map(|pos| (pos - rect.min) / rect.size());
+
+    if let Some(cursor) = cursor {
+        let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+        if scroll != 0.0 {
+            let ndc = glm::vec2(cursor.x * 2.0 - 1.0, 1.0 - cursor.y * 2.0);  // Flip Y so +1 is the top
+            camera.zoom(scroll, ndc, rect.aspect_ratio());
+        }
+    }
+
+    if camera.is_moving() || app_state.fly_keys.direction() != glm::Vec3::zeros() {
+        ui.ctx().request_repaint();  // Keep frames coming until the camera settles
+    }
+
+    // Double-click asks the renderer for the surface under the cursor
+    if response.double_clicked() { cursor } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_tile_the_panel_without_overlap() {
+        let panel = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(802.0, 602.0));
+        for layout in ViewportLayout::ALL {
+            let rects = split(layout, panel);
+            assert_eq!(rects.len(), layout.count());
+            for (i, a) in rects.iter().enumerate() {
+                assert!(panel.contains_rect(*a), "{:?}", layout);
+                assert!(rects[i + 1..].iter().all(|b| !a.intersects(b.shrink(0.5))), "{:?}", layout);
+            }
+        }
+        let quad = split(ViewportLayout::Quad, panel);
+        assert_eq!(quad[0].size(), egui::vec2(400.0, 300.0));
+        assert_eq!(quad[3].max, panel.max);
+    }
+}
@@ -0,0 +1,385 @@
+// Lights panel - live editing of the environment, the light list, shadows and the selected object's material and textures
+use crate::app::AppState;
+use crate::renderer::{
+    ColorSpace, Filter, Light, LightKind, Lighting, Material, MaterialTextures, PbrMaterial, Renderer, SamplerSettings,
+    ShadingModel, ShadowSettings, TextureSlot, Wrap, MAX_ANISOTROPY, MAX_CASCADES, MAX_LIGHTS,
+};
+use nalgebra_glm as glm;  // Light position/direction vectors
+
+/// Show the floating "Lights" window while `app_state.show_lights_panel` is set
+pub fn show_lights_panel(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer) {
+    if !app_state.show_lights_panel {
+        return;
+    }
+
+    // Edit copies and write back only on change, so paint callbacks don't force a clone every frame
+    let mut lighting = renderer.lighting().clone();
+    let selected = app_state.selection.as_ref()
+        .and_then(|selection| Some((selection.index, selection.name.clone(), renderer.object_material(selection.index)?)));
+    let mut material = selected.as_ref().map(|(_, _, material)| *material);
+    let textures = selected.as_ref().and_then(|(index, _, _)| renderer.object_textures(*index).cloned());
+    let mut texture_edit = None;
+
+    let mut open = true;  // egui sets this to false when the window's X is clicked
+    let mut clear_environment = false;
+    egui::Window::new("💡 Lights")
+        .open(&mut open)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if environment_ui(ui, app_state, renderer.environment_name(), &mut lighting) {
+                    clear_environment = true;
+                }
+                ui.separator();
+
+                lights_ui(ui, &mut lighting);
+
+                ui.separator();
+                shadows_ui(ui, &mut app_state.show_shadow_map, &mut lighting.shadows, renderer.shadow_error());
+
+                ui.separator();
+                match (&selected, &mut material) {
+                    (Some((_, name, _)), Some((shading, material, pbr))) => {
+                        ui.heading(format!("Material: {}", name));
+                        ui.horizontal(|ui| {
+                            ui.label("Shading");
+                            for model in ShadingModel::ALL {
+                                ui.selectable_value(shading, model, model.name());
+                            }
+                        });
+                        match shading {
+                            ShadingModel::BlinnPhong => material_ui(ui, material),
+                            ShadingModel::Pbr => pbr_material_ui(ui, pbr),
+                        }
+                        if let Some(textures) = &textures {
+                            texture_edit = textures_ui(ui, textures, *shading);
+                        }
+                    }
+                    _ => {
+                        ui.label("Select an object to edit its material");
+                    }
+                }
+            });
+        });
+
+    if clear_environment {
+        renderer.clear_environment();
+    }
+    if lighting != *renderer.lighting() {
+        *renderer.lighting_mut() = lighting;
+    }
+    if let (Some((index, _, _)), Some(edit)) = (&selected, texture_edit) {
+        match edit {
+            TextureEdit::Load(slot) => {
+                let picked = rfd::FileDialog::new()
+                    .add_filter("Image Files", &["png", "jpg", "jpeg", "bmp", "gif"])
+                    .set_title(format!("Open {} Texture", slot.name()))
+                    .pick_file();
+                if let Some(path) = picked {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    app_state.status_text = match renderer.load_texture(&path, Some(*index), slot) {
+                        Ok(summary) => format!("Loaded {}: {}", name, summary),
+                        Err(e) => format!("Failed to load {}: {}", name, e),
+                    };
+                }
+            }
+            TextureEdit::Sampler(slot, settings) => {
+                if let Err(e) = renderer.set_texture_sampler(*index, slot, settings) {
+                    app_state.status_text = format!("Failed to change sampler: {}", e);
+                }
+            }
+            TextureEdit::Clear(slot) => renderer.clear_texture(*index, slot),
+        }
+    }
+    if let (Some((index, _, before)), Some(material)) = (selected, material) {
+        if material != before {
+            let (shading, material, pbr) = material;
+            renderer.set_object_material(index, shading, material, pbr);
+        }
+    }
+    if !open {
+        app_state.show_lights_panel = false;
+    }
+}
+
+/// Change requested in the textures section
+enum TextureEdit {
+    Load(TextureSlot),
+    Sampler(TextureSlot, SamplerSettings),
+    Clear(TextureSlot),
+}
+
+/// HDR environment file and its exposure/rotation; returns true if Clear was clicked
+fn environment_ui(ui: &mut egui::Ui, app_state: &mut AppState, name: Option<&str>, lighting: &mut Lighting) -> bool {
+    let mut clear = false;
+    ui.horizontal(|ui| {
+        ui.label("Environment");
+        ui.strong(name.unwrap_or("None (flat ambient)"));
+    });
+    ui.horizontal(|ui| {
+        if ui.button("📁 Open .hdr…").clicked() {
+            app_state.open_file_dialog();  // load_file routes .hdr files here
+        }
+        if ui.add_enabled(name.is_some(), egui::Button::new("Clear")).clicked() {
+            clear = true;
+        }
+    });
+    if name.is_some() {
+        let settings = &mut lighting.environment;
+        ui.add(egui::Slider::new(&mut settings.exposure, -5.0..=5.0).text("Exposure (EV)"));
+        ui.add(egui::Slider::new(&mut settings.rotation, 0.0..=360.0).suffix("°").text("Rotation"));
+        ui.checkbox(&mut settings.show_skybox, "Show skybox");
+        ui.label("Image-based lighting applies to PBR objects");
+    }
+    clear
+}
+
+/// Ambient color plus one collapsible section per light
+fn lights_ui(ui: &mut egui::Ui, lighting: &mut Lighting) {
+    ui.horizontal(|ui| {
+        ui.label("Ambient");
+        ui.color_edit_button_rgb(&mut lighting.ambient);
+    });
+
+    let mut remove = None;
+    for (i, light) in lighting.lights.iter_mut().enumerate() {
+        let title = format!("{} {}", light.kind.name(), i + 1);
+        egui::CollapsingHeader::new(title).id_salt(("light", i)).default_open(true).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut light.enabled, "On");
+                egui::ComboBox::from_id_salt(("light_kind", i))
+                    .selected_text(light.kind.name())
+                    .show_ui(ui, |ui| {
+                        for kind in LightKind::ALL {
+                            ui.selectable_value(&mut light.kind, kind, kind.name());
+                        }
+                    });
+                if ui.button("🗑").on_hover_text("Remove light").clicked() {
+                    remove = Some(i);
+                }
+            });
+            light_ui(ui, i, light);
+        });
+    }
+    if let Some(i) = remove {
+        lighting.lights.remove(i);
+    }
+
+    // The shader has a fixed number of slots
+    let room = lighting.lights.len() < MAX_LIGHTS;
+    ui.horizontal(|ui| {
+        for kind in LightKind::ALL {
+            if ui.add_enabled(room, egui::Button::new(format!("+ {}", kind.name()))).clicked() {
+                lighting.lights.push(Light::new(kind));
+            }
+        }
+    });
+}
+
+/// Fields of one light - only those its kind uses
+fn light_ui(ui: &mut egui::Ui, id: usize, light: &mut Light) {
+    egui::Grid::new(("light_params", id)).num_columns(2).show(ui, |ui| {
+        ui.label("Color");
+        ui.color_edit_button_rgb(&mut light.color);
+        ui.end_row();
+
+        ui.label("Intensity");
+        ui.add(egui::Slider::new(&mut light.intensity, 0.0..=10.0).logarithmic(true));
+        ui.end_row();
+
+        if light.kind != LightKind::Directional {
+            ui.label("Position");
+            vec3_ui(ui, &mut light.position);
+            ui.end_row();
+
+            ui.label("Range");
+            ui.add(egui::Slider::new(&mut light.range, 0.1..=100.0).logarithmic(true));
+            ui.end_row();
+        }
+        if light.kind != LightKind::Point {
+            ui.label("Direction");
+            vec3_ui(ui, &mut light.direction);
+            ui.end_row();
+        }
+        if light.kind == LightKind::Spot {
+            ui.label("Inner angle");
+            ui.add(egui::Slider::new(&mut light.inner_angle, 0.0..=90.0).suffix("°"));
+            ui.end_row();
+
+            ui.label("Outer angle");
+            ui.add(egui::Slider::new(&mut light.outer_angle, 0.0..=90.0).suffix("°"));
+            ui.end_row();
+        }
+    });
+}
+
+/// Shadow map quality, cascades and the debug window toggle
+fn shadows_ui(ui: &mut egui::Ui, show_map: &mut bool, settings: &mut ShadowSettings, error: Option<String>) {
+    egui::CollapsingHeader::new("Shadows").default_open(true).show(ui, |ui| {
+        ui.checkbox(&mut settings.enabled, "Cast shadows from the first directional light");
+        if let (true, Some(error)) = (settings.enabled, error) {
+            ui.colored_label(egui::Color32::from_rgb(255, 110, 110), format!("Unavailable: {} - try a lower resolution", error));
+        }
+        ui.add_enabled_ui(settings.enabled, |ui| {
+            egui::Grid::new("shadow_params").num_columns(2).show(ui, |ui| {
+                ui.label("Resolution");
+                egui::ComboBox::from_id_salt("shadow_resolution")
+                    .selected_text(settings.resolution.to_string())
+                    .show_ui(ui, |ui| {
+                        for resolution in [512, 1024, 2048, 4096] {
+                            ui.selectable_value(&mut settings.resolution, resolution, resolution.to_string());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Depth bias");
+                ui.add(egui::Slider::new(&mut settings.bias, 0.0..=0.02).logarithmic(true));
+                ui.end_row();
+
+                ui.label("Normal bias");
+                ui.add(egui::Slider::new(&mut settings.normal_bias, 0.0..=5.0).suffix(" texels"));
+                ui.end_row();
+
+                ui.label("PCF radius");
+                ui.add(egui::Slider::new(&mut settings.pcf_radius, 0..=3));
+                ui.end_row();
+
+                ui.label("Cascades");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut settings.cascaded, "");
+                    ui.add_enabled(settings.cascaded, egui::Slider::new(&mut settings.cascades, 2..=MAX_CASCADES));
+                });
+                ui.end_row();
+
+                if settings.cascaded {
+                    ui.label("Split blend");
+                    ui.add(egui::Slider::new(&mut settings.split_lambda, 0.0..=1.0))
+                        .on_hover_text("0 = even splits, 1 = logarithmic (sharper near the camera)");
+                    ui.end_row();
+                }
+            });
+            ui.checkbox(show_map, "Show shadow map");
+        });
+    });
+}
+
+/// Diffuse/specular/shininess/emissive editors
+fn material_ui(ui: &mut egui::Ui, material: &mut Material) {
+    egui::Grid::new("material_params").num_columns(2).show(ui, |ui| {
+        ui.label("Diffuse");
+        ui.color_edit_button_rgb(&mut material.diffuse);
+        ui.end_row();
+
+        ui.label("Specular");
+        ui.color_edit_button_rgb(&mut material.specular);
+        ui.end_row();
+
+        ui.label("Shininess");
+        ui.add(egui::Slider::new(&mut material.shininess, 1.0..=512.0).logarithmic(true));
+        ui.end_row();
+
+        ui.label("Emissive");
+        ui.color_edit_button_rgb(&mut material.emissive);
+        ui.end_row();
+    });
+    if ui.button("Reset material").clicked() {
+        *material = Material::default();
+    }
+}
+
+/// Metallic-roughness editors
+fn pbr_material_ui(ui: &mut egui::Ui, material: &mut PbrMaterial) {
+    egui::Grid::new("pbr_params").num_columns(2).show(ui, |ui| {
+        ui.label("Base color");
+        ui.color_edit_button_rgb(&mut material.base_color);
+        ui.end_row();
+
+        ui.label("Metallic");
+        ui.add(egui::Slider::new(&mut material.metallic, 0.0..=1.0));
+        ui.end_row();
+
+        ui.label("Roughness");
+        ui.add(egui::Slider::new(&mut material.roughness, 0.0..=1.0));
+        ui.end_row();
+
+        ui.label("Occlusion");
+        ui.add(egui::Slider::new(&mut material.occlusion, 0.0..=1.0));
+        ui.end_row();
+
+        ui.label("Emissive");
+        ui.color_edit_button_rgb(&mut material.emissive);
+        ui.end_row();
+    });
+    if ui.button("Reset material").clicked() {
+        *material = PbrMaterial::default();
+    }
+}
+
+/// Texture per material slot with its sampler settings (Blinn-Phong only samples base color)
+fn textures_ui(ui: &mut egui::Ui, textures: &MaterialTextures, shading: ShadingModel) -> Option<TextureEdit> {
+    let mut edit = None;
+    egui::CollapsingHeader::new("Textures").default_open(true).show(ui, |ui| {
+        let slots = TextureSlot::ALL.into_iter().filter(|slot| shading == ShadingModel::Pbr || slot.used_by_blinn_phong());
+        for slot in slots {
+            let Some(binding) = textures.get(slot) else {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", slot.name()));
+                    ui.weak("none");
+                    if ui.button("📁").on_hover_text("Load an image into this slot").clicked() {
+                        edit = Some(TextureEdit::Load(slot));
+                    }
+                });
+                continue;
+            };
+            let texture = &binding.texture;
+            let space = match texture.color_space {
+                ColorSpace::Srgb => "sRGB",
+                ColorSpace::Linear => "linear",
+            };
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", slot.name()));
+                ui.strong(&texture.name);
+                ui.weak(format!("{}×{} {}", texture.width, texture.height, space));
+                if ui.button("📁").on_hover_text("Replace with another image").clicked() {
+                    edit = Some(TextureEdit::Load(slot));
+                }
+                if ui.button("🗑").on_hover_text("Remove texture").clicked() {
+                    edit = Some(TextureEdit::Clear(slot));
+                }
+            });
+
+            let mut settings = binding.sampler.settings;
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt(("texture_wrap", slot))
+                    .selected_text(settings.wrap.name())
+                    .show_ui(ui, |ui| {
+                        for wrap in Wrap::ALL {
+                            ui.selectable_value(&mut settings.wrap, wrap, wrap.name());
+                        }
+                    });
+                egui::ComboBox::from_id_salt(("texture_filter", slot))
+                    .selected_text(settings.filter.name())
+                    .show_ui(ui, |ui| {
+                        for filter in Filter::ALL {
+                            ui.selectable_value(&mut settings.filter, filter, filter.name());
+                        }
+                    });
+                ui.add(egui::Slider::new(&mut settings.anisotropy, 1..=MAX_ANISOTROPY).text("aniso"));
+            });
+            if settings != binding.sampler.settings {
+                edit = Some(TextureEdit::Sampler(slot, settings));
+            }
+        }
+        ui.weak("File ▸ Open on an image sets the base color of the selection");
+    });
+    edit
+}
+
+/// Three drag values side by side
+fn vec3_ui(ui: &mut egui::Ui, value: &mut glm::Vec3) {
+    ui.horizontal(|ui| {
+        for axis in 0..3 {
+            ui.add(egui::DragValue::new(&mut value[axis]).speed(0.05).max_decimals(2));
+        }
+    });
+}
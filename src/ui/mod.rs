@@ -1,12 +1,19 @@
-// Module declaration - include gl_viewport.rs as a submodule
+// Module declarations - include gl_viewport.rs, gizmo.rs and the panels as submodules
+mod gizmo;
 mod gl_viewport;
+mod lights_panel;
+mod post_panel;
+mod primitive_panel;
+mod shader_inspector;
+mod shadow_view;
 
 // Import types from our crate (crate = current package)
-use crate::app::AppState;
-use crate::renderer::Renderer;
+use crate::app::{AppState, GizmoMode, GizmoSpace, ViewportLayout};
+use crate::renderer::{Antialiasing, CameraMode, Primitive, Projection, Renderer, TextureSlot, ViewPreset, ViewShading};
 
 /// Main UI rendering function - called once per frame to build the entire UI
-pub fn show_ui(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer, _window_width: u32, _window_height: u32) {
+/// `painter` is egui's GL painter, for showing the renderer's offscreen texture
+pub fn show_ui(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer, painter: &mut egui_glow::Painter, _window_width: u32, _window_height: u32) {
     // _ prefix means "unused parameter" - we don't use window dimensions here
     
     // Update mouse position from egui context
@@ -14,17 +21,28 @@ pub fn show_ui(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Ren
         app_state.update_mouse(pos.x, pos.y);     // Extract x, y from Pos2
     }
 
+    // Pick up saved shader edits before anything queues a draw with the old programs
+    if let Some(status) = renderer.reload_changed_shaders() {
+        app_state.status_text = status;
+    }
+
     // Build UI components in order (top to bottom)
-    show_menu(ctx, app_state);       // File/Help menu at top
+    show_menu(ctx, app_state, renderer);  // File/Help menu at top
     show_toolbar(ctx, app_state);    // Play/Step/Reset buttons below menu
+    renderer.set_normal_mapping(app_state.normal_mapping);  // Toolbar toggle, applied before the viewport draws
     
     // Central panel with OpenGL viewport - takes remaining space
     egui::CentralPanel::default().show(ctx, |ui| {  // .show() takes a closure for UI building
         let available_rect = ui.available_rect_before_wrap();  // Get remaining space
-        gl_viewport::show_viewport(ui, renderer, app_state, available_rect);  // Render 3D cube here
+        gl_viewport::show_viewports(ui, renderer, painter, app_state, available_rect);  // Render 3D scene here
     });
 
     show_statusbar(ctx, app_state);  // Status info at bottom
+    primitive_panel::show_primitive_panel(ctx, app_state, renderer);  // Floating window, if open
+    lights_panel::show_lights_panel(ctx, app_state, renderer);        // Same
+    shadow_view::show_shadow_view(ctx, app_state, renderer);          // Same
+    shader_inspector::show_shader_inspector(ctx, app_state, renderer);  // Same
+    post_panel::show_post_panel(ctx, app_state, renderer);            // Same
 
     // Handle animation updates
     if app_state.playing {  // Only update if animation is playing
@@ -49,6 +67,85 @@ fn show_toolbar(ctx: &egui::Context, app_state: &mut AppState) {
 
             ui.separator();  // Visual separator line
 
+            // Camera mode toggle - shows the current mode, click to switch
+            let mode_text = match app_state.camera().mode {
+                CameraMode::Orbit => "🔄 Orbit",
+                CameraMode::Fly => "✈ Fly",
+            };
+            if ui.button(mode_text).on_hover_text("Switch between orbit and fly camera").clicked() {
+                app_state.toggle_camera_mode();
+            }
+
+            // Projection toggle - orthographic for true CAD-style views
+            let projection_text = match app_state.camera().projection {
+                Projection::Perspective => "🔭 Perspective",
+                Projection::Orthographic => "📐 Orthographic",
+            };
+            if ui.button(projection_text).on_hover_text("Toggle perspective/orthographic (Numpad 5)").clicked() {
+                app_state.toggle_projection();
+            }
+
+            // Canonical view presets
+            ui.menu_button("🧭 Views", |ui| {
+                for preset in ViewPreset::ALL {
+                    if ui.button(preset.name()).clicked() {
+                        app_state.set_view(preset);
+                        ui.close();
+                    }
+                }
+            });
+
+            // How the active viewport draws every object - the diagnostic modes help with broken imports
+            let active = app_state.active_viewport;
+            let mut shading = app_state.viewports[active].shading;
+            egui::ComboBox::from_id_salt("toolbar_shading")
+                .selected_text(format!("🎨 {}", shading.name()))
+                .show_ui(ui, |ui| {
+                    for option in ViewShading::ALL {
+                        ui.selectable_value(&mut shading, option, option.name());
+                    }
+                })
+                .response
+                .on_hover_text("Viewport shading: materials, wireframe overlay, flat, normals, depth, UV checker or matcap");
+            if shading != app_state.viewports[active].shading {
+                app_state.viewports[active].shading = shading;
+                app_state.status_text = format!("{} shading", shading.name());
+            }
+
+            ui.separator();
+
+            // Gizmo mode, space and snapping for the selected object
+            let gizmo = &mut app_state.gizmo;
+            ui.selectable_value(&mut gizmo.mode, GizmoMode::Translate, "✥ Move");
+            ui.selectable_value(&mut gizmo.mode, GizmoMode::Rotate, "⟲ Rotate");
+            ui.selectable_value(&mut gizmo.mode, GizmoMode::Scale, "⤢ Scale");
+            let space_text = match gizmo.space {
+                GizmoSpace::World => "🌐 World",
+                GizmoSpace::Local => "📦 Local",
+            };
+            if ui.button(space_text).on_hover_text("Gizmo axes: world or object (scale always uses object axes)").clicked() {
+                gizmo.space = match gizmo.space {
+                    GizmoSpace::World => GizmoSpace::Local,
+                    GizmoSpace::Local => GizmoSpace::World,
+                };
+            }
+            ui.checkbox(&mut gizmo.snap, "Snap");
+            if gizmo.snap {
+                // Only the step for the current mode, to keep the toolbar short
+                match gizmo.mode {
+                    GizmoMode::Translate => ui.add(egui::DragValue::new(&mut gizmo.translate_step).speed(0.01).range(0.001..=100.0).suffix(" u")),
+                    GizmoMode::Rotate => ui.add(egui::DragValue::new(&mut gizmo.rotate_step).speed(0.5).range(0.1..=180.0).suffix("°")),
+                    GizmoMode::Scale => ui.add(egui::DragValue::new(&mut gizmo.scale_step).speed(0.01).range(0.001..=10.0).prefix("×")),
+                };
+            }
+
+            ui.separator();
+
+            ui.toggle_value(&mut app_state.show_lights_panel, "💡 Lights").on_hover_text("Edit lights and the selected object's material");
+            ui.toggle_value(&mut app_state.normal_mapping, "🗺 Normal maps").on_hover_text("Show surfaces with or without their normal maps (N)");
+
+            ui.separator();
+
             // Dynamic play/pause button text based on state
             let play_text = if app_state.playing { "⏸ Pause" } else { "▶ Play" };
             if ui.button(play_text).clicked() {
@@ -87,7 +184,7 @@ fn show_statusbar(ctx: &egui::Context, app_state: &AppState) {
 }  // End of show_statusbar function
 
 /// Display the menu bar with File and Help menus
-fn show_menu(ctx: &egui::Context, app_state: &mut AppState) {
+fn show_menu(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer) {
     // Handle file dialog if the flag is set (checked once per frame)
     if app_state.file_open_dialog {
         app_state.file_open_dialog = false;  // Reset flag immediately
@@ -95,6 +192,8 @@ fn show_menu(ctx: &egui::Context, app_state: &mut AppState) {
         // rfd = Rust File Dialog - native OS file picker
         if let Some(path) = rfd::FileDialog::new()  // Builder pattern for dialog config
             .add_filter("All Files", &["*"])                                    // File type filters
+            .add_filter("3D Models", &["obj", "gltf", "glb"])
+            .add_filter("HDR Environments", &["hdr"])
             .add_filter("Text Files", &["txt"])                                 // &["..."] = slice of string literals
             .add_filter("Data Files", &["json", "csv", "xml"])
             .add_filter("Image Files", &["png", "jpg", "jpeg", "bmp", "gif"])
@@ -102,7 +201,8 @@ fn show_menu(ctx: &egui::Context, app_state: &mut AppState) {
             .pick_file()                                                        // Show dialog and return Option<PathBuf>
         {
             // User selected a file - the Some(path) case
-            app_state.set_current_file(path);  // Store the selected file
+            app_state.set_current_file(path.clone());  // Store the selected file
+            load_file(app_state, renderer, &path);     // Show it in the viewport if it's a model
         } else {
             // User cancelled dialog - the None case
             app_state.status_text = "File open cancelled".to_string();
@@ -126,6 +226,75 @@ fn show_menu(ctx: &egui::Context, app_state: &mut AppState) {
                 }
             });
 
+            // Add Primitive menu - one entry per procedural shape
+            ui.menu_button("Add Primitive", |ui| {
+                for primitive in Primitive::defaults() {
+                    if ui.button(primitive.name()).clicked() {
+                        match renderer.add_primitive(primitive) {
+                            Ok(index) => {
+                                app_state.editing_primitive = Some(index);  // Open its parameter panel
+                                app_state.status_text = format!("Added {}", primitive.name());
+                            }
+                            Err(e) => app_state.status_text = format!("Failed to add {}: {}", primitive.name(), e),
+                        }
+                        ui.close();
+                    }
+                }
+            });
+
+            // View menu - camera controls and sensitivity
+            ui.menu_button("View", |ui| {
+                if ui.button("Frame All (F)").clicked() {
+                    app_state.frame_all();
+                    ui.close();
+                }
+                if ui.button("Reset Camera").clicked() {
+                    app_state.camera_mut().reset_view();
+                    ui.close();
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Layout:");
+                    for layout in ViewportLayout::ALL {
+                        if ui.selectable_label(app_state.layout == layout, layout.name()).clicked() {
+                            app_state.set_layout(layout);
+                        }
+                    }
+                });
+                ui.menu_button("Anti-aliasing", |ui| {
+                    let current = renderer.antialiasing();
+                    for option in Antialiasing::ALL {
+                        if ui.radio(current == option, option.name()).clicked() {
+                            renderer.set_antialiasing(option);  // Takes effect next frame, no restart
+                            app_state.status_text = format!("Anti-aliasing: {}", option.name());
+                        }
+                    }
+                });
+                ui.checkbox(&mut app_state.show_lights_panel, "Lights Panel");
+                ui.checkbox(&mut app_state.show_shader_inspector, "Shader Parameters");
+                ui.checkbox(&mut app_state.show_post_panel, "Post Processing");
+                if ui.button("Reload Shaders").on_hover_text("Rebuild from the shaders folder (saved edits reload on their own)").clicked() {
+                    app_state.status_text = renderer.reload_shaders();
+                    ui.close();
+                }
+
+                let mut fly = app_state.camera().mode == CameraMode::Fly;
+                if ui.checkbox(&mut fly, "Fly Mode").changed() {
+                    app_state.toggle_camera_mode();
+                }
+
+                ui.separator();
+                ui.label("Orbit: left-drag orbit | Middle-drag: pan | Scroll: zoom | Double-click: set pivot");
+                ui.label("Fly: click the viewport, then WASD move, Q/E down/up, Shift faster, right-drag look");
+                ui.label("Numpad: 1 front, 3 right, 7 top (Ctrl = opposite side), 0 isometric, 5 projection");
+                let camera = app_state.camera_mut();
+                ui.add(egui::Slider::new(&mut camera.orbit_sensitivity, 0.001..=0.05).logarithmic(true).text("Orbit speed"));
+                ui.add(egui::Slider::new(&mut camera.pan_sensitivity, 0.1..=5.0).logarithmic(true).text("Pan speed"));
+                ui.add(egui::Slider::new(&mut camera.zoom_sensitivity, 0.0002..=0.02).logarithmic(true).text("Zoom speed"));
+                ui.add(egui::Slider::new(&mut camera.inertia, 0.0..=1.0).text("Inertia (s)"));
+                ui.add(egui::Slider::new(&mut camera.fly_speed, 0.1..=50.0).logarithmic(true).text("Fly speed"));
+            });
+
             // Help menu dropdown
             ui.menu_button("Help", |ui| {
                 if ui.button("About").clicked() {
@@ -135,4 +304,57 @@ fn show_menu(ctx: &egui::Context, app_state: &mut AppState) {
             });
         });  // End of menu bar
     });  // End of top panel
-}  // End of show_menu function
\ No newline at end of file
+}  // End of show_menu function
+
+/// Load a model file into the renderer, reporting success or failure in the status bar
+fn load_file(app_state: &mut AppState, renderer: &mut Renderer, path: &std::path::Path) {
+    // Pick a loader by file extension (case-insensitive)
+    let extension = path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    // An environment map only changes the lighting - the scene and selection stay
+    if extension == "hdr" {
+        app_state.status_text = match renderer.load_environment(path) {
+            Ok(summary) => format!("Loaded {}: {}", name, summary),
+            Err(e) => {
+                eprintln!("Failed to load {}: {}", path.display(), e);
+                format!("Failed to load {}: {}", name, e)
+            }
+        };
+        return;
+    }
+
+    // An image textures the selection (or everything when nothing is selected)
+    if matches!(extension.as_str(), "png" | "jpg" | "jpeg" | "bmp" | "gif") {
+        let target = app_state.selection.as_ref().map(|selection| selection.index);
+        app_state.status_text = match renderer.load_texture(path, target, TextureSlot::BaseColor) {
+            Ok(summary) => format!("Loaded {}: {}", name, summary),
+            Err(e) => {
+                eprintln!("Failed to load {}: {}", path.display(), e);
+                format!("Failed to load {}: {}", name, e)
+            }
+        };
+        return;
+    }
+
+    let result = match extension.as_str() {
+        "obj" => renderer.load_obj(path),
+        "gltf" | "glb" => renderer.load_gltf(path),
+        _ => return,  // Not a model - keep the "Opened" status from set_current_file
+    };
+
+    match result {
+        Ok(summary) => {
+            app_state.editing_primitive = None;  // Object indices refer to the old scene
+            app_state.selection = None;
+            app_state.frame_all_quietly();       // Models come in any scale - bring it into view, keep the summary
+            app_state.status_text = format!("Loaded {}: {}", name, summary);
+        }
+        Err(e) => {
+            eprintln!("Failed to load {}: {}", path.display(), e);
+            app_state.status_text = format!("Failed to load {}: {}", name, e);
+        }
+    }
+}
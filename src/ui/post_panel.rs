@@ -0,0 +1,68 @@
+// Post-processing panel - one section per pass of the viewport's color pipeline, in chain order
+use crate::app::AppState;
+use crate::renderer::{PostSettings, Renderer, ToneMapOperator};
+
+/// Show the "Post Processing" window while `app_state.show_post_panel` is set
+pub fn show_post_panel(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer) {
+    if !app_state.show_post_panel {
+        return;
+    }
+
+    // Edit a copy and write back only on change
+    let mut settings = *renderer.post_settings();
+    let mut open = true;
+    egui::Window::new("🎬 Post Processing")
+        .open(&mut open)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            ui.weak("Runs top to bottom on the linear HDR scene");
+
+            pass_ui(ui, "1. Exposure", &mut settings.exposure.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut settings.exposure.stops, -5.0..=5.0).suffix(" EV").text("Exposure"));
+            });
+            pass_ui(ui, "2. Bloom", &mut settings.bloom.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut settings.bloom.threshold, 0.0..=5.0).text("Threshold"))
+                    .on_hover_text("Brightness where glow starts - 1 is the brightest a surface shows without tone mapping");
+                ui.add(egui::Slider::new(&mut settings.bloom.intensity, 0.0..=2.0).text("Intensity"));
+                ui.add(egui::Slider::new(&mut settings.bloom.radius, 1..=8).text("Radius"))
+                    .on_hover_text("Blur passes - more spreads the glow further");
+            });
+            pass_ui(ui, "3. Tone Mapping", &mut settings.tone_mapping.enabled, |ui| {
+                ui.horizontal(|ui| {
+                    for operator in ToneMapOperator::ALL {
+                        ui.selectable_value(&mut settings.tone_mapping.operator, operator, operator.name());
+                    }
+                });
+            });
+            pass_ui(ui, "4. Vignette", &mut settings.vignette.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut settings.vignette.strength, 0.0..=1.0).text("Strength"));
+                ui.add(egui::Slider::new(&mut settings.vignette.radius, 0.0..=1.0).text("Radius"));
+                ui.add(egui::Slider::new(&mut settings.vignette.softness, 0.01..=1.0).text("Softness"));
+            });
+            pass_ui(ui, "5. Gamma", &mut settings.gamma.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut settings.gamma.gamma, 1.0..=3.0).text("Gamma"))
+                    .on_hover_text("2.2 approximates sRGB displays");
+            });
+
+            ui.separator();
+            if ui.button("Reset").clicked() {
+                settings = PostSettings::default();
+            }
+        });
+
+    if settings != *renderer.post_settings() {
+        *renderer.post_settings_mut() = settings;
+    }
+    if !open {
+        app_state.show_post_panel = false;
+    }
+}
+
+/// A pass's enable checkbox with its parameters underneath, greyed out while it is off
+fn pass_ui(ui: &mut egui::Ui, title: &str, enabled: &mut bool, parameters: impl FnOnce(&mut egui::Ui)) {
+    ui.separator();
+    ui.checkbox(enabled, egui::RichText::new(title).strong());
+    ui.add_enabled_ui(*enabled, |ui| {
+        ui.indent(title, parameters);
+    });
+}
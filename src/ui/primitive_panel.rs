@@ -0,0 +1,82 @@
+// Parameter panel for procedural primitives - edits regenerate the mesh immediately
+use crate::app::AppState;
+use crate::renderer::{Primitive, Renderer};
+
+/// Show the floating "Primitive" window for the object in `app_state.editing_primitive`
+pub fn show_primitive_panel(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer) {
+    let Some(index) = app_state.editing_primitive else { return };  // Panel closed
+    let Some(mut primitive) = renderer.primitive(index) else {
+        app_state.editing_primitive = None;  // Object was replaced by a file load
+        return;
+    };
+
+    let mut open = true;  // egui sets this to false when the window's X is clicked
+    let mut changed = false;
+    egui::Window::new(format!("{} Parameters", primitive.name()))
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            // Grid lines up labels and sliders in two columns
+            egui::Grid::new("primitive_params").num_columns(2).show(ui, |ui| {
+                // Each slider reports whether it moved; |= keeps any earlier change
+                match &mut primitive {
+                    Primitive::UvSphere { radius, segments, rings } => {
+                        changed |= size_slider(ui, "Radius", radius);
+                        changed |= count_slider(ui, "Segments", segments, 3..=128);
+                        changed |= count_slider(ui, "Rings", rings, 2..=64);
+                    }
+                    Primitive::IcoSphere { radius, subdivisions } => {
+                        changed |= size_slider(ui, "Radius", radius);
+                        changed |= count_slider(ui, "Subdivisions", subdivisions, 0..=6);
+                    }
+                    Primitive::Torus { major_radius, minor_radius, major_segments, minor_segments } => {
+                        changed |= size_slider(ui, "Major radius", major_radius);
+                        changed |= size_slider(ui, "Minor radius", minor_radius);
+                        changed |= count_slider(ui, "Major segments", major_segments, 3..=128);
+                        changed |= count_slider(ui, "Minor segments", minor_segments, 3..=64);
+                    }
+                    Primitive::Cylinder { radius, height, segments } | Primitive::Cone { radius, height, segments } => {
+                        changed |= size_slider(ui, "Radius", radius);
+                        changed |= size_slider(ui, "Height", height);
+                        changed |= count_slider(ui, "Segments", segments, 3..=128);
+                    }
+                    Primitive::Plane { width, depth, subdivisions } => {
+                        changed |= size_slider(ui, "Width", width);
+                        changed |= size_slider(ui, "Depth", depth);
+                        changed |= count_slider(ui, "Subdivisions", subdivisions, 1..=64);
+                    }
+                    Primitive::Capsule { radius, height, segments, rings } => {
+                        changed |= size_slider(ui, "Radius", radius);
+                        changed |= size_slider(ui, "Height", height);
+                        changed |= count_slider(ui, "Segments", segments, 3..=128);
+                        changed |= count_slider(ui, "Rings", rings, 1..=32);
+                    }
+                }
+            });
+        });
+
+    if changed {
+        if let Err(e) = renderer.update_primitive(index, primitive) {
+            app_state.status_text = format!("Failed to update {}: {}", primitive.name(), e);
+        }
+    }
+    if !open {
+        app_state.editing_primitive = None;
+    }
+}
+
+/// Labeled slider for a length, returns true if the value changed
+fn size_slider(ui: &mut egui::Ui, label: &str, value: &mut f32) -> bool {
+    ui.label(label);
+    let changed = ui.add(egui::Slider::new(value, 0.01..=5.0)).changed();
+    ui.end_row();
+    changed
+}
+
+/// Labeled slider for a segment/subdivision count
+fn count_slider(ui: &mut egui::Ui, label: &str, value: &mut u32, range: std::ops::RangeInclusive<u32>) -> bool {
+    ui.label(label);
+    let changed = ui.add(egui::Slider::new(value, range)).changed();
+    ui.end_row();
+    changed
+}
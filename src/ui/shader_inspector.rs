@@ -0,0 +1,134 @@
+// Shader parameter inspector - widgets generated from the reflected uniforms of the lit programs
+use crate::app::AppState;
+use crate::renderer::{Renderer, ShadingModel, UniformInfo, UniformKind};
+
+/// Show the "Shader Parameters" window while `app_state.show_shader_inspector` is set
+pub fn show_shader_inspector(ctx: &egui::Context, app_state: &mut AppState, renderer: &mut Renderer) {
+    if !app_state.show_shader_inspector {
+        return;
+    }
+
+    let shading = app_state.inspector_shading;
+    let uniforms = renderer.shader_uniforms(shading);
+    let mut edits: Vec<(String, Vec<f32>)> = Vec::new();
+    let mut reset = false;
+    let mut open = true;
+    egui::Window::new("🎛 Shader Parameters")
+        .open(&mut open)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            egui::ComboBox::from_label("Program")
+                .selected_text(shading.name())
+                .show_ui(ui, |ui| {
+                    for option in ShadingModel::ALL {
+                        ui.selectable_value(&mut app_state.inspector_shading, option, option.name());
+                    }
+                });
+            if uniforms.is_empty() {
+                ui.weak("Not built yet - add an object that uses this shading model");
+                return;
+            }
+
+            let (parameters, app_driven): (Vec<&UniformInfo>, Vec<&UniformInfo>) = uniforms.iter().partition(|info| info.is_parameter());
+            ui.separator();
+            if parameters.is_empty() {
+                ui.weak("No free parameters - declare a float, vec or bool uniform in the shader to tweak it here");
+            }
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("shader_parameters").num_columns(3).striped(true).show(ui, |ui| {
+                    for info in &parameters {
+                        ui.label(&info.name);
+                        ui.weak(type_name(info));
+                        let values = renderer.shader_parameter(shading, info);
+                        let mut edited = values.clone();
+                        ui.vertical(|ui| {
+                            for element in edited.chunks_mut(info.kind.components()) {
+                                value_ui(ui, info, element);
+                            }
+                        });
+                        ui.end_row();
+                        if edited != values {
+                            edits.push((info.name.clone(), edited));
+                        }
+                    }
+                });
+
+                // Everything the renderer sets itself, for reference
+                ui.collapsing(format!("Set by the app ({})", app_driven.len()), |ui| {
+                    egui::Grid::new("app_uniforms").num_columns(2).striped(true).show(ui, |ui| {
+                        for info in &app_driven {
+                            ui.label(&info.name);
+                            ui.weak(type_name(info));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+            ui.separator();
+            if ui.button("Reset to shader defaults").on_hover_text("Forget the values set here and use the uniforms' initializers").clicked() {
+                reset = true;
+            }
+        });
+
+    for (name, values) in edits {
+        renderer.set_shader_parameter(shading, &name, values);
+    }
+    if reset {
+        app_state.status_text = renderer.reset_shader_parameters(shading);
+    }
+    if !open {
+        app_state.show_shader_inspector = false;
+    }
+}
+
+/// GLSL type with the array length, e.g. "vec3[4]"
+fn type_name(info: &UniformInfo) -> String {
+    if info.size > 1 { format!("{}[{}]", info.kind.name(), info.size) } else { info.kind.name().to_string() }
+}
+
+/// Widget for one value (one array element): checkbox, slider, color picker or drag fields
+fn value_ui(ui: &mut egui::Ui, info: &UniformInfo, values: &mut [f32]) {
+    // vec3/vec4 named like a color get a picker, other vectors a field per component
+    let is_color = ["color", "colour", "tint", "albedo"].iter().any(|hint| info.name.to_lowercase().contains(hint));
+    match info.kind {
+        UniformKind::Bool => {
+            let mut checked = values[0] != 0.0;
+            if ui.checkbox(&mut checked, "").changed() {
+                values[0] = if checked { 1.0 } else { 0.0 };
+            }
+        }
+        UniformKind::Int => {
+            let mut value = values[0].round() as i32;
+            if ui.add(egui::DragValue::new(&mut value)).changed() {
+                values[0] = value as f32;
+            }
+        }
+        UniformKind::Float => {
+            // Range from the value's magnitude - typing outside it still works
+            let magnitude = values[0].abs();
+            let max = if magnitude <= 1.0 { 1.0 } else { 10f32.powf(magnitude.log10().ceil()) };
+            let min = if values[0] < 0.0 { -max } else { 0.0 };
+            ui.add(egui::Slider::new(&mut values[0], min..=max).clamping(egui::SliderClamping::Never));
+        }
+        UniformKind::Vec3 if is_color => {
+            let mut color = [values[0], values[1], values[2]];
+            if ui.color_edit_button_rgb(&mut color).changed() {
+                values.copy_from_slice(&color);
+            }
+        }
+        UniformKind::Vec4 if is_color => {
+            let mut color = [values[0], values[1], values[2], values[3]];
+            if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                values.copy_from_slice(&color);
+            }
+        }
+        _ => {
+            ui.horizontal(|ui| {
+                for (value, axis) in values.iter_mut().zip(["x", "y", "z", "w"]) {
+                    ui.add(egui::DragValue::new(value).speed(0.01).prefix(format!("{}: ", axis)));
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Shadow map debug window - each cascade's depth as seen from the light
+use crate::app::AppState;
+use crate::renderer::Renderer;
+use std::sync::Arc;  // Paint callbacks are shared with egui
+
+/// Size of each cascade's thumbnail in points
+const THUMBNAIL_SIZE: f32 = 256.0;
+
+/// Show the "Shadow Map" window while `app_state.show_shadow_map` is set
+pub fn show_shadow_view(ctx: &egui::Context, app_state: &mut AppState, renderer: &Renderer) {
+    if !app_state.show_shadow_map {
+        return;
+    }
+
+    let settings = renderer.lighting().shadows;
+    let mut open = true;
+    egui::Window::new("🌓 Shadow Map")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if renderer.lighting().shadow_caster().is_none() {
+                ui.label("No shadows: enable them and add a directional light");
+                return;
+            }
+            if let Some(error) = renderer.shadow_error() {
+                ui.label(format!("Shadows unavailable: {}", error));
+                return;
+            }
+            if !renderer.has_shadow_map() {
+                ui.label("Waiting for the first frame…");
+                return;
+            }
+
+            ui.label(format!("{0}×{0}, near = black, far = white", settings.resolution));
+            ui.horizontal_wrapped(|ui| {
+                for layer in 0..settings.cascade_count() {
+                    ui.vertical(|ui| {
+                        if settings.cascaded {
+                            ui.label(format!("Cascade {}", layer + 1));
+                        }
+                        let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(THUMBNAIL_SIZE), egui::Sense::hover());
+                        let renderer = renderer.clone();  // Cheap - shared state is behind Arcs
+                        ui.painter().add(egui::PaintCallback {
+                            rect,
+                            callback: Arc::new(egui_glow::CallbackFn::new(move |info, painter| {
+                                renderer.paint_shadow_map(painter.gl(), &info, layer);
+                            })),
+                        });
+                    });
+                }
+            });
+        });
+
+    if !open {
+        app_state.show_shadow_map = false;
+    }
+}